@@ -0,0 +1,354 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use tokio::sync::RwLock;
+use weave_core::amino::to_arbitrary_message_bytes;
+
+use crate::error::ClientError;
+use crate::signer::WeaveSigner;
+use crate::types::{
+    AccountBundle, ApiError, ApplyProofRequestDto, ApplyProofResponseDto, AttestationDto, AuthWalletRequestDto,
+    AuthWalletResponseDto, PrepareAuthDataResponseDto, PrepareAuthRequestDto, PrepareToApplyProofRequestDto,
+    PrepareToApplyProofResponseDto, PrepareToRevokeProofRequestDto, PrepareToRevokeProofResponseDto,
+    RefreshTokensResponseDto, RevokeProofRequestDto, RevokeProofResponseDto, UserDataResponseDto,
+    ErrorCode, JobResponseDto, UserQueryDto,
+};
+
+struct Tokens {
+    access_token: String,
+    refresh_token: String,
+}
+
+/// Signature of a sign doc in the form the API expects
+struct SignedData {
+    public_key: String,
+    signature: String,
+}
+
+pub struct WeaveClient<S: WeaveSigner> {
+    http: reqwest::Client,
+    base_url: String,
+    signer: S,
+    tokens: RwLock<Option<Tokens>>,
+}
+
+impl<S: WeaveSigner> WeaveClient<S> {
+    pub fn new(base_url: &str, signer: S) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            signer,
+            tokens: RwLock::new(None),
+        }
+    }
+
+    /// Creates the Prism account on first login and stores the issued tokens
+    pub async fn login(&self) -> Result<AuthWalletResponseDto, ClientError> {
+        let signer = self.signer.address();
+        let public_key = STANDARD.encode(self.signer.public_key());
+
+        let prepared: PrepareAuthDataResponseDto = self.parse(
+            self.http.post(self.url("/api/auth/prepare"))
+                .json(&PrepareAuthRequestDto { public_key, signer: signer.clone() })
+                .send()
+                .await?
+        ).await?;
+        let signed = self.sign(&prepared.data).await?;
+
+        let response: AuthWalletResponseDto = self.parse(
+            self.http.post(self.url("/api/auth"))
+                .json(&AuthWalletRequestDto {
+                    public_key: signed.public_key,
+                    signature: signed.signature,
+                    signer,
+                    data: prepared.data,
+                })
+                .send()
+                .await?
+        ).await?;
+
+        *self.tokens.write().await = Some(Tokens {
+            access_token: response.access_token.clone(),
+            refresh_token: response.refresh_token.clone(),
+        });
+        Ok(response)
+    }
+
+    /// Exchanges the refresh token for a new token pair
+    pub async fn refresh(&self) -> Result<(), ClientError> {
+        let refresh_token = match self.tokens.read().await.as_ref() {
+            Some(tokens) => tokens.refresh_token.clone(),
+            None => return Err(ClientError::NotLoggedIn),
+        };
+
+        let response = self.http.post(self.url("/api/auth/refresh"))
+            .bearer_auth(refresh_token)
+            .send()
+            .await?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            *self.tokens.write().await = None;
+            return Err(ClientError::SessionExpired(api_error(response).await.message));
+        }
+        let refreshed: RefreshTokensResponseDto = self.parse(response).await?;
+
+        *self.tokens.write().await = Some(Tokens {
+            access_token: refreshed.access_token,
+            refresh_token: refreshed.refresh_token,
+        });
+        Ok(())
+    }
+
+    pub async fn me(&self, query: &UserQueryDto) -> Result<UserDataResponseDto, ClientError> {
+        self.send_authorized(|token| {
+            self.http.get(self.url("/api/me")).query(query).bearer_auth(token)
+        }).await
+    }
+
+    pub async fn user(&self, user_id: &str, query: &UserQueryDto) -> Result<UserDataResponseDto, ClientError> {
+        self.send_authorized(|token| {
+            self.http.get(self.url(&format!("/api/user/{}", user_id))).query(query).bearer_auth(token)
+        }).await
+    }
+
+    /// State of a job returned by a `Prefer: respond-async` submission
+    pub async fn job(&self, job_id: &str) -> Result<JobResponseDto, ClientError> {
+        self.send_authorized(|token| {
+            self.http.get(self.url(&format!("/api/jobs/{}", job_id))).bearer_auth(token)
+        }).await
+    }
+
+    /// Account bundle which can be checked offline with `AccountBundle::verify`
+    pub async fn user_proof(&self, user_id: &str) -> Result<AccountBundle, ClientError> {
+        self.parse(
+            self.http.get(self.url(&format!("/api/user/{}/proof", user_id))).send().await?
+        ).await
+    }
+
+    /// Validates the attestation, signs the resulting identity record and links it to the account
+    pub async fn apply_proof(&self, provider_id: &str, proof: AttestationDto) -> Result<ApplyProofResponseDto, ClientError> {
+        let signer = self.signer.address();
+        let prepared: PrepareToApplyProofResponseDto = self.send_authorized(|token| {
+            self.http.post(self.url("/api/proof/prepare"))
+                .json(&PrepareToApplyProofRequestDto {
+                    proof: proof.clone(),
+                    provider_id: provider_id.to_string(),
+                    signer: signer.clone(),
+                })
+                .bearer_auth(token)
+        }).await?;
+        let signed = self.sign(&prepared.data).await?;
+
+        let request = ApplyProofRequestDto {
+            public_key: signed.public_key,
+            signature: signed.signature,
+            signer,
+            data: prepared.data,
+            proof,
+            provider_id: provider_id.to_string(),
+        };
+        self.send_authorized(|token| {
+            self.http.post(self.url("/api/proof")).json(&request).bearer_auth(token)
+        }).await
+    }
+
+    /// Revokes the identities of a provider, or a single proof when `proof_identifier` is set
+    pub async fn revoke_proof(
+        &self,
+        provider_id: &str,
+        proof_identifier: Option<String>,
+    ) -> Result<RevokeProofResponseDto, ClientError> {
+        let signer = self.signer.address();
+        let prepared: PrepareToRevokeProofResponseDto = self.send_authorized(|token| {
+            self.http.post(self.url(&format!("/api/proof/{}/revoke/prepare", provider_id)))
+                .json(&PrepareToRevokeProofRequestDto {
+                    signer: signer.clone(),
+                    proof_identifier: proof_identifier.clone(),
+                })
+                .bearer_auth(token)
+        }).await?;
+        let signed = self.sign(&prepared.data).await?;
+
+        let request = RevokeProofRequestDto {
+            public_key: signed.public_key,
+            signature: signed.signature,
+            signer,
+            data: prepared.data,
+        };
+        self.send_authorized(|token| {
+            self.http.delete(self.url(&format!("/api/proof/{}", provider_id))).json(&request).bearer_auth(token)
+        }).await
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Signs the amino sign doc of `data` like a Cosmos wallet does for arbitrary messages
+    async fn sign(&self, data: &[u8]) -> Result<SignedData, ClientError> {
+        let sign_doc = to_arbitrary_message_bytes(&self.signer.address(), &STANDARD.encode(data));
+        let signature = self.signer.sign(&sign_doc).await?;
+        Ok(SignedData {
+            public_key: STANDARD.encode(self.signer.public_key()),
+            signature: STANDARD.encode(signature),
+        })
+    }
+
+    /// Sends a bearer authorized request, refreshing the tokens once when the access token expired
+    async fn send_authorized<T, F>(&self, build: F) -> Result<T, ClientError>
+    where
+        T: DeserializeOwned,
+        F: Fn(&str) -> RequestBuilder,
+    {
+        let access_token = self.access_token().await?;
+        let mut response = build(&access_token).send().await?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            self.refresh().await?;
+            let access_token = self.access_token().await?;
+            response = build(&access_token).send().await?;
+        }
+        self.parse(response).await
+    }
+
+    async fn access_token(&self) -> Result<String, ClientError> {
+        self.tokens.read().await
+            .as_ref()
+            .map(|tokens| tokens.access_token.clone())
+            .ok_or(ClientError::NotLoggedIn)
+    }
+
+    async fn parse<T: DeserializeOwned>(&self, response: Response) -> Result<T, ClientError> {
+        let status = response.status();
+        if status.is_success() {
+            return response.json::<T>().await
+                .map_err(|e| ClientError::InvalidResponse(e.to_string()));
+        }
+
+        let status = status.as_u16();
+        let error = api_error(response).await;
+        Err(ClientError::Api { status, error })
+    }
+}
+
+/// Reads the `ApiError` body, falling back to the HTTP status for bodies that are not
+/// one (e.g. from a proxy)
+async fn api_error(response: Response) -> ApiError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    serde_json::from_str::<ApiError>(&body)
+        .unwrap_or_else(|_| ApiError::new(ErrorCode::Unknown, status.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::signer::LocalSigner;
+    use crate::types::UserQueryDto;
+
+    const USER: &str = r#"{"id":"cosmos1user","identity_records":[],"unrecognized_records":[]}"#;
+    const REFRESHED: &str = r#"{"success":true,"message":null,"accessToken":"new-access","refreshToken":"new-refresh"}"#;
+    const UNAUTHORIZED: &str = r#"{"code":"UNAUTHORIZED","error":"Token expired"}"#;
+
+    /// Request line and bearer token of every request the server received
+    type Requests = Arc<Mutex<Vec<(String, String)>>>;
+
+    /// Serves one connection per request and answers with `respond(path, token)`
+    async fn serve(respond: fn(&str, &str) -> (u16, &'static str)) -> (String, Requests) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let requests: Requests = Arc::default();
+        let received = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buffer = vec![0; 16 * 1024];
+                let read = stream.read(&mut buffer).await.unwrap();
+                let request = String::from_utf8_lossy(&buffer[..read]).to_string();
+                let path = request.split(' ').nth(1).unwrap_or_default().split('?').next().unwrap_or_default().to_string();
+                let token = request.lines()
+                    .find_map(|line| line.strip_prefix("authorization: Bearer "))
+                    .unwrap_or_default()
+                    .to_string();
+                let (status, body) = respond(&path, &token);
+                received.lock().unwrap().push((path, token));
+                let response = format!(
+                    "HTTP/1.1 {} Status\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status, body.len(), body,
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (format!("http://{}", address), requests)
+    }
+
+    async fn logged_in_client(base_url: &str) -> WeaveClient<LocalSigner> {
+        let client = WeaveClient::new(base_url, LocalSigner::from_bytes(&[7; 32], "cosmos").unwrap());
+        *client.tokens.write().await = Some(Tokens {
+            access_token: "old-access".to_string(),
+            refresh_token: "old-refresh".to_string(),
+        });
+        client
+    }
+
+    #[tokio::test]
+    async fn expired_access_token_is_refreshed_and_the_request_retried() {
+        let (base_url, requests) = serve(|path, token| match (path, token) {
+            ("/api/me", "new-access") => (200, USER),
+            ("/api/me", _) => (401, UNAUTHORIZED),
+            ("/api/auth/refresh", "old-refresh") => (200, REFRESHED),
+            _ => (404, "{}"),
+        }).await;
+        let client = logged_in_client(&base_url).await;
+
+        let user = client.me(&UserQueryDto::default()).await.unwrap();
+
+        assert_eq!(user.id, "cosmos1user");
+        assert_eq!(*requests.lock().unwrap(), vec![
+            ("/api/me".to_string(), "old-access".to_string()),
+            ("/api/auth/refresh".to_string(), "old-refresh".to_string()),
+            ("/api/me".to_string(), "new-access".to_string()),
+        ]);
+        assert_eq!(client.access_token().await.unwrap(), "new-access");
+    }
+
+    #[tokio::test]
+    async fn tokens_are_refreshed_only_once_per_request() {
+        let (base_url, requests) = serve(|path, _| match path {
+            "/api/auth/refresh" => (200, REFRESHED),
+            _ => (401, UNAUTHORIZED),
+        }).await;
+        let client = logged_in_client(&base_url).await;
+
+        let error = client.me(&UserQueryDto::default()).await.unwrap_err();
+
+        assert_eq!(error.status(), Some(401));
+        assert_eq!(error.code(), Some(ErrorCode::Unauthorized));
+        let paths: Vec<_> = requests.lock().unwrap().iter().map(|(path, _)| path.clone()).collect();
+        assert_eq!(paths, vec!["/api/me", "/api/auth/refresh", "/api/me"]);
+    }
+
+    #[tokio::test]
+    async fn rejected_refresh_token_ends_the_session() {
+        let (base_url, requests) = serve(|_, _| (401, UNAUTHORIZED)).await;
+        let client = logged_in_client(&base_url).await;
+
+        let error = client.me(&UserQueryDto::default()).await.unwrap_err();
+
+        assert!(matches!(error, ClientError::SessionExpired(_)));
+        assert!(matches!(client.access_token().await, Err(ClientError::NotLoggedIn)));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn requests_need_a_login() {
+        let client = WeaveClient::new("http://127.0.0.1:9", LocalSigner::from_bytes(&[7; 32], "cosmos").unwrap());
+
+        let error = client.me(&UserQueryDto::default()).await.unwrap_err();
+
+        assert!(matches!(error, ClientError::NotLoggedIn));
+    }
+}
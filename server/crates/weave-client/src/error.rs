@@ -0,0 +1,58 @@
+use thiserror::Error;
+use weave_core::errors::api_error::{ApiError, ErrorCode};
+
+/// Errors of the Weave API carry the server's `ApiError`, branch on its `code`
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("Api error ({status}): {error}")]
+    Api { status: u16, error: ApiError },
+
+    #[error("Not logged in")]
+    NotLoggedIn,
+
+    #[error("Session expired: {0}")]
+    SessionExpired(String),
+
+    #[error("Signer error: {0}")]
+    Signer(String),
+
+    #[error("Http error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+}
+
+impl ClientError {
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ClientError::Api { status, .. } => Some(*status),
+            ClientError::Http(e) => e.status().map(|status| status.as_u16()),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            ClientError::Api { error, .. } => Some(error.code),
+            _ => None,
+        }
+    }
+
+    /// Request id to quote when reporting the error
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            ClientError::Api { error, .. } => error.request_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+
+    /// The proof or the linked identity exists already
+    pub fn is_conflict(&self) -> bool {
+        matches!(self.code(), Some(ErrorCode::IdentityAlreadyLinked | ErrorCode::DuplicateProof))
+    }
+}
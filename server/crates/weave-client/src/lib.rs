@@ -0,0 +1,13 @@
+//! Typed HTTP client for the Weave API.
+//!
+//! Wraps the prepare → sign → submit flows of `/api/auth` and `/api/proof` behind a
+//! pluggable [`WeaveSigner`] and refreshes the access token when it expires.
+
+pub mod client;
+pub mod error;
+pub mod signer;
+pub mod types;
+
+pub use client::WeaveClient;
+pub use error::ClientError;
+pub use signer::{LocalSigner, WeaveSigner};
@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use bech32::{ToBase32, Variant};
+use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+use k256::sha2::{Digest, Sha256};
+use ripemd::Ripemd160;
+
+use crate::error::ClientError;
+
+/// Signs Weave sign docs with a secp256k1 key, e.g. a local key, a KMS or a wallet bridge
+#[async_trait]
+pub trait WeaveSigner: Send + Sync {
+    /// Bech32 address of the key, used as the Weave account id
+    fn address(&self) -> String;
+
+    /// Compressed secp256k1 public key
+    fn public_key(&self) -> Vec<u8>;
+
+    /// Compact (64 bytes) ECDSA signature over the sha256 of the sign doc
+    async fn sign(&self, sign_doc: &[u8]) -> Result<Vec<u8>, ClientError>;
+}
+
+/// Signer holding the secp256k1 key in memory
+pub struct LocalSigner {
+    key: SigningKey,
+    address: String,
+}
+
+impl LocalSigner {
+    /// `prefix` is the bech32 prefix of the chain, e.g. `cosmos`
+    pub fn new(key: SigningKey, prefix: &str) -> Result<Self, ClientError> {
+        let public_key = key.verifying_key().to_encoded_point(true);
+        let address_bytes = Ripemd160::digest(Sha256::digest(public_key.as_bytes()));
+        let address = bech32::encode(prefix, address_bytes.to_base32(), Variant::Bech32)
+            .map_err(|e| ClientError::Signer(e.to_string()))?;
+        Ok(Self { key, address })
+    }
+
+    pub fn from_bytes(secret_key: &[u8], prefix: &str) -> Result<Self, ClientError> {
+        let key = SigningKey::from_slice(secret_key)
+            .map_err(|e| ClientError::Signer(e.to_string()))?;
+        Self::new(key, prefix)
+    }
+}
+
+#[async_trait]
+impl WeaveSigner for LocalSigner {
+    fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    fn public_key(&self) -> Vec<u8> {
+        self.key.verifying_key().to_encoded_point(true).as_bytes().to_vec()
+    }
+
+    async fn sign(&self, sign_doc: &[u8]) -> Result<Vec<u8>, ClientError> {
+        let signature: Signature = self.key.sign(sign_doc);
+        Ok(signature.to_bytes().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use k256::ecdsa::{signature::Verifier, VerifyingKey};
+    use weave_core::amino::to_arbitrary_message_bytes;
+    use weave_core::signature::key_matches_address;
+
+    use super::*;
+
+    fn signer() -> LocalSigner {
+        LocalSigner::from_bytes(&[7; 32], "cosmos").unwrap()
+    }
+
+    #[test]
+    fn address_is_derived_from_the_public_key() {
+        let signer = signer();
+        let key = VerifyingKey::from_sec1_bytes(&signer.public_key()).unwrap();
+
+        assert!(signer.address().starts_with("cosmos1"));
+        assert!(key_matches_address(&key, &signer.address()));
+    }
+
+    #[tokio::test]
+    async fn signature_verifies_over_the_server_sign_doc() {
+        let signer = signer();
+        let data = br#"{"version":2,"provider_id":"x"}"#;
+        // the server rebuilds the sign doc from the base64 of the submitted data
+        let server_sign_doc = to_arbitrary_message_bytes(&signer.address(), &STANDARD.encode(data));
+
+        let signature = signer.sign(&server_sign_doc).await.unwrap();
+        let key = VerifyingKey::from_sec1_bytes(&signer.public_key()).unwrap();
+        let signature = Signature::from_slice(&signature).unwrap();
+        assert!(key.verify(&server_sign_doc, &signature).is_ok());
+    }
+}
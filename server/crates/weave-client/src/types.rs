@@ -0,0 +1,25 @@
+//! Request and response bodies of the Weave API, shared with the server through `weave_core`
+
+pub use weave_core::dto::request::{
+    auth_req::{AuthWalletRequestDto, PrepareAuthRequestDto},
+    proof_req::{
+        ApplyProofRequestDto, AttestationDto, PrepareToApplyProofRequestDto, PrepareToRevokeProofRequestDto,
+        RevokeProofRequestDto,
+    },
+    user_req::UserQueryDto,
+};
+pub use weave_core::dto::response::{
+    auth_res::{AuthWalletResponseDto, PrepareAuthDataResponseDto, RefreshTokensResponseDto},
+    job_res::JobResponseDto,
+    proof_res::{
+        ApplyProofResponseDto, PrepareToApplyProofResponseDto, PrepareToRevokeProofResponseDto,
+        RevokeProofResponseDto,
+    },
+    user_res::UserDataResponseDto,
+};
+pub use weave_core::errors::api_error::{ApiError, ErrorCode};
+pub use weave_core::models::job::{JobKind, JobStatus};
+pub use weave_core::models::receipt::TransactionReceipt;
+pub use weave_core::models::webhook::{WebhookEventType, WebhookPayload};
+pub use weave_core::webhook_signature;
+pub use weave_verifier::AccountBundle;
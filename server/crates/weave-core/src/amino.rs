@@ -1,5 +1,6 @@
 use serde::{de::DeserializeOwned, Deserialize};
 use anyhow::anyhow;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 
 #[derive(Deserialize)]
 struct ArbitraryMessage {
@@ -29,11 +30,14 @@ pub fn to_arbitrary_message_bytes(signer: &str, data: &str) -> Vec<u8> {
 
 pub fn from_arbitrary_message_bytes_to_data_structure<D: DeserializeOwned>(data_bytes: &[u8]) -> anyhow::Result<D> {
     let data = match serde_json::from_slice::<ArbitraryMessage>(data_bytes) {
-        Ok(amino_message) => amino_message.msgs[0].value.data.clone(),
+        Ok(amino_message) => match amino_message.msgs.into_iter().next() {
+            Some(msg) => msg.value.data,
+            None => return Err(anyhow!("Arbitrary message has no msgs")),
+        },
         Err(e) => return Err(anyhow!("Failed to parse arbitrary message: {}", e)),
     };
 
-    let decoded = base64::decode(data)?;
+    let decoded = STANDARD.decode(data)?;
     let json_str = String::from_utf8(decoded)?;
     Ok(serde_json::from_str::<D>(&json_str)?)
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct AuthWalletRequestDto {
     pub public_key: String,
     pub signature: String,
@@ -9,6 +10,7 @@ pub struct AuthWalletRequestDto {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PrepareAuthRequestDto {
     pub public_key: String,
     pub signer: String,
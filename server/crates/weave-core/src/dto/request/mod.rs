@@ -0,0 +1,7 @@
+pub mod auth_req;
+pub mod proof_req;
+pub mod lookup_req;
+pub mod user_req;
+pub mod webhook_req;
+pub mod operation_req;
+pub mod reindex_req;
\ No newline at end of file
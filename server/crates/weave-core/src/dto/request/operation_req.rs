@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use crate::models::operation::OperationStatus;
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct OperationsRequestDto {
+    /// Only operations in this status, incomplete ones (pending, submitted, failed) by default
+    pub status: Option<OperationStatus>,
+    /// Most recent operations first, 50 by default
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::attestation::{Attestation, ReclaimProof};
+
+/// Accepts a tagged attestation of any kind or a bare Reclaim proof,
+/// as sent by clients before other attestation kinds were supported
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum AttestationDto {
+    Attestation(Attestation),
+    Reclaim(ReclaimProof),
+}
+
+impl From<AttestationDto> for Attestation {
+    fn from(dto: AttestationDto) -> Self {
+        match dto {
+            AttestationDto::Attestation(attestation) => attestation,
+            AttestationDto::Reclaim(proof) => Attestation::Reclaim(proof),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PrepareToRevokeProofRequestDto {
+    pub signer: String,
+    pub proof_identifier: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RevokeProofRequestDto {
+    pub public_key: String,
+    pub signature: String,
+    pub signer: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PrepareToApplyProofRequestDto {
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub proof: AttestationDto,
+    pub provider_id: String,
+    pub signer: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApplyProofRequestDto {
+    pub public_key: String,
+    pub signature: String,
+    pub signer: String,
+    pub data: Vec<u8>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub proof: AttestationDto,
+    pub provider_id: String,
+}
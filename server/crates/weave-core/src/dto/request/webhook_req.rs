@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use crate::models::webhook::WebhookEventType;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateWebhookRequestDto {
+    /// http or https URL receiving the deliveries
+    pub url: String,
+    /// Subscribed events, all of them when empty
+    #[serde(default)]
+    pub events: Vec<WebhookEventType>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct WebhookDeliveriesRequestDto {
+    /// Most recent deliveries first, 50 by default
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use crate::dto::response::job_res::JobResponseDto;
+use crate::models::receipt::TransactionReceipt;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AuthWalletResponseDto {
+    pub success: bool,
+    pub message: Option<String>,
+    pub access_token: String,
+    pub refresh_token: String,
+    pub receipt: Option<TransactionReceipt>,
+    /// Job creating the account when the request was answered asynchronously
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job: Option<JobResponseDto>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PrepareAuthDataResponseDto {
+    pub data: Vec<u8>,
+    pub signer: String,
+}
+
+/// Fields are camelCase, unlike the other responses
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RefreshTokensResponseDto {
+    pub success: bool,
+    pub message: Option<String>,
+    pub access_token: String,
+    pub refresh_token: String,
+}
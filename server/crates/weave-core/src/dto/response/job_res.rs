@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::errors::api_error::ApiError;
+use crate::models::job::{JobKind, JobStatus};
+
+/// State of an asynchronous job, returned with `202 Accepted` and by `GET /api/jobs/:id`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct JobResponseDto {
+    pub id: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub user_id: String,
+    /// Set once the transaction was posted to Prism
+    pub transaction_hash: Option<String>,
+    /// Response body the synchronous request would have returned, once included
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub result: Option<Value>,
+    pub error: Option<ApiError>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
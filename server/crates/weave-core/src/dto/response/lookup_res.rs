@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use crate::models::user::UserIdentityRecord;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LookupMatchDto {
+    pub address: String,
+    pub provider_id: String,
+    pub handle: String,
+    pub proof_timestamp: i64,
+    pub flagged: bool,
+    pub record: Option<UserIdentityRecord>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LookupResponseDto {
+    pub platform: String,
+    pub handle: String,
+    pub matches: Vec<LookupMatchDto>,
+}
@@ -0,0 +1,9 @@
+pub mod auth_res;
+pub mod proof_res;
+pub mod user_res;
+pub mod provider_res;
+pub mod lookup_res;
+pub mod job_res;
+pub mod webhook_res;
+pub mod operation_res;
+pub mod reindex_res;
\ No newline at end of file
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use crate::models::operation::{OperationKind, OperationStatus};
+
+/// State of an outbox operation, returned by the admin routes
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct OperationResponseDto {
+    pub id: String,
+    pub kind: OperationKind,
+    pub status: OperationStatus,
+    pub user_id: String,
+    /// Set once the transaction was posted to Prism
+    pub transaction_hash: Option<String>,
+    /// Account nonce the transaction was signed for
+    pub transaction_nonce: Option<u64>,
+    /// Attempts of the worker to resolve the operation
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    /// When the worker looks at the operation next, unset once finished
+    pub next_attempt_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct OperationsResponseDto {
+    pub operations: Vec<OperationResponseDto>,
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use crate::models::receipt::TransactionReceipt;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PrepareToApplyProofResponseDto {
+    pub data: Vec<u8>,
+    pub signer: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApplyProofResponseDto {
+    pub success: bool,
+    pub receipt: Option<TransactionReceipt>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PrepareToRevokeProofResponseDto {
+    pub data: Vec<u8>,
+    pub signer: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RevokeProofResponseDto {
+    pub success: bool,
+    pub receipt: Option<TransactionReceipt>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AppliedProofStatsResponseDto {
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<Vec<Object>>))]
+    pub stats: Vec<(String, i64)>,
+}
\ No newline at end of file
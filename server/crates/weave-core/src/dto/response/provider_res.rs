@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProviderResponseDto {
+    pub id: String,
+    pub name: String,
+    pub platform: String,
+    pub category: String,
+    pub icon_url: Option<String>,
+    pub enabled: bool,
+    pub max_proof_age: Option<i64>,
+    /// Applied proofs which are not revoked
+    pub proofs_count: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProvidersResponseDto {
+    pub providers: Vec<ProviderResponseDto>,
+}
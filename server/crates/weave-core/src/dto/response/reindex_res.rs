@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use crate::models::reindex::ReindexDifferenceKind;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReindexDifferenceDto {
+    pub kind: ReindexDifferenceKind,
+    pub user_id: String,
+    pub provider_id: Option<String>,
+    /// Raw data hash of the affected `proofs` row
+    pub raw_data_hash: Option<String>,
+    pub detail: Option<String>,
+}
+
+/// Outcome of rebuilding `users` and `proofs` from the Prism accounts
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReindexResponseDto {
+    /// When set nothing was changed, the differences are only reported
+    pub dry_run: bool,
+    /// Account ids looked up on Prism, those created for the service on the DA layer and
+    /// those the database references
+    pub accounts_checked: usize,
+    /// Accounts created for the service on the DA layer which the database did not reference
+    pub accounts_unknown_to_database: usize,
+    /// Accounts found on Prism
+    pub accounts_found: usize,
+    pub differences: Vec<ReindexDifferenceDto>,
+    /// Differences which could not be fixed, with the error in their `detail`
+    pub errors: usize,
+}
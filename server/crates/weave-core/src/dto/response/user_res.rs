@@ -0,0 +1,61 @@
+#[cfg(feature = "prism")]
+use prism_client::Account;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "prism")]
+use crate::{models::account_proof::AccountProof, signature::SignedRecord};
+use crate::models::{
+    account_state::RecordStatus,
+    user::{AccountRecord, RecordSigner, UnrecognizedRecord, VerifiedIdentityRecord},
+};
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UserDataResponseDto {
+    pub id: String,
+    pub identity_records: Vec<VerifiedIdentityRecord>,
+    pub unrecognized_records: Vec<UnrecognizedRecord>,
+}
+
+impl UserDataResponseDto {
+    pub fn new(
+        id: String,
+        identity_records: Vec<VerifiedIdentityRecord>,
+        unrecognized_records: Vec<UnrecognizedRecord>,
+    ) -> Self {
+        Self { id, identity_records, unrecognized_records }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UserHistoryEntryDto {
+    pub index: usize,
+    pub record_type: String,
+    pub prism_height: Option<u64>,
+    pub status: Option<RecordStatus>,
+    pub signer_key: String,
+    pub signer: RecordSigner,
+    pub signature_valid: bool,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub record: Option<AccountRecord>,
+    pub unrecognized: Option<UnrecognizedRecord>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UserHistoryResponseDto {
+    pub id: String,
+    pub entries: Vec<UserHistoryEntryDto>,
+}
+
+#[cfg(feature = "prism")]
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UserProofResponseDto {
+    pub id: String,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub account: Option<Account>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Vec<Object>))]
+    pub records: Vec<SignedRecord>,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub proof: AccountProof,
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use crate::models::webhook::{DeliveryStatus, WebhookEventType, WebhookPayload};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookResponseDto {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<WebhookEventType>,
+    pub created_at: i64,
+    /// HMAC key of the signature header, only returned when the webhook is created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhooksResponseDto {
+    pub webhooks: Vec<WebhookResponseDto>,
+}
+
+/// Entry of the delivery log of a webhook
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookDeliveryDto {
+    pub id: String,
+    pub webhook_id: String,
+    pub payload: WebhookPayload,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    /// When the next retry is due, unset once delivered or failed
+    pub next_attempt_at: Option<i64>,
+    /// HTTP status of the last attempt, unset when the receiver could not be reached
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookDeliveriesResponseDto {
+    pub deliveries: Vec<WebhookDeliveryDto>,
+}
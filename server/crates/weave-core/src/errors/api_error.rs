@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+/// Machine-readable error codes, stable across releases so clients can branch on them
+/// instead of on messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ErrorCode {
+    InvalidRequest,
+    InvalidPublicKey,
+    InvalidSignature,
+    Unauthorized,
+    InvalidToken,
+    SessionExpired,
+    SignerMismatch,
+    AccountNotFound,
+    UnrecognizedRecords,
+    ProofNotVerified,
+    UnsupportedAttestation,
+    UntrustedAttester,
+    UnknownProvider,
+    ProviderDisabled,
+    ProofTooOld,
+    IdentityAlreadyLinked,
+    DuplicateProof,
+    IdempotencyKeyReused,
+    RequestInProgress,
+    PayloadTooLarge,
+    InvalidRevocation,
+    NotFound,
+    TransactionFailed,
+    TransactionTimeout,
+    DatabaseError,
+    KeyStoreError,
+    InternalError,
+    /// Code added by a newer server
+    #[serde(other)]
+    Unknown,
+}
+
+impl ErrorCode {
+    pub fn http_status(self) -> u16 {
+        match self {
+            ErrorCode::InvalidRequest
+            | ErrorCode::InvalidPublicKey
+            | ErrorCode::InvalidSignature
+            | ErrorCode::ProofNotVerified
+            | ErrorCode::UnsupportedAttestation
+            | ErrorCode::UntrustedAttester
+            | ErrorCode::ProofTooOld
+            | ErrorCode::InvalidRevocation => 400,
+            ErrorCode::Unauthorized
+            | ErrorCode::InvalidToken
+            | ErrorCode::SessionExpired => 401,
+            ErrorCode::SignerMismatch
+            | ErrorCode::ProviderDisabled => 403,
+            ErrorCode::AccountNotFound
+            | ErrorCode::UnknownProvider
+            | ErrorCode::NotFound => 404,
+            ErrorCode::IdentityAlreadyLinked
+            | ErrorCode::DuplicateProof
+            | ErrorCode::RequestInProgress => 409,
+            ErrorCode::UnrecognizedRecords
+            | ErrorCode::IdempotencyKeyReused => 422,
+            ErrorCode::PayloadTooLarge => 413,
+            ErrorCode::TransactionFailed
+            | ErrorCode::DatabaseError
+            | ErrorCode::KeyStoreError
+            | ErrorCode::InternalError
+            | ErrorCode::Unknown => 500,
+            ErrorCode::TransactionTimeout => 504,
+        }
+    }
+}
+
+/// Body of every error response of the API.
+/// `error` keeps the human readable message under the key older clients read.
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+#[error("{message}")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiError {
+    pub code: ErrorCode,
+    #[serde(rename = "error")]
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<Object>))]
+    pub details: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+            request_id: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    pub fn status(&self) -> u16 {
+        self.code.http_status()
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for ApiError {
+    fn into_response(self) -> axum::response::Response {
+        let status = axum::http::StatusCode::from_u16(self.status())
+            .unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (
+            status,
+            [(axum::http::header::CONTENT_TYPE, "application/json")],
+            axum::Json(&self),
+        ).into_response();
+        // Kept on the response so the request id middleware can stamp and log it
+        response.extensions_mut().insert(self);
+        response
+    }
+}
@@ -0,0 +1,4 @@
+pub mod api_error;
+pub mod proof_errors;
+pub mod record_errors;
+pub mod signature_errors;
@@ -0,0 +1,107 @@
+use serde_json::json;
+use thiserror::Error;
+use super::api_error::{ApiError, ErrorCode};
+
+#[derive(Debug, Error)]
+pub enum ProofError {
+    #[error("Proof verification failed: {0}")]
+    ProofNotVerifiedError(String),
+
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+
+    #[error("Unsupported attestation kind: {0}")]
+    UnsupportedAttestation(String),
+
+    #[error("Attestation signed by untrusted key: {0}")]
+    UntrustedSigner(String),
+
+    #[error("Unknown provider: {0}")]
+    UnknownProvider(String),
+
+    #[error("Attestation was not issued for provider {0}")]
+    ProviderMismatch(String),
+
+    #[error("Provider is disabled: {0}")]
+    ProviderDisabled(String),
+
+    #[error("Proof is older than {0} seconds")]
+    ProofTooOld(i64),
+
+    #[error("Social account is already linked to wallet {0}")]
+    IdentityAlreadyLinked(String),
+
+    #[error("Proof has already been applied: {0}")]
+    DuplicateProof(String),
+
+    #[error("Invalid revocation: {0}")]
+    InvalidRevocation(String),
+
+    #[error("Signer {0} does not match the authenticated user")]
+    SignerMismatch(String),
+
+    #[error("Signed record does not match the attestation: {0}")]
+    RecordMismatch(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("Transaction error: {0}")]
+    TransactionError(String),
+}
+
+impl ProofError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ProofError::ProofNotVerifiedError(_) => ErrorCode::ProofNotVerified,
+            ProofError::SerializationError(_) => ErrorCode::InvalidRequest,
+            ProofError::UnsupportedAttestation(_) => ErrorCode::UnsupportedAttestation,
+            ProofError::UntrustedSigner(_) => ErrorCode::UntrustedAttester,
+            ProofError::UnknownProvider(_) => ErrorCode::UnknownProvider,
+            ProofError::ProviderMismatch(_) => ErrorCode::InvalidRequest,
+            ProofError::ProviderDisabled(_) => ErrorCode::ProviderDisabled,
+            ProofError::ProofTooOld(_) => ErrorCode::ProofTooOld,
+            ProofError::IdentityAlreadyLinked(_) => ErrorCode::IdentityAlreadyLinked,
+            ProofError::DuplicateProof(_) => ErrorCode::DuplicateProof,
+            ProofError::InvalidRevocation(_) => ErrorCode::InvalidRevocation,
+            ProofError::SignerMismatch(_) => ErrorCode::SignerMismatch,
+            ProofError::RecordMismatch(_) => ErrorCode::InvalidRequest,
+            ProofError::DatabaseError(_) => ErrorCode::DatabaseError,
+            ProofError::TransactionError(_) => ErrorCode::TransactionFailed,
+        }
+    }
+}
+
+impl From<ProofError> for ApiError {
+    fn from(error: ProofError) -> Self {
+        let details = match &error {
+            ProofError::IdentityAlreadyLinked(user_id) => Some(json!({ "linked_user_id": user_id })),
+            ProofError::ProofTooOld(max_age) => Some(json!({ "max_age_seconds": max_age })),
+            _ => None,
+        };
+        let api_error = ApiError::new(error.code(), error.to_string());
+        match details {
+            Some(details) => api_error.with_details(details),
+            None => api_error,
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for ProofError {
+    fn into_response(self) -> axum::response::Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+impl From<anyhow::Error> for ProofError {
+    fn from(error: anyhow::Error) -> Self {
+        ProofError::DatabaseError(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for ProofError {
+    fn from(error: serde_json::Error) -> Self {
+        ProofError::SerializationError(error.to_string())
+    }
+}
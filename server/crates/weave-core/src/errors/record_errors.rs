@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RecordError {
+    #[error("Unsupported identity record version: {0}")]
+    UnsupportedVersion(u64),
+
+    #[error("Unknown record type: {0}")]
+    UnknownRecordType(String),
+
+    #[error("Invalid record: {0}")]
+    InvalidRecord(String),
+}
+
+impl From<serde_json::Error> for RecordError {
+    fn from(error: serde_json::Error) -> Self {
+        RecordError::InvalidRecord(error.to_string())
+    }
+}
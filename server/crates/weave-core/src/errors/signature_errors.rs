@@ -0,0 +1,46 @@
+use thiserror::Error;
+use super::api_error::{ApiError, ErrorCode};
+
+/// Malformed keys, signatures and data of a signed record
+#[derive(Debug, Error)]
+pub enum SignatureError {
+    #[error("Public key is not valid base64: {0}")]
+    PublicKeyEncoding(String),
+
+    #[error("Public key is not a secp256k1 key")]
+    InvalidPublicKey,
+
+    #[error("Signature is not valid base64: {0}")]
+    SignatureEncoding(String),
+
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+
+    #[error("Signed data is not valid base64: {0}")]
+    DataEncoding(String),
+}
+
+impl SignatureError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SignatureError::PublicKeyEncoding(_) |
+            SignatureError::InvalidPublicKey => ErrorCode::InvalidPublicKey,
+            SignatureError::SignatureEncoding(_) |
+            SignatureError::InvalidSignature(_) => ErrorCode::InvalidSignature,
+            SignatureError::DataEncoding(_) => ErrorCode::InvalidRequest,
+        }
+    }
+}
+
+impl From<SignatureError> for ApiError {
+    fn from(error: SignatureError) -> Self {
+        ApiError::new(error.code(), error.to_string())
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for SignatureError {
+    fn into_response(self) -> axum::response::Response {
+        ApiError::from(self).into_response()
+    }
+}
@@ -0,0 +1,45 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// Platforms matched case sensitively. They have no handles, their accounts are keyed by
+/// user id.
+const CASE_SENSITIVE_PLATFORMS: [&str; 2] = ["binance", "coinbase"];
+
+/// Normalizes a social handle so that lookups match regardless of how it was typed:
+/// NFKC Unicode normalization, surrounding whitespace and leading `@` removed,
+/// and lowercased unless the platform treats handles case sensitively.
+pub fn normalize_handle(platform: &str, handle: &str) -> String {
+    let normalized: String = handle.nfkc().collect();
+    let normalized = normalized.trim().trim_start_matches('@');
+
+    if CASE_SENSITIVE_PLATFORMS.contains(&platform) {
+        normalized.to_string()
+    } else {
+        normalized.to_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handles_are_trimmed_and_lowercased() {
+        assert_eq!(normalize_handle("github", "Alice"), "alice");
+        assert_eq!(normalize_handle("github", "  @Alice "), "alice");
+        assert_eq!(normalize_handle("twitter", "@@alice"), "alice");
+    }
+
+    #[test]
+    fn compatibility_characters_are_folded() {
+        // fullwidth letters and the fullwidth commercial at
+        assert_eq!(normalize_handle("github", "\u{FF20}\u{FF21}lice"), "alice");
+        // precomposed and combining forms of é match
+        assert_eq!(normalize_handle("github", "Jos\u{E9}"), normalize_handle("github", "Jose\u{301}"));
+    }
+
+    #[test]
+    fn case_sensitive_platforms_keep_the_case() {
+        assert_eq!(normalize_handle("binance", " @Alice"), "Alice");
+        assert_eq!(normalize_handle("coinbase", "\u{FF21}lice"), "Alice");
+    }
+}
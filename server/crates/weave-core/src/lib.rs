@@ -0,0 +1,18 @@
+//! Types shared by the Weave server, SDKs and tools: domain models, the amino
+//! encoding of signed records, the record codec, signature checks of account
+//! records, errors, the API DTOs and the signature of webhook deliveries.
+//!
+//! Features:
+//! - `prism` (default): conversions of wallet signed records into Prism types
+//! - `reclaim` (default): reading Reclaim proofs, without it they are kept as raw JSON
+//! - `axum`: `IntoResponse` for `ApiError` and the domain errors
+//! - `openapi`: OpenAPI schemas of the DTOs
+
+pub mod amino;
+pub mod dto;
+pub mod errors;
+pub mod handle;
+pub mod hash;
+pub mod models;
+pub mod signature;
+pub mod webhook_signature;
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Account together with its JMT proof against a committed Prism root.
+/// Hashes are hex encoded, `account_bytes` is the base64 of the account exactly as
+/// it is stored in the tree, so the proof can be checked without trusting the API,
+/// e.g. with `weave_verifier`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountProof {
+    pub account_id: String,
+    pub account_bytes: Option<String>,
+    /// Key hash of the leaf at the end of the account's path, `None` when the path ends
+    /// in an empty subtree. For a missing account this is the leaf of another account.
+    pub leaf_key: Option<String>,
+    /// Value hash of that leaf
+    pub leaf_value_hash: Option<String>,
+    /// Sibling hashes ordered from the root down to the leaf
+    pub siblings: Vec<String>,
+    pub root: String,
+}
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::user::{AccountRecord, UserIdentityRecord};
+use crate::handle::normalize_handle;
+
+/// State of an identity record after folding the whole account log
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum RecordStatus {
+    /// Latest non-revoked record of its provider and handle
+    Current,
+    /// A newer record of the same provider and handle exists
+    Replaced,
+    Revoked,
+    /// The identity was transferred to another wallet
+    Superseded,
+}
+
+/// Decoded records of an account in append order
+pub struct AccountState {
+    records: Vec<AccountRecord>,
+}
+
+impl AccountState {
+    pub fn new(records: Vec<AccountRecord>) -> Self {
+        Self { records }
+    }
+
+    pub fn records(&self) -> &[AccountRecord] {
+        &self.records
+    }
+
+    /// Status of every record by position, `None` for records which are not identities.
+    /// Records are grouped by provider and normalized handle (proof identifier when there
+    /// is no handle), and the one with the latest `created_at` wins, append order breaking ties.
+    pub fn statuses(&self) -> Vec<Option<RecordStatus>> {
+        let mut statuses: Vec<Option<RecordStatus>> = vec![None; self.records.len()];
+        let mut winners: HashMap<(String, String), usize> = HashMap::new();
+
+        for (index, record) in self.records.iter().enumerate() {
+            let identity = match record {
+                AccountRecord::Identity(identity) => identity,
+                _ => continue,
+            };
+
+            if self.is_revoked(identity) {
+                statuses[index] = Some(RecordStatus::Revoked);
+                continue;
+            }
+            if self.is_superseded(identity) {
+                statuses[index] = Some(RecordStatus::Superseded);
+                continue;
+            }
+
+            statuses[index] = Some(RecordStatus::Replaced);
+            let key = group_key(identity);
+            let replaces_winner = match winners.get(&key) {
+                Some(&winner) => match &self.records[winner] {
+                    AccountRecord::Identity(current) => identity.created_at() >= current.created_at(),
+                    _ => true,
+                },
+                None => true,
+            };
+            if replaces_winner {
+                winners.insert(key, index);
+            }
+        }
+
+        for index in winners.into_values() {
+            statuses[index] = Some(RecordStatus::Current);
+        }
+        statuses
+    }
+
+    /// Current identities ordered by provider and handle
+    pub fn current_identities(&self) -> Vec<UserIdentityRecord> {
+        self.identities_at(&self.current_positions())
+    }
+
+    /// Every identity record in append order, revoked and replaced ones included
+    pub fn all_identities(&self) -> Vec<UserIdentityRecord> {
+        self.identities_at(&self.identity_positions())
+    }
+
+    /// Positions of the current identities ordered by provider and handle
+    pub fn current_positions(&self) -> Vec<usize> {
+        let mut current: Vec<usize> = self.statuses().into_iter()
+            .enumerate()
+            .filter(|(_, status)| *status == Some(RecordStatus::Current))
+            .map(|(index, _)| index)
+            .collect();
+        current.sort_by_cached_key(|&index| match &self.records[index] {
+            AccountRecord::Identity(identity) => group_key(identity),
+            _ => (String::new(), String::new()),
+        });
+        current
+    }
+
+    /// Positions of every identity record in append order
+    pub fn identity_positions(&self) -> Vec<usize> {
+        self.records.iter()
+            .enumerate()
+            .filter(|(_, record)| matches!(record, AccountRecord::Identity(_)))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    fn identities_at(&self, positions: &[usize]) -> Vec<UserIdentityRecord> {
+        positions.iter()
+            .filter_map(|&index| match &self.records[index] {
+                AccountRecord::Identity(identity) => Some(identity.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn is_revoked(&self, identity: &UserIdentityRecord) -> bool {
+        self.records.iter().any(|record| match record {
+            AccountRecord::Revocation(revocation) => revocation.revokes(identity),
+            _ => false,
+        })
+    }
+
+    fn is_superseded(&self, identity: &UserIdentityRecord) -> bool {
+        self.records.iter().any(|record| match record {
+            AccountRecord::Supersede(supersede) => supersede.supersedes(identity),
+            _ => false,
+        })
+    }
+}
+
+fn group_key(identity: &UserIdentityRecord) -> (String, String) {
+    let handle_key = identity.identity()
+        .and_then(|social| social.handle.as_ref().map(|handle| normalize_handle(&social.platform, handle)))
+        .unwrap_or_else(|| identity.proof_identifier().to_string());
+    (identity.provider_id().to_string(), handle_key)
+}
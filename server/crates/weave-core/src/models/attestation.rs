@@ -0,0 +1,258 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::errors::proof_errors::ProofError;
+use crate::hash::hash_bytes_sha256;
+
+#[cfg(feature = "reclaim")]
+pub use reclaim_rust_sdk::Proof as ReclaimProof;
+/// Without the `reclaim` feature Reclaim proofs are carried as raw JSON and can not be read
+#[cfg(not(feature = "reclaim"))]
+pub type ReclaimProof = serde_json::Value;
+/// Kind of attestation a user submits to link a social account
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum AttestationKind {
+    #[default]
+    Reclaim,
+    TlsNotary,
+    Signed,
+}
+
+impl fmt::Display for AttestationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            AttestationKind::Reclaim => "reclaim",
+            AttestationKind::TlsNotary => "tls_notary",
+            AttestationKind::Signed => "signed",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+/// Claim content of an attestation which is not produced by Reclaim.
+/// It is signed by a notary (TLSNotary-style) or by a trusted issuer.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttestedClaim {
+    pub provider: String,
+    pub parameters: String,
+    pub context: Option<String>,
+    pub public_data: Option<HashMap<String, String>>,
+    pub issued_at: i64,
+}
+
+/// Attestation signed by a secp256k1 key.
+/// `payload` is the base64 encoded JSON of an `AttestedClaim` exactly as it was signed.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SignedClaimAttestation {
+    pub payload: String,
+    pub public_key: String,
+    pub signature: String,
+}
+
+impl SignedClaimAttestation {
+    pub fn payload_bytes(&self) -> Result<Vec<u8>, ProofError> {
+        STANDARD.decode(&self.payload)
+            .map_err(|e| ProofError::SerializationError(format!("Invalid attestation payload: {}", e)))
+    }
+
+    pub fn claim(&self) -> Result<AttestedClaim, ProofError> {
+        Ok(serde_json::from_slice::<AttestedClaim>(&self.payload_bytes()?)?)
+    }
+}
+
+/// Claim data extracted from any kind of attestation
+#[derive(Debug, Clone)]
+pub struct AttestationClaim {
+    pub provider: Option<String>,
+    pub parameters: String,
+    pub context: Option<String>,
+    pub public_data: Option<HashMap<String, String>>,
+    pub issued_at: i64,
+}
+
+impl AttestationClaim {
+    /// Names of the provider the claim was attested for: `provider` of the claim, and the
+    /// `providerId` and `providerHash` Reclaim puts in the context
+    pub fn provider_names(&self) -> Vec<String> {
+        let context = self.context.as_deref()
+            .and_then(|context| serde_json::from_str::<serde_json::Value>(context).ok());
+        let from_context = ["providerId", "providerHash"].into_iter()
+            .filter_map(|key| context.as_ref()?.get(key)?.as_str().map(str::to_string));
+        self.provider.clone().into_iter().chain(from_context).collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Attestation {
+    Reclaim(ReclaimProof),
+    TlsNotary(SignedClaimAttestation),
+    Signed(SignedClaimAttestation),
+}
+
+impl Attestation {
+    pub fn kind(&self) -> AttestationKind {
+        match self {
+            Attestation::Reclaim(_) => AttestationKind::Reclaim,
+            Attestation::TlsNotary(_) => AttestationKind::TlsNotary,
+            Attestation::Signed(_) => AttestationKind::Signed,
+        }
+    }
+
+    /// Unique identifier of the attested claim
+    pub fn identifier(&self) -> Result<String, ProofError> {
+        match self {
+            #[cfg(feature = "reclaim")]
+            Attestation::Reclaim(proof) => Ok(proof.identifier.clone()),
+            #[cfg(not(feature = "reclaim"))]
+            Attestation::Reclaim(_) => Err(reclaim_unsupported()),
+            Attestation::TlsNotary(attestation) | Attestation::Signed(attestation) => {
+                Ok(hash_bytes_sha256(attestation.payload_bytes()?))
+            }
+        }
+    }
+
+    pub fn claim(&self) -> Result<AttestationClaim, ProofError> {
+        match self {
+            #[cfg(not(feature = "reclaim"))]
+            Attestation::Reclaim(_) => Err(reclaim_unsupported()),
+            #[cfg(feature = "reclaim")]
+            Attestation::Reclaim(proof) => Ok(AttestationClaim {
+                provider: Some(proof.claim_data.provider.clone()),
+                parameters: proof.claim_data.parameters.clone(),
+                context: Some(proof.claim_data.context.clone()),
+                public_data: proof.public_data.clone(),
+                issued_at: proof.claim_data.timestamp_s as i64,
+            }),
+            Attestation::TlsNotary(attestation) | Attestation::Signed(attestation) => {
+                let claim = attestation.claim()?;
+                Ok(AttestationClaim {
+                    provider: Some(claim.provider),
+                    parameters: claim.parameters,
+                    context: claim.context,
+                    public_data: claim.public_data,
+                    issued_at: claim.issued_at,
+                })
+            }
+        }
+    }
+
+    /// Binary representation stored in the database and used for duplicate detection
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ProofError> {
+        bincode::serialize(self).map_err(|e| ProofError::SerializationError(e.to_string()))
+    }
+
+    /// Rows written before attestation kinds existed hold a bare Reclaim proof
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        match bincode::deserialize::<Attestation>(bytes) {
+            Ok(attestation) => Ok(attestation),
+            Err(_) => bincode::deserialize::<ReclaimProof>(bytes)
+                .map(Attestation::Reclaim)
+                .map_err(|e| ProofError::SerializationError(e.to_string())),
+        }
+    }
+
+    /// Hash used for duplicate detection. Reclaim proofs are hashed without the
+    /// enum tag so that hashes of proofs stored before remain comparable.
+    pub fn hash(&self) -> Result<String, ProofError> {
+        let bytes = match self {
+            Attestation::Reclaim(proof) => bincode::serialize(proof)
+                .map_err(|e| ProofError::SerializationError(e.to_string()))?,
+            _ => self.to_bytes()?,
+        };
+        Ok(hash_bytes_sha256(bytes))
+    }
+}
+
+#[cfg(not(feature = "reclaim"))]
+fn reclaim_unsupported() -> ProofError {
+    ProofError::UnsupportedAttestation("Reclaim proofs require the `reclaim` feature".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed_attestation() -> SignedClaimAttestation {
+        let claim = AttestedClaim {
+            provider: "github".to_string(),
+            parameters: r#"{"username":"alice"}"#.to_string(),
+            context: None,
+            public_data: None,
+            issued_at: 1_700_000_000,
+        };
+        SignedClaimAttestation {
+            payload: STANDARD.encode(serde_json::to_vec(&claim).unwrap()),
+            public_key: "key".to_string(),
+            signature: "signature".to_string(),
+        }
+    }
+
+    #[test]
+    fn signed_claims_are_identified_by_their_payload() {
+        let attestation = signed_attestation();
+        let expected = hash_bytes_sha256(attestation.payload_bytes().unwrap());
+
+        assert_eq!(Attestation::Signed(attestation.clone()).identifier().unwrap(), expected);
+        assert_eq!(Attestation::TlsNotary(attestation).identifier().unwrap(), expected);
+    }
+
+    #[test]
+    fn attestations_round_trip_through_bytes() {
+        let attestation = Attestation::TlsNotary(signed_attestation());
+        let decoded = Attestation::from_bytes(&attestation.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(decoded.kind(), AttestationKind::TlsNotary);
+        assert_eq!(decoded.hash().unwrap(), attestation.hash().unwrap());
+        assert_eq!(decoded.claim().unwrap().provider.as_deref(), Some("github"));
+    }
+
+    #[test]
+    fn invalid_payloads_are_rejected() {
+        let mut attestation = signed_attestation();
+        attestation.payload = "not base64!".to_string();
+
+        assert!(Attestation::Signed(attestation).identifier().is_err());
+    }
+
+    /// Proof in the shape the Reclaim JS SDK sends it
+    #[cfg(feature = "reclaim")]
+    fn reclaim_proof() -> ReclaimProof {
+        serde_json::from_value(serde_json::json!({
+            "identifier": "0x2a1b5fd3ce34b0cd2c8c3d4de8bda9a9fd3a1f7f4b8d6fe3c8d1dd0e5b4c4b1a",
+            "claimData": {
+                "provider": "http",
+                "parameters": "{\"url\":\"https://api.github.com/user\"}",
+                "owner": "0x6c5ea6e2f0b8f63d3c5b0d4e7c2a1b9f8e7d6c5b",
+                "timestampS": 1_700_000_000,
+                "context": "{\"extractedParameters\":{\"username\":\"alice\"}}",
+                "identifier": "0x2a1b5fd3ce34b0cd2c8c3d4de8bda9a9fd3a1f7f4b8d6fe3c8d1dd0e5b4c4b1a",
+                "epoch": 1
+            },
+            "signatures": ["0x5f1c"],
+            "witnesses": [{ "id": "0x244897572368eadf65bfbc5aec98d8e5443a9072", "url": "wss://witness.reclaimprotocol.org/ws" }],
+            "publicData": null
+        })).unwrap()
+    }
+
+    /// Rows written before attestation kinds hold `encode_to_bytes` of the bare proof,
+    /// their hashes and contents must stay readable
+    #[cfg(feature = "reclaim")]
+    #[test]
+    fn reclaim_proofs_are_encoded_like_the_sdk() {
+        let proof = reclaim_proof();
+        let legacy_bytes = proof.encode_to_bytes().unwrap();
+
+        assert_eq!(bincode::serialize(&proof).unwrap(), legacy_bytes);
+        let attestation = Attestation::Reclaim(proof.clone());
+        assert_eq!(attestation.hash().unwrap(), hash_bytes_sha256(legacy_bytes.clone()));
+
+        let decoded = Attestation::from_bytes(&legacy_bytes).unwrap();
+        assert_eq!(decoded.kind(), AttestationKind::Reclaim);
+        assert_eq!(decoded.identifier().unwrap(), proof.identifier);
+    }
+}
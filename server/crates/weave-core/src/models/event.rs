@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use crate::models::job::JobStatus;
+
+/// Change of a user's account, pushed to the user's event streams
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UserEvent {
+    /// The Prism account was created on login
+    AccountCreated {
+        user_id: String,
+        transaction_hash: Option<String>,
+    },
+    /// An identity or supersede record was added to the account
+    RecordAdded {
+        user_id: String,
+        record_type: String,
+        provider_id: String,
+        transaction_hash: Option<String>,
+    },
+    /// A revocation record was added to the account
+    RecordRevoked {
+        user_id: String,
+        provider_id: String,
+        transaction_hash: Option<String>,
+    },
+    JobUpdated {
+        user_id: String,
+        job_id: String,
+        status: JobStatus,
+    },
+}
+
+impl UserEvent {
+    pub fn user_id(&self) -> &str {
+        match self {
+            UserEvent::AccountCreated { user_id, .. }
+            | UserEvent::RecordAdded { user_id, .. }
+            | UserEvent::RecordRevoked { user_id, .. }
+            | UserEvent::JobUpdated { user_id, .. } => user_id,
+        }
+    }
+
+    /// Name of the event, same as its `type`
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserEvent::AccountCreated { .. } => "account_created",
+            UserEvent::RecordAdded { .. } => "record_added",
+            UserEvent::RecordRevoked { .. } => "record_revoked",
+            UserEvent::JobUpdated { .. } => "job_updated",
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// Operation performed by an asynchronous job
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum JobKind {
+    CreateAccount,
+    ApplyProof,
+}
+
+/// Progress of an asynchronous job, stored as its snake_case name
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum JobStatus {
+    /// Accepted, the transaction is not posted yet
+    Queued,
+    /// The transaction was posted to Prism and waits for a batch
+    Submitted,
+    /// The transaction was included and the database is updated
+    Included,
+    Failed,
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobKind::CreateAccount => "create_account",
+            JobKind::ApplyProof => "apply_proof",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "create_account" => Some(JobKind::CreateAccount),
+            "apply_proof" => Some(JobKind::ApplyProof),
+            _ => None,
+        }
+    }
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Submitted => "submitted",
+            JobStatus::Included => "included",
+            JobStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "queued" => Some(JobStatus::Queued),
+            "submitted" => Some(JobStatus::Submitted),
+            "included" => Some(JobStatus::Included),
+            "failed" => Some(JobStatus::Failed),
+            _ => None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Included | JobStatus::Failed)
+    }
+}
@@ -0,0 +1,13 @@
+pub mod user;
+pub mod attestation;
+pub mod provider;
+pub mod social_identity;
+pub mod account_state;
+pub mod record_codec;
+pub mod receipt;
+pub mod job;
+pub mod event;
+pub mod webhook;
+pub mod operation;
+pub mod reindex;
+pub mod account_proof;
\ No newline at end of file
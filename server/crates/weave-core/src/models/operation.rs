@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+/// Write spanning Prism and the database, tracked by the outbox
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum OperationKind {
+    CreateAccount,
+    ApplyProof,
+    RevokeProof,
+    /// Supersede record written to the previous owner of a transferred identity
+    TransferIdentity,
+}
+
+/// Progress of an operation, stored as its snake_case name
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum OperationStatus {
+    /// Intent recorded, the Prism transaction is not posted yet
+    Pending,
+    /// The Prism transaction was posted, the database is not updated yet
+    Submitted,
+    /// Prism and the database are both updated
+    Committed,
+    /// The Prism write did not happen, reservations taken for it were released
+    Compensated,
+    /// The worker gave up: the database could not be updated, or the Prism transaction
+    /// was neither included nor known to be dropped. Retried or compensated on request only
+    Failed,
+}
+
+impl OperationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationKind::CreateAccount => "create_account",
+            OperationKind::ApplyProof => "apply_proof",
+            OperationKind::RevokeProof => "revoke_proof",
+            OperationKind::TransferIdentity => "transfer_identity",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "create_account" => Some(OperationKind::CreateAccount),
+            "apply_proof" => Some(OperationKind::ApplyProof),
+            "revoke_proof" => Some(OperationKind::RevokeProof),
+            "transfer_identity" => Some(OperationKind::TransferIdentity),
+            _ => None,
+        }
+    }
+}
+
+impl OperationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationStatus::Pending => "pending",
+            OperationStatus::Submitted => "submitted",
+            OperationStatus::Committed => "committed",
+            OperationStatus::Compensated => "compensated",
+            OperationStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(OperationStatus::Pending),
+            "submitted" => Some(OperationStatus::Submitted),
+            "committed" => Some(OperationStatus::Committed),
+            "compensated" => Some(OperationStatus::Compensated),
+            "failed" => Some(OperationStatus::Failed),
+            _ => None,
+        }
+    }
+
+    /// Pending and submitted operations are picked up by the outbox worker
+    pub fn is_finished(&self) -> bool {
+        matches!(self, OperationStatus::Committed | OperationStatus::Compensated | OperationStatus::Failed)
+    }
+}
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::attestation::AttestationClaim;
+
+/// Where to find normalized claim fields inside an attestation.
+/// Every rule is a dot separated path starting with `parameters`, `context` or `public_data`,
+/// e.g. `context.extractedParameters.username`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ClaimExtractionRules {
+    pub handle: Option<String>,
+    pub user_id: Option<String>,
+    pub profile_url: Option<String>,
+    pub follower_count: Option<String>,
+    /// Used when the claim has no profile URL, `{handle}` is replaced with the extracted handle
+    pub profile_url_template: Option<String>,
+}
+
+/// What happens when a social account that is already linked to another wallet is linked again
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UniquenessPolicy {
+    /// The new proof fails with a conflict
+    #[default]
+    Reject,
+    /// The new link is accepted and marked as flagged
+    Flag,
+    /// The new link is accepted and the old accounts get a supersede record
+    Transfer,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Provider {
+    pub id: String,
+    pub name: String,
+    /// Normalized platform name, e.g. `twitter`
+    pub platform: String,
+    pub category: String,
+    pub icon_url: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub claim_extraction: ClaimExtractionRules,
+    /// Maximum age of the attested claim in seconds
+    pub max_proof_age: Option<i64>,
+    #[serde(default)]
+    pub uniqueness_policy: UniquenessPolicy,
+    /// Further names attestations of this provider carry, e.g. the `providerHash` of its
+    /// Reclaim provider. The provider id and platform are always accepted.
+    #[serde(default)]
+    pub attested_providers: Vec<String>,
+}
+
+impl Provider {
+    /// Whether the attestation was issued for this provider, so that a proof of one
+    /// platform can not claim a handle under another provider
+    pub fn matches_claim(&self, claim: &AttestationClaim) -> bool {
+        claim.provider_names().iter().any(|name| {
+            *name == self.id
+                || name.eq_ignore_ascii_case(&self.platform)
+                || self.attested_providers.contains(name)
+        })
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider() -> Provider {
+        serde_json::from_value(serde_json::json!({
+            "id": "6d3f6753-7ee6-49ee-a545-62f1b1822ae5",
+            "name": "Github",
+            "platform": "github",
+            "category": "developer",
+            "icon_url": null,
+            "attested_providers": ["0xgithub"]
+        })).unwrap()
+    }
+
+    fn claim(provider: &str, context: Option<&str>) -> AttestationClaim {
+        AttestationClaim {
+            provider: Some(provider.to_string()),
+            parameters: "{}".to_string(),
+            context: context.map(str::to_string),
+            public_data: None,
+            issued_at: 0,
+        }
+    }
+
+    #[test]
+    fn claims_name_the_provider_by_id_platform_or_attested_name() {
+        let provider = provider();
+        assert!(provider.matches_claim(&claim("GitHub", None)));
+        assert!(provider.matches_claim(&claim("http", Some(r#"{"providerId":"6d3f6753-7ee6-49ee-a545-62f1b1822ae5"}"#))));
+        assert!(provider.matches_claim(&claim("http", Some(r#"{"providerHash":"0xgithub"}"#))));
+    }
+
+    #[test]
+    fn claims_of_other_providers_do_not_match() {
+        let provider = provider();
+        assert!(!provider.matches_claim(&claim("linkedin", None)));
+        assert!(!provider.matches_claim(&claim("http", Some(r#"{"providerHash":"0xlinkedin"}"#))));
+        // names inside the extracted parameters are not trusted
+        assert!(!provider.matches_claim(&claim("http", Some(r#"{"extractedParameters":{"providerId":"github"}}"#))));
+    }
+}
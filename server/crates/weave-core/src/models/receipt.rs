@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// Locates an account update on Prism so that clients can verify it independently.
+/// Only returned when a transaction was posted; the account proof to check against
+/// `commitment` is served by `GET /api/user/{id}/proof`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TransactionReceipt {
+    /// Hex encoded sha256 of the signed transaction
+    pub transaction_hash: String,
+    pub account_id: String,
+    /// Account nonce after the transaction was applied
+    pub nonce: u64,
+    /// Height of the DA block which included the transaction
+    pub height: u64,
+    /// Hex encoded commitment (JMT root) of the first epoch finalized at or after `height`,
+    /// the first root covering the update. `None` while that epoch is not posted yet
+    pub commitment: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn receipts_carry_the_commitment_of_their_height() {
+        let receipt = TransactionReceipt {
+            transaction_hash: "ab".repeat(32),
+            account_id: "cosmos1user".to_string(),
+            nonce: 2,
+            height: 7,
+            commitment: Some("cd".repeat(32)),
+        };
+
+        let value = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(value, json!({
+            "transaction_hash": "ab".repeat(32),
+            "account_id": "cosmos1user",
+            "nonce": 2,
+            "height": 7,
+            "commitment": "cd".repeat(32),
+        }));
+        let parsed: TransactionReceipt = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.commitment, receipt.commitment);
+    }
+
+    #[test]
+    fn receipts_without_a_finalized_epoch_have_no_commitment() {
+        let value = json!({
+            "transaction_hash": "ab".repeat(32),
+            "account_id": "cosmos1user",
+            "nonce": 1,
+            "height": 3,
+            "commitment": null,
+        });
+
+        let receipt: TransactionReceipt = serde_json::from_value(value).unwrap();
+        assert_eq!(receipt.commitment, None);
+    }
+}
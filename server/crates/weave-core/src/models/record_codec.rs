@@ -0,0 +1,150 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::errors::record_errors::RecordError;
+use crate::models::user::{
+    AccountRecord, IdentityRevocationRecord, IdentitySupersedeRecord, UserIdentityRecord,
+    IDENTITY_RECORD_VERSION,
+};
+
+/// Identity record as written before records were versioned
+#[derive(Deserialize)]
+struct UserIdentityRecordV1 {
+    proof_identifier: String,
+    public_data: Option<HashMap<String, String>>,
+    provider_id: String,
+    claim_data_params: String,
+    created_at: i64,
+}
+
+impl From<UserIdentityRecordV1> for UserIdentityRecord {
+    fn from(record: UserIdentityRecordV1) -> Self {
+        UserIdentityRecord::from_v1(
+            record.proof_identifier,
+            record.public_data,
+            record.provider_id,
+            record.claim_data_params,
+            record.created_at,
+        )
+    }
+}
+
+/// Fields which only exist since version 2. A record carrying them without a `version`
+/// field is not a version 1 record and is not read as one.
+const V2_FIELDS: [&str; 2] = ["attestation_kind", "identity"];
+
+/// Decodes an identity record of any known version and upgrades it to the current model.
+/// Records without a `version` field are version 1.
+pub fn decode_identity_record(value: Value) -> Result<UserIdentityRecord, RecordError> {
+    let version = match value.get("version") {
+        None if V2_FIELDS.iter().any(|field| value.get(field).is_some()) => {
+            return Err(RecordError::InvalidRecord("record without a version has version 2 fields".to_string()));
+        }
+        None => 1,
+        Some(version) => version.as_u64()
+            .ok_or_else(|| RecordError::InvalidRecord("version must be a number".to_string()))?,
+    };
+
+    match version {
+        1 => Ok(serde_json::from_value::<UserIdentityRecordV1>(value)?.into()),
+        v if v == IDENTITY_RECORD_VERSION as u64 => Ok(serde_json::from_value::<UserIdentityRecord>(value)?),
+        v => Err(RecordError::UnsupportedVersion(v)),
+    }
+}
+
+/// Dispatches a stored record by its `record_type`; identity records have none
+pub fn decode_account_record(value: Value) -> Result<AccountRecord, RecordError> {
+    let record_type = value.get("record_type").and_then(Value::as_str).map(str::to_string);
+
+    match record_type.as_deref() {
+        None => decode_identity_record(value).map(AccountRecord::Identity),
+        Some(IdentityRevocationRecord::RECORD_TYPE) => Ok(AccountRecord::Revocation(serde_json::from_value(value)?)),
+        Some(IdentitySupersedeRecord::RECORD_TYPE) => Ok(AccountRecord::Supersede(serde_json::from_value(value)?)),
+        Some(other) => Err(RecordError::UnknownRecordType(other.to_string())),
+    }
+}
+
+/// Decodes the JSON bytes of a stored record
+pub fn decode_account_record_bytes(bytes: &[u8]) -> Result<AccountRecord, RecordError> {
+    decode_account_record(serde_json::from_slice::<Value>(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::attestation::AttestationKind;
+
+    /// Identity record as written before records were versioned
+    const IDENTITY_V1: &str = r#"{"proof_identifier":"0xabc","public_data":null,"provider_id":"github","claim_data_params":"{\"username\":\"alice\"}","created_at":1700000000}"#;
+    /// Identity record as written by this server
+    const IDENTITY_V2: &str = r#"{"version":2,"proof_identifier":"0xabc","public_data":{"repos":"3"},"provider_id":"github","claim_data_params":"{\"username\":\"alice\"}","created_at":1700000000,"attestation_kind":"tls_notary","identity":{"platform":"github","handle":"alice","user_id":"42","profile_url":"https://github.com/alice","follower_count":7}}"#;
+    const REVOCATION: &str = r#"{"record_type":"revocation","provider_id":"github","proof_identifier":"0xabc","revoked_at":1700000100}"#;
+    const SUPERSEDE: &str = r#"{"record_type":"supersede","provider_id":"github","platform":"github","handle":"alice","proof_identifier":"0xabc","superseded_by":"cosmos1new","created_at":1700000200}"#;
+
+    fn identity(bytes: &str) -> UserIdentityRecord {
+        match decode_account_record_bytes(bytes.as_bytes()).unwrap() {
+            AccountRecord::Identity(record) => record,
+            other => panic!("Expected an identity record, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn version_1_is_upgraded() {
+        let record = identity(IDENTITY_V1);
+
+        assert_eq!(record.version(), IDENTITY_RECORD_VERSION);
+        assert_eq!(record.proof_identifier(), "0xabc");
+        assert_eq!(record.provider_id(), "github");
+        assert_eq!(record.created_at(), 1_700_000_000);
+        assert!(record.identity().is_none());
+        assert_eq!(serde_json::to_value(&record).unwrap()["attestation_kind"], "reclaim");
+    }
+
+    #[test]
+    fn version_2_decodes_and_encodes_to_the_same_bytes() {
+        let record = identity(IDENTITY_V2);
+
+        assert_eq!(record.identity().and_then(|identity| identity.handle.as_deref()), Some("alice"));
+        assert_eq!(serde_json::to_value(&record).unwrap()["attestation_kind"], serde_json::to_value(AttestationKind::TlsNotary).unwrap());
+        assert_eq!(serde_json::to_string(&record).unwrap(), IDENTITY_V2);
+    }
+
+    #[test]
+    fn versionless_record_with_version_2_fields_is_rejected() {
+        let mut value: Value = serde_json::from_str(IDENTITY_V1).unwrap();
+        value["attestation_kind"] = Value::from("signed");
+
+        assert!(matches!(decode_identity_record(value), Err(RecordError::InvalidRecord(_))));
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        let mut value: Value = serde_json::from_str(IDENTITY_V2).unwrap();
+        value["version"] = Value::from(3);
+
+        assert!(matches!(decode_identity_record(value), Err(RecordError::UnsupportedVersion(3))));
+    }
+
+    #[test]
+    fn revocation_and_supersede_records_are_dispatched_by_type() {
+        match decode_account_record_bytes(REVOCATION.as_bytes()).unwrap() {
+            AccountRecord::Revocation(record) => {
+                assert_eq!(record.proof_identifier.as_deref(), Some("0xabc"));
+                assert_eq!(record.revoked_at, 1_700_000_100);
+            }
+            other => panic!("Expected a revocation, got {:?}", other),
+        }
+        match decode_account_record_bytes(SUPERSEDE.as_bytes()).unwrap() {
+            AccountRecord::Supersede(record) => assert_eq!(record.superseded_by, "cosmos1new"),
+            other => panic!("Expected a supersede record, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_record_types_are_rejected() {
+        let result = decode_account_record_bytes(br#"{"record_type":"delegation","provider_id":"github"}"#);
+
+        assert!(matches!(result, Err(RecordError::UnknownRecordType(kind)) if kind == "delegation"));
+    }
+}
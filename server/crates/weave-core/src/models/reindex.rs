@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// Difference between the database and the Prism accounts found by a reindex
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ReindexDifferenceKind {
+    /// The account is on Prism but has no `users` row
+    MissingUser,
+    /// An identity record of the account has no `proofs` row
+    MissingProof,
+    /// A `proofs` row matches no identity record of the account
+    UnknownProof,
+    /// The raw proof of a `proofs` row could not be decoded, the row is kept
+    UnreadableProof,
+    /// The record is revoked on the account but its row is not
+    MissingRevocation,
+    /// The row is revoked but the record on the account is not
+    UnexpectedRevocation,
+    /// The database references the account but it is not on Prism, never fixed automatically
+    MissingAccount,
+    /// The account could not be read, nothing of it was compared
+    UnreadableAccount,
+    /// The account has unresolved outbox operations, nothing of it was compared
+    OperationInProgress,
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::attestation::AttestationClaim;
+use crate::models::provider::Provider;
+
+/// Normalized social account fields extracted from a provider specific claim
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SocialIdentity {
+    pub platform: String,
+    pub handle: Option<String>,
+    pub user_id: Option<String>,
+    pub profile_url: Option<String>,
+    pub follower_count: Option<u64>,
+}
+
+impl SocialIdentity {
+    /// Applies the provider's claim extraction rules to the attested claim.
+    /// Fields which are not configured or not present in the claim are left empty.
+    pub fn extract(provider: &Provider, claim: &AttestationClaim) -> Self {
+        let rules = &provider.claim_extraction;
+        let lookup = |rule: &Option<String>| rule.as_deref().and_then(|path| resolve_claim_path(claim, path));
+
+        let handle = lookup(&rules.handle).map(|handle| handle.trim_start_matches('@').to_string());
+        let profile_url = lookup(&rules.profile_url).or_else(|| {
+            match (&rules.profile_url_template, &handle) {
+                (Some(template), Some(handle)) => Some(template.replace("{handle}", handle)),
+                _ => None,
+            }
+        });
+        let follower_count = lookup(&rules.follower_count)
+            .and_then(|count| count.replace([',', '_', ' '], "").parse::<u64>().ok());
+
+        Self {
+            platform: provider.platform.clone(),
+            handle,
+            user_id: lookup(&rules.user_id),
+            profile_url,
+            follower_count,
+        }
+    }
+
+    /// Key a social account is linked and looked up by: the handle, or the platform's user
+    /// id on platforms without handles (e.g. exchange accounts)
+    pub fn uniqueness_key(&self) -> Option<&str> {
+        self.handle.as_deref().or(self.user_id.as_deref())
+    }
+}
+
+/// Resolves a dot separated path like `context.extractedParameters.username`.
+/// `parameters` and `context` are JSON strings, `public_data` is a flat map.
+fn resolve_claim_path(claim: &AttestationClaim, path: &str) -> Option<String> {
+    let mut segments = path.split('.');
+    let root = segments.next()?;
+
+    let mut value = match root {
+        "parameters" => serde_json::from_str::<Value>(&claim.parameters).ok()?,
+        "context" => serde_json::from_str::<Value>(claim.context.as_deref()?).ok()?,
+        "public_data" => {
+            let key = segments.next()?;
+            return claim.public_data.as_ref()?.get(key).cloned();
+        }
+        _ => return None,
+    };
+
+    for segment in segments {
+        value = match value {
+            Value::Object(mut map) => map.remove(segment)?,
+            Value::Array(mut items) => {
+                let index = segment.parse::<usize>().ok()?;
+                if index >= items.len() {
+                    return None;
+                }
+                items.swap_remove(index)
+            }
+            _ => return None,
+        };
+    }
+
+    match value {
+        Value::String(s) if !s.is_empty() => Some(s),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handle::normalize_handle;
+
+    fn provider(platform: &str, extraction: Value) -> Provider {
+        serde_json::from_value(serde_json::json!({
+            "id": platform,
+            "name": platform,
+            "platform": platform,
+            "category": "kyc",
+            "icon_url": null,
+            "claim_extraction": extraction,
+        })).unwrap()
+    }
+
+    fn claim(context: Value) -> AttestationClaim {
+        AttestationClaim {
+            provider: None,
+            parameters: "{}".to_string(),
+            context: Some(context.to_string()),
+            public_data: None,
+            issued_at: 0,
+        }
+    }
+
+    #[test]
+    fn accounts_are_keyed_by_handle() {
+        let provider = provider("github", serde_json::json!({
+            "handle": "context.extractedParameters.username",
+            "user_id": "context.extractedParameters.id",
+        }));
+        let identity = SocialIdentity::extract(&provider, &claim(serde_json::json!({
+            "extractedParameters": { "username": "@Alice", "id": 42 },
+        })));
+
+        assert_eq!(identity.uniqueness_key(), Some("Alice"));
+        assert_eq!(identity.user_id.as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn accounts_without_handles_are_keyed_by_user_id() {
+        let provider = provider("binance", serde_json::json!({ "user_id": "context.extractedParameters.userId" }));
+        let identity = SocialIdentity::extract(&provider, &claim(serde_json::json!({
+            "extractedParameters": { "userId": "AbC123" },
+        })));
+
+        let key = identity.uniqueness_key().unwrap();
+        assert_eq!(key, "AbC123");
+        assert_eq!(normalize_handle(&identity.platform, key), "AbC123");
+        assert_eq!(SocialIdentity::default().uniqueness_key(), None);
+    }
+}
@@ -0,0 +1,415 @@
+#[cfg(feature = "prism")]
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+#[cfg(feature = "prism")]
+use tendermint::PublicKey;
+#[cfg(feature = "prism")]
+use prism_client::SignatureBundle;
+#[cfg(feature = "prism")]
+use prism_keys::{
+    Signature,
+    VerifyingKey,
+    CryptoAlgorithm,
+};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::errors::proof_errors::ProofError;
+#[cfg(feature = "prism")]
+use crate::errors::signature_errors::SignatureError;
+use crate::models::attestation::{Attestation, AttestationKind};
+use crate::models::provider::Provider;
+use crate::models::record_codec::decode_account_record;
+use crate::models::social_identity::SocialIdentity;
+#[cfg(feature = "prism")]
+use crate::amino::to_arbitrary_message_bytes;
+
+/// Version of the identity record format written by this server.
+/// Older versions are upgraded by `record_codec::decode_identity_record`.
+pub const IDENTITY_RECORD_VERSION: u32 = 2;
+
+/// Identity record in the current format. Always decode stored records through
+/// `record_codec` rather than deserializing this type directly.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UserIdentityRecord {
+    version: u32,
+    proof_identifier: String,
+    public_data: Option<HashMap<String, String>>,
+    provider_id: String,
+    claim_data_params: String,
+    created_at: i64,
+    #[serde(default)]
+    attestation_kind: AttestationKind,
+    #[serde(default)]
+    identity: Option<SocialIdentity>,
+}
+
+impl UserIdentityRecord {
+    pub const RECORD_TYPE: &'static str = "identity";
+
+    pub fn new(attestation: &Attestation, created_at: i64, provider: &Provider) -> Result<Self, ProofError> {
+        let claim = attestation.claim()?;
+        let identity = SocialIdentity::extract(provider, &claim);
+        Ok(Self {
+            version: IDENTITY_RECORD_VERSION,
+            proof_identifier: attestation.identifier()?,
+            provider_id: provider.id.clone(),
+            created_at,
+            public_data: claim.public_data,
+            claim_data_params: claim.parameters,
+            attestation_kind: attestation.kind(),
+            identity: Some(identity),
+        })
+    }
+
+    /// Builds the current model from the fields of a version 1 record
+    pub(crate) fn from_v1(
+        proof_identifier: String,
+        public_data: Option<HashMap<String, String>>,
+        provider_id: String,
+        claim_data_params: String,
+        created_at: i64,
+    ) -> Self {
+        Self {
+            version: IDENTITY_RECORD_VERSION,
+            proof_identifier,
+            public_data,
+            provider_id,
+            claim_data_params,
+            created_at,
+            attestation_kind: AttestationKind::Reclaim,
+            identity: None,
+        }
+    }
+
+    pub fn proof_identifier(&self) -> &str {
+        &self.proof_identifier
+    }
+
+    pub fn provider_id(&self) -> &str {
+        &self.provider_id
+    }
+
+    pub fn created_at(&self) -> i64 {
+        self.created_at
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn identity(&self) -> Option<&SocialIdentity> {
+        self.identity.as_ref()
+    }
+}
+
+/// Service written record marking an identity of this account as transferred to another wallet
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IdentitySupersedeRecord {
+    pub record_type: String,
+    pub provider_id: String,
+    pub platform: String,
+    pub handle: String,
+    pub proof_identifier: String,
+    pub superseded_by: String,
+    pub created_at: i64,
+}
+
+impl IdentitySupersedeRecord {
+    pub const RECORD_TYPE: &'static str = "supersede";
+
+    pub fn new(
+        provider_id: String,
+        platform: String,
+        handle: String,
+        proof_identifier: String,
+        superseded_by: String,
+        created_at: i64,
+    ) -> Self {
+        Self {
+            record_type: Self::RECORD_TYPE.to_string(),
+            provider_id,
+            platform,
+            handle,
+            proof_identifier,
+            superseded_by,
+            created_at,
+        }
+    }
+}
+
+impl IdentitySupersedeRecord {
+    pub fn supersedes(&self, record: &UserIdentityRecord) -> bool {
+        record.proof_identifier == self.proof_identifier
+    }
+}
+
+/// Wallet signed record revoking the identities linked with a provider.
+/// Identity records of the provider created up to `revoked_at` are revoked.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IdentityRevocationRecord {
+    pub record_type: String,
+    pub provider_id: String,
+    /// Revokes only the record with this proof identifier when set
+    pub proof_identifier: Option<String>,
+    pub revoked_at: i64,
+}
+
+impl IdentityRevocationRecord {
+    pub const RECORD_TYPE: &'static str = "revocation";
+
+    pub fn new(provider_id: String, proof_identifier: Option<String>, revoked_at: i64) -> Self {
+        Self {
+            record_type: Self::RECORD_TYPE.to_string(),
+            provider_id,
+            proof_identifier,
+            revoked_at,
+        }
+    }
+
+    pub fn revokes(&self, record: &UserIdentityRecord) -> bool {
+        record.provider_id == self.provider_id
+            && record.created_at <= self.revoked_at
+            && self.proof_identifier.as_ref().is_none_or(|id| *id == record.proof_identifier)
+    }
+}
+
+/// Any record stored in the signed data of a user account.
+/// Identity records have no `record_type` field, other records are dispatched by it.
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum AccountRecord {
+    Identity(UserIdentityRecord),
+    Revocation(IdentityRevocationRecord),
+    Supersede(IdentitySupersedeRecord),
+}
+
+impl AccountRecord {
+    pub fn record_type(&self) -> &'static str {
+        match self {
+            AccountRecord::Identity(_) => UserIdentityRecord::RECORD_TYPE,
+            AccountRecord::Revocation(_) => IdentityRevocationRecord::RECORD_TYPE,
+            AccountRecord::Supersede(_) => IdentitySupersedeRecord::RECORD_TYPE,
+        }
+    }
+
+    /// Identity records and revocations come from the wallet, or from the custodial key
+    /// which signed them before wallet signatures were stored; supersede records are
+    /// written by the service only
+    pub fn accepts_signer(&self, signer: RecordSigner) -> bool {
+        match self {
+            AccountRecord::Identity(_) | AccountRecord::Revocation(_) => {
+                matches!(signer, RecordSigner::Wallet | RecordSigner::Custodial)
+            }
+            AccountRecord::Supersede(_) => signer == RecordSigner::Service,
+        }
+    }
+
+    pub fn provider_id(&self) -> &str {
+        match self {
+            AccountRecord::Identity(record) => record.provider_id(),
+            AccountRecord::Revocation(record) => &record.provider_id,
+            AccountRecord::Supersede(record) => &record.provider_id,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AccountRecord {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        decode_account_record(value).map_err(D::Error::custom)
+    }
+}
+
+/// Signed data entry which could not be decoded into a known record
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UnrecognizedRecord {
+    pub index: usize,
+    /// Hex encoded sha256 of the raw signed data
+    pub data_hash: String,
+    /// Base64 encoded key which signed the data
+    pub signer_key: String,
+    pub error: String,
+}
+
+/// Who signed a record stored on an account
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum RecordSigner {
+    /// The Weave service key
+    Service,
+    /// The custodial key the service keeps for the user
+    Custodial,
+    /// The user's own wallet key
+    Wallet,
+    Unknown,
+}
+
+/// Signed data entry of an account at its position in the account log
+#[derive(Debug, Clone)]
+pub struct AccountEntry {
+    pub index: usize,
+    /// Hex encoded sha256 of the raw signed data
+    pub data_hash: String,
+    pub signer_key: String,
+    pub signer: RecordSigner,
+    pub signature_valid: bool,
+    pub record: Result<AccountRecord, UnrecognizedRecord>,
+}
+
+impl AccountEntry {
+    /// Only records with a valid signature of a key allowed to write them are taken into account
+    pub fn is_trusted(&self) -> bool {
+        self.signature_valid && match &self.record {
+            Ok(record) => record.accepts_signer(self.signer),
+            Err(_) => self.signer != RecordSigner::Unknown,
+        }
+    }
+}
+
+/// Identity record annotated with the result of read-time signature verification
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VerifiedIdentityRecord {
+    #[serde(flatten)]
+    pub record: UserIdentityRecord,
+    pub signer: RecordSigner,
+    pub signature_valid: bool,
+}
+
+/// Options for reading a user account
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UserReadOptions {
+    /// Return every identity record instead of the current ones
+    pub include_revoked: bool,
+    /// Fail when the account contains records which could not be decoded or verified
+    pub strict: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct User {
+    pub id: String,
+    pub identity_records: Vec<VerifiedIdentityRecord>,
+    #[serde(default)]
+    pub unrecognized_records: Vec<UnrecognizedRecord>,
+}
+
+impl User {
+    pub fn new(
+        id: String,
+        identity_records: Vec<VerifiedIdentityRecord>,
+        unrecognized_records: Vec<UnrecognizedRecord>,
+    ) -> Self {
+        Self { id, identity_records, unrecognized_records }
+    }
+}
+
+/// Data signed by the user's wallet, ready to be added to the Prism account
+#[cfg(feature = "prism")]
+pub struct UserRecord {
+    pub signature_bundle: SignatureBundle,
+    pub user_data: Vec<u8>,
+    pub user_id: String,
+}
+
+#[cfg(feature = "prism")]
+impl UserRecord {
+    pub fn new(signature_bundle: SignatureBundle, user_data: Vec<u8>, user_id: String) -> Self {
+        Self { signature_bundle, user_data, user_id }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UserAminoSignedRecord {
+    pub public_key: String,
+    pub signature: String,
+    pub signer: String,
+    pub data: Vec<u8>,
+}
+
+#[cfg(feature = "prism")]
+impl UserAminoSignedRecord {
+    pub fn new(public_key: String, signature: String, signer: String, data: Vec<u8>) -> Self {
+        Self { public_key, signature, signer, data }
+    }
+
+    fn to_signature_bundle(&self) -> Result<SignatureBundle, SignatureError> {
+        let signature_bytes = STANDARD.decode(&self.signature)
+            .map_err(|e| SignatureError::SignatureEncoding(e.to_string()))?;
+        let public_key_bytes = STANDARD.decode(&self.public_key)
+            .map_err(|e| SignatureError::PublicKeyEncoding(e.to_string()))?;
+        let pk = PublicKey::from_raw_secp256k1(public_key_bytes.as_slice())
+            .ok_or(SignatureError::InvalidPublicKey)?;
+        let vk = pk.secp256k1().ok_or(SignatureError::InvalidPublicKey)?;
+
+        Ok(SignatureBundle::new(
+            VerifyingKey::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, vk.to_bytes().as_slice())
+                .map_err(|_| SignatureError::InvalidPublicKey)?,
+            Signature::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, &signature_bytes)
+                .map_err(|e| SignatureError::InvalidSignature(e.to_string()))?,
+        ))
+    }
+
+    /// Fails on keys or signatures which are not valid secp256k1 encodings;
+    /// whether the signature matches is checked by Prism
+    pub fn to_user_record(&self) -> Result<UserRecord, SignatureError> {
+        let arbitrary_message_bytes = to_arbitrary_message_bytes(&self.signer, &STANDARD.encode(self.data.clone()));
+
+        Ok(UserRecord::new(self.to_signature_bundle()?, arbitrary_message_bytes, self.signer.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(record: AccountRecord, signer: RecordSigner, signature_valid: bool) -> AccountEntry {
+        AccountEntry {
+            index: 0,
+            data_hash: String::new(),
+            signer_key: String::new(),
+            signer,
+            signature_valid,
+            record: Ok(record),
+        }
+    }
+
+    fn revocation() -> AccountRecord {
+        AccountRecord::Revocation(IdentityRevocationRecord::new("github".to_string(), None, 0))
+    }
+
+    fn supersede() -> AccountRecord {
+        AccountRecord::Supersede(IdentitySupersedeRecord::new(
+            "github".to_string(),
+            "github".to_string(),
+            "alice".to_string(),
+            "0xabc".to_string(),
+            "cosmos1new".to_string(),
+            0,
+        ))
+    }
+
+    #[test]
+    fn supersede_records_need_the_service_key() {
+        assert!(entry(supersede(), RecordSigner::Service, true).is_trusted());
+        assert!(!entry(supersede(), RecordSigner::Wallet, true).is_trusted());
+        assert!(!entry(supersede(), RecordSigner::Custodial, true).is_trusted());
+    }
+
+    #[test]
+    fn revocations_need_the_wallet_or_custodial_key() {
+        assert!(entry(revocation(), RecordSigner::Wallet, true).is_trusted());
+        assert!(entry(revocation(), RecordSigner::Custodial, true).is_trusted());
+        assert!(!entry(revocation(), RecordSigner::Service, true).is_trusted());
+        assert!(!entry(revocation(), RecordSigner::Unknown, true).is_trusted());
+    }
+
+    #[test]
+    fn invalid_signatures_are_never_trusted() {
+        assert!(!entry(revocation(), RecordSigner::Wallet, false).is_trusted());
+        assert!(!entry(supersede(), RecordSigner::Service, false).is_trusted());
+    }
+}
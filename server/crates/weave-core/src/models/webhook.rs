@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Event an integrator's webhook can subscribe to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum WebhookEventType {
+    #[serde(rename = "account.created")]
+    AccountCreated,
+    #[serde(rename = "identity.linked")]
+    IdentityLinked,
+    #[serde(rename = "identity.revoked")]
+    IdentityRevoked,
+}
+
+/// Progress of a webhook delivery, stored as its snake_case name
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum DeliveryStatus {
+    /// Waiting for its first attempt or for a retry
+    Pending,
+    /// The receiver answered with a 2xx status
+    Delivered,
+    /// Every attempt failed, only a manual redelivery sends it again
+    Failed,
+}
+
+/// Body POSTed to a webhook endpoint
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookPayload {
+    /// Id of the delivery, the same on every retry so receivers can deduplicate
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event: WebhookEventType,
+    pub created_at: i64,
+    #[cfg_attr(feature = "openapi", schema(value_type = Object))]
+    pub data: Value,
+}
+
+impl WebhookEventType {
+    pub const ALL: [WebhookEventType; 3] = [
+        WebhookEventType::AccountCreated,
+        WebhookEventType::IdentityLinked,
+        WebhookEventType::IdentityRevoked,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::AccountCreated => "account.created",
+            WebhookEventType::IdentityLinked => "identity.linked",
+            WebhookEventType::IdentityRevoked => "identity.revoked",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.as_str() == value)
+    }
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(DeliveryStatus::Pending),
+            "delivered" => Some(DeliveryStatus::Delivered),
+            "failed" => Some(DeliveryStatus::Failed),
+            _ => None,
+        }
+    }
+}
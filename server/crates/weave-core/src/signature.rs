@@ -0,0 +1,155 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use bech32::FromBase32;
+use k256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use k256::sha2::{Digest, Sha256};
+use ripemd::Ripemd160;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::signature_errors::SignatureError;
+use crate::models::user::RecordSigner;
+
+/// Keys the verifying party trusts besides the account's wallet key.
+/// Keys are base64 encoded secp256k1 public keys, compressed or not.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedKeys {
+    pub service: Vec<String>,
+    pub custodial: Vec<String>,
+}
+
+/// Signed data entry of an account, all fields base64 encoded.
+/// `data` is the amino sign doc wrapping the record.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SignedRecord {
+    pub data: String,
+    pub public_key: String,
+    pub signature: String,
+}
+
+impl SignedRecord {
+    pub fn data_bytes(&self) -> Result<Vec<u8>, SignatureError> {
+        STANDARD.decode(&self.data).map_err(|e| SignatureError::DataEncoding(e.to_string()))
+    }
+
+    /// Checks the secp256k1 signature (sha256 digest) over the data.
+    /// Both compact and DER encoded signatures are accepted.
+    pub fn verify_signature(&self) -> Result<(), SignatureError> {
+        let key = decode_verifying_key(&self.public_key)?;
+        let signature_bytes = STANDARD.decode(&self.signature)
+            .map_err(|e| SignatureError::SignatureEncoding(e.to_string()))?;
+        let signature = Signature::from_slice(&signature_bytes)
+            .or_else(|_| Signature::from_der(&signature_bytes))
+            .map_err(|e| SignatureError::InvalidSignature(e.to_string()))?;
+        let signature = signature.normalize_s().unwrap_or(signature);
+
+        key.verify(&self.data_bytes()?, &signature)
+            .map_err(|e| SignatureError::InvalidSignature(e.to_string()))
+    }
+
+    /// Matches the signing key against the trusted keys and the account's wallet address
+    pub fn signer(&self, account_id: &str, trusted_keys: &TrustedKeys) -> Result<RecordSigner, SignatureError> {
+        let key = decode_verifying_key(&self.public_key)?;
+        let matches = |keys: &[String]| keys.iter()
+            .filter_map(|trusted| decode_verifying_key(trusted).ok())
+            .any(|trusted| trusted == key);
+
+        if matches(&trusted_keys.service) {
+            Ok(RecordSigner::Service)
+        } else if matches(&trusted_keys.custodial) {
+            Ok(RecordSigner::Custodial)
+        } else if key_matches_address(&key, account_id) {
+            Ok(RecordSigner::Wallet)
+        } else {
+            Ok(RecordSigner::Unknown)
+        }
+    }
+}
+
+/// Decodes a base64 encoded secp256k1 public key in SEC1 form, compressed or not
+pub fn decode_verifying_key(public_key: &str) -> Result<VerifyingKey, SignatureError> {
+    let bytes = STANDARD.decode(public_key)
+        .map_err(|e| SignatureError::PublicKeyEncoding(e.to_string()))?;
+    VerifyingKey::from_sec1_bytes(&bytes).map_err(|_| SignatureError::InvalidPublicKey)
+}
+
+/// Checks that a key belongs to a bech32 Cosmos address,
+/// i.e. the address data is ripemd160(sha256(compressed public key))
+pub fn key_matches_address(key: &VerifyingKey, address: &str) -> bool {
+    let address_bytes = match bech32::decode(address) {
+        Ok((_, data, _)) => match Vec::<u8>::from_base32(&data) {
+            Ok(bytes) => bytes,
+            Err(_) => return false,
+        },
+        Err(_) => return false,
+    };
+    let compressed = key.to_encoded_point(true);
+
+    Ripemd160::digest(Sha256::digest(compressed.as_bytes()))[..] == address_bytes[..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bech32::{ToBase32, Variant};
+    use k256::ecdsa::{signature::Signer, SigningKey};
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_slice(&[seed; 32]).unwrap()
+    }
+
+    fn address(key: &SigningKey) -> String {
+        let compressed = key.verifying_key().to_encoded_point(true);
+        let data = Ripemd160::digest(Sha256::digest(compressed.as_bytes()));
+        bech32::encode("cosmos", data.to_base32(), Variant::Bech32).unwrap()
+    }
+
+    fn public_key(key: &SigningKey) -> String {
+        STANDARD.encode(key.verifying_key().to_encoded_point(true).as_bytes())
+    }
+
+    fn signed(key: &SigningKey, data: &[u8]) -> SignedRecord {
+        let signature: Signature = key.sign(data);
+        SignedRecord {
+            data: STANDARD.encode(data),
+            public_key: public_key(key),
+            signature: STANDARD.encode(signature.to_bytes()),
+        }
+    }
+
+    #[test]
+    fn verifies_compact_and_der_signatures() {
+        let key = signing_key(1);
+        let mut record = signed(&key, b"record");
+        assert!(record.verify_signature().is_ok());
+
+        let signature: Signature = key.sign(b"record");
+        record.signature = STANDARD.encode(signature.to_der().as_bytes());
+        assert!(record.verify_signature().is_ok());
+
+        record.data = STANDARD.encode(b"other record");
+        assert!(matches!(record.verify_signature(), Err(SignatureError::InvalidSignature(_))));
+    }
+
+    #[test]
+    fn classifies_signers() {
+        let (wallet, service, custodial, other) = (signing_key(1), signing_key(2), signing_key(3), signing_key(4));
+        let account_id = address(&wallet);
+        let trusted_keys = TrustedKeys {
+            service: vec![public_key(&service)],
+            custodial: vec![public_key(&custodial)],
+        };
+        let signer = |key: &SigningKey| signed(key, b"record").signer(&account_id, &trusted_keys).unwrap();
+
+        assert_eq!(signer(&wallet), RecordSigner::Wallet);
+        assert_eq!(signer(&service), RecordSigner::Service);
+        assert_eq!(signer(&custodial), RecordSigner::Custodial);
+        assert_eq!(signer(&other), RecordSigner::Unknown);
+    }
+
+    #[test]
+    fn matches_keys_to_addresses() {
+        let key = signing_key(1);
+        assert!(key_matches_address(key.verifying_key(), &address(&key)));
+        assert!(!key_matches_address(signing_key(2).verifying_key(), &address(&key)));
+        assert!(!key_matches_address(key.verifying_key(), "not an address"));
+    }
+}
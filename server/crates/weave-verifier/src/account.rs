@@ -0,0 +1,140 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use weave_core::signature::SignedRecord;
+
+use crate::error::VerifyError;
+
+// `CryptoAlgorithm::Secp256k1` of prism-keys, the only algorithm Weave accounts are signed with
+const SECP256K1: u32 = 1;
+
+/// Fields of a Prism account read from its committed bytes
+#[derive(Debug, Clone)]
+pub struct CommittedAccount {
+    pub id: String,
+    pub nonce: u64,
+    /// Signed data entries in append order
+    pub records: Vec<SignedRecord>,
+}
+
+/// Reads the bincode encoding of a Prism `Account` as it is stored in the tree:
+/// `id`, `nonce`, `valid_keys`, `signed_data`, then the service challenge which is not read.
+/// Keys and signatures are encoded as their algorithm followed by their bytes.
+pub fn decode_account(bytes: &[u8]) -> Result<CommittedAccount, VerifyError> {
+    let mut reader = Reader { bytes, position: 0 };
+
+    let id = String::from_utf8(reader.bytes()?.to_vec())
+        .map_err(|e| VerifyError::Encoding(format!("Account id is not UTF-8: {}", e)))?;
+    let nonce = reader.u64()?;
+    for _ in 0..reader.length()? {
+        reader.crypto_payload()?;
+    }
+    let mut records = Vec::new();
+    for _ in 0..reader.length()? {
+        let data = reader.bytes()?;
+        let public_key = reader.crypto_payload()?;
+        let signature = reader.crypto_payload()?;
+        records.push(SignedRecord {
+            data: STANDARD.encode(data),
+            public_key: STANDARD.encode(public_key),
+            signature: STANDARD.encode(signature),
+        });
+    }
+
+    Ok(CommittedAccount { id, nonce, records })
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], VerifyError> {
+        let end = self.position.checked_add(length)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| VerifyError::Encoding("Account bytes end early".to_string()))?;
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+
+    fn u64(&mut self) -> Result<u64, VerifyError> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().expect("8 bytes")))
+    }
+
+    fn u32(&mut self) -> Result<u32, VerifyError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().expect("4 bytes")))
+    }
+
+    fn length(&mut self) -> Result<usize, VerifyError> {
+        usize::try_from(self.u64()?).map_err(|e| VerifyError::Encoding(e.to_string()))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], VerifyError> {
+        let length = self.length()?;
+        self.take(length)
+    }
+
+    fn crypto_payload(&mut self) -> Result<&'a [u8], VerifyError> {
+        let algorithm = self.u32()?;
+        if algorithm != SECP256K1 {
+            return Err(VerifyError::InvalidKey(format!("Unsupported key algorithm {}", algorithm)));
+        }
+        self.bytes()
+    }
+}
+
+/// Encoding side of `decode_account`, used to build committed accounts in tests
+#[cfg(test)]
+pub(crate) fn encode_account(id: &str, nonce: u64, records: &[(Vec<u8>, Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+    fn bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+        out.extend((bytes.len() as u64).to_le_bytes());
+        out.extend(bytes);
+    }
+    fn payload(out: &mut Vec<u8>, payload: &[u8]) {
+        out.extend(SECP256K1.to_le_bytes());
+        bytes(out, payload);
+    }
+
+    let mut out = Vec::new();
+    bytes(&mut out, id.as_bytes());
+    out.extend(nonce.to_le_bytes());
+    out.extend(0u64.to_le_bytes());
+    out.extend((records.len() as u64).to_le_bytes());
+    for (data, public_key, signature) in records {
+        bytes(&mut out, data);
+        payload(&mut out, public_key);
+        payload(&mut out, signature);
+    }
+    // no service challenge
+    out.push(0);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_signed_data_in_order() {
+        let bytes = encode_account("cosmos1abc", 3, &[
+            (b"first".to_vec(), vec![2; 33], vec![7; 64]),
+            (b"second".to_vec(), vec![3; 33], vec![8; 64]),
+        ]);
+        let account = decode_account(&bytes).unwrap();
+
+        assert_eq!(account.id, "cosmos1abc");
+        assert_eq!(account.nonce, 3);
+        assert_eq!(account.records.len(), 2);
+        assert_eq!(account.records[0].data_bytes().unwrap(), b"first");
+        assert_eq!(account.records[1].public_key, STANDARD.encode([3; 33]));
+        assert_eq!(account.records[1].signature, STANDARD.encode([8; 64]));
+    }
+
+    #[test]
+    fn rejects_truncated_accounts() {
+        let bytes = encode_account("cosmos1abc", 0, &[(b"record".to_vec(), vec![2; 33], vec![7; 64])]);
+        assert!(decode_account(&bytes[..bytes.len() - 20]).is_err());
+    }
+}
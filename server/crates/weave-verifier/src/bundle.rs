@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+use weave_core::amino::from_arbitrary_message_bytes_to_data_structure;
+use weave_core::models::account_proof::AccountProof;
+use weave_core::models::record_codec::decode_account_record;
+use weave_core::models::user::{AccountRecord, RecordSigner};
+use weave_core::signature::TrustedKeys;
+
+use crate::account::decode_account;
+use crate::error::VerifyError;
+use crate::proof::{ProofOutcome, VerifyAccountProof};
+
+/// Account bundle in the shape served by `GET /api/user/:user_id/proof`.
+/// Other fields of the response are ignored, records are read from the committed
+/// account bytes of the proof rather than from the response.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountBundle {
+    pub id: String,
+    pub proof: AccountProof,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct VerifiedRecord {
+    pub index: usize,
+    pub signer: RecordSigner,
+    pub record: AccountRecord,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct VerifiedBundle {
+    pub outcome: ProofOutcome,
+    pub records: Vec<VerifiedRecord>,
+}
+
+impl AccountBundle {
+    /// Verifies the proof against its own root, then every record of the committed
+    /// account: its signature and that it decodes.
+    /// Records of unknown signers are returned with `RecordSigner::Unknown`.
+    pub fn verify(&self, trusted_keys: &TrustedKeys) -> Result<VerifiedBundle, VerifyError> {
+        if self.proof.account_id != self.id {
+            return Err(VerifyError::InvalidProof(
+                format!("Proof is for account {}, not {}", self.proof.account_id, self.id)
+            ));
+        }
+        let outcome = self.proof.verify()?;
+        let account_bytes = match self.proof.account_bytes()? {
+            Some(account_bytes) => account_bytes,
+            None => return Ok(VerifiedBundle { outcome, records: Vec::new() }),
+        };
+        let account = decode_account(&account_bytes)?;
+        if account.id != self.id {
+            return Err(VerifyError::InvalidProof(
+                format!("Committed account is {}, not {}", account.id, self.id)
+            ));
+        }
+
+        let mut records = Vec::with_capacity(account.records.len());
+        for (index, signed) in account.records.iter().enumerate() {
+            signed.verify_signature()?;
+            let value = from_arbitrary_message_bytes_to_data_structure::<serde_json::Value>(&signed.data_bytes()?)
+                .map_err(|e| VerifyError::InvalidAmino(e.to_string()))?;
+
+            records.push(VerifiedRecord {
+                index,
+                signer: signed.signer(&self.id, trusted_keys)?,
+                record: decode_account_record(value)?,
+            });
+        }
+
+        Ok(VerifiedBundle { outcome, records })
+    }
+
+    /// Like `verify`, but additionally requires the proof root to be the trusted root
+    pub fn verify_against(&self, trusted_root: &str, trusted_keys: &TrustedKeys) -> Result<VerifiedBundle, VerifyError> {
+        self.proof.verify_against(trusted_root)?;
+        self.verify(trusted_keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use bech32::{ToBase32, Variant};
+    use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+    use k256::sha2::{Digest, Sha256};
+    use ripemd::Ripemd160;
+    use weave_core::amino::to_arbitrary_message_bytes;
+    use weave_core::models::user::{IdentityRevocationRecord, IdentitySupersedeRecord};
+
+    use crate::account::encode_account;
+    use crate::jmt;
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_slice(&[seed; 32]).unwrap()
+    }
+
+    fn public_key(key: &SigningKey) -> Vec<u8> {
+        key.verifying_key().to_encoded_point(true).as_bytes().to_vec()
+    }
+
+    fn address(key: &SigningKey) -> String {
+        let data = Ripemd160::digest(Sha256::digest(public_key(key)));
+        bech32::encode("cosmos", data.to_base32(), Variant::Bech32).unwrap()
+    }
+
+    fn signed(key: &SigningKey, signer: &str, record: &AccountRecord) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let data = to_arbitrary_message_bytes(signer, &STANDARD.encode(serde_json::to_vec(record).unwrap()));
+        let signature: Signature = key.sign(&data);
+        (data, public_key(key), signature.to_der().as_bytes().to_vec())
+    }
+
+    /// Bundle of the only account of a tree, whose root is then the account's leaf
+    fn bundle(id: &str, account_bytes: Vec<u8>) -> AccountBundle {
+        let key_hash = jmt::key_hash(id);
+        let value_hash = jmt::value_hash(&account_bytes);
+        AccountBundle {
+            id: id.to_string(),
+            proof: AccountProof {
+                account_id: id.to_string(),
+                account_bytes: Some(STANDARD.encode(&account_bytes)),
+                leaf_key: Some(hex::encode(key_hash)),
+                leaf_value_hash: Some(hex::encode(value_hash)),
+                siblings: Vec::new(),
+                root: hex::encode(jmt::leaf_hash(&key_hash, &value_hash)),
+            },
+        }
+    }
+
+    fn revocation() -> AccountRecord {
+        AccountRecord::Revocation(IdentityRevocationRecord::new("github".to_string(), None, 1))
+    }
+
+    fn supersede() -> AccountRecord {
+        AccountRecord::Supersede(IdentitySupersedeRecord::new(
+            "github".to_string(),
+            "github".to_string(),
+            "alice".to_string(),
+            "0xabc".to_string(),
+            "cosmos1new".to_string(),
+            2,
+        ))
+    }
+
+    #[test]
+    fn reads_records_from_the_committed_account() {
+        let (wallet, service) = (signing_key(1), signing_key(2));
+        let id = address(&wallet);
+        let account_bytes = encode_account(&id, 2, &[
+            signed(&wallet, &id, &revocation()),
+            signed(&service, &id, &supersede()),
+        ]);
+        let bundle = bundle(&id, account_bytes);
+        let trusted_keys = TrustedKeys { service: vec![STANDARD.encode(public_key(&service))], custodial: vec![] };
+
+        let verified = bundle.verify_against(&bundle.proof.root.clone(), &trusted_keys).unwrap();
+        assert_eq!(verified.outcome, ProofOutcome::Included);
+        assert_eq!(verified.records.len(), 2);
+        assert_eq!(verified.records[0].signer, RecordSigner::Wallet);
+        assert!(matches!(verified.records[0].record, AccountRecord::Revocation(_)));
+        assert_eq!(verified.records[1].signer, RecordSigner::Service);
+        assert!(matches!(verified.records[1].record, AccountRecord::Supersede(_)));
+    }
+
+    #[test]
+    fn rejects_records_with_invalid_signatures() {
+        let (wallet, other) = (signing_key(1), signing_key(3));
+        let id = address(&wallet);
+        let (data, _, signature) = signed(&other, &id, &revocation());
+        let account_bytes = encode_account(&id, 1, &[(data, public_key(&wallet), signature)]);
+
+        assert!(bundle(&id, account_bytes).verify(&TrustedKeys::default()).is_err());
+    }
+
+    #[test]
+    fn rejects_bytes_of_another_account() {
+        let (wallet, other) = (signing_key(1), signing_key(3));
+        let account_bytes = encode_account(&address(&other), 0, &[]);
+        let mut bundle = bundle(&address(&wallet), account_bytes);
+        bundle.proof.account_id = address(&wallet);
+
+        assert!(bundle.verify(&TrustedKeys::default()).is_err());
+    }
+}
@@ -0,0 +1,35 @@
+use thiserror::Error;
+use weave_core::errors::{record_errors::RecordError, signature_errors::SignatureError};
+
+#[derive(Debug, Error)]
+pub enum VerifyError {
+    #[error("Invalid encoding: {0}")]
+    Encoding(String),
+
+    #[error("Invalid amino message: {0}")]
+    InvalidAmino(String),
+
+    #[error(transparent)]
+    Record(#[from] RecordError),
+
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+
+    #[error(transparent)]
+    Signature(#[from] SignatureError),
+
+    #[error("Invalid proof: {0}")]
+    InvalidProof(String),
+}
+
+impl From<base64::DecodeError> for VerifyError {
+    fn from(error: base64::DecodeError) -> Self {
+        VerifyError::Encoding(error.to_string())
+    }
+}
+
+impl From<hex::FromHexError> for VerifyError {
+    fn from(error: hex::FromHexError) -> Self {
+        VerifyError::Encoding(error.to_string())
+    }
+}
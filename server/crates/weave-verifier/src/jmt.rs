@@ -0,0 +1,66 @@
+use k256::sha2::{Digest, Sha256};
+
+use crate::error::VerifyError;
+
+// Hashing scheme of the Jellyfish Merkle Tree used by Prism (sha256 tree hasher)
+const LEAF_DOMAIN_SEPARATOR: &[u8] = b"JMT::LeafNode";
+const INTERNAL_DOMAIN_SEPARATOR: &[u8] = b"JMT::IntrnalNode";
+pub const PLACEHOLDER_HASH: [u8; 32] = *b"SPARSE_MERKLE_PLACEHOLDER_HASH__";
+
+pub type Hash = [u8; 32];
+
+fn sha256(parts: &[&[u8]]) -> Hash {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// Tree key of an account id
+pub fn key_hash(account_id: &str) -> Hash {
+    sha256(&[account_id.as_bytes()])
+}
+
+pub fn value_hash(value: &[u8]) -> Hash {
+    sha256(&[value])
+}
+
+pub fn leaf_hash(key_hash: &Hash, value_hash: &Hash) -> Hash {
+    sha256(&[LEAF_DOMAIN_SEPARATOR, key_hash, value_hash])
+}
+
+pub fn internal_hash(left: &Hash, right: &Hash) -> Hash {
+    sha256(&[INTERNAL_DOMAIN_SEPARATOR, left, right])
+}
+
+/// Bit of the key selecting the child at the given depth, `1` being the right child
+pub fn bit(key_hash: &Hash, depth: usize) -> u8 {
+    (key_hash[depth / 8] >> (7 - depth % 8)) & 1
+}
+
+/// Whether both keys take the same path down to the given depth
+pub fn shares_prefix(key_hash: &Hash, other: &Hash, depth: usize) -> bool {
+    (0..depth).all(|depth| bit(key_hash, depth) == bit(other, depth))
+}
+
+/// Folds the siblings (ordered from the root down to the leaf) into the root hash
+/// of the path selected by the bits of the key
+pub fn root_from_path(key_hash: &Hash, leaf: &Hash, siblings: &[Hash]) -> Hash {
+    siblings.iter()
+        .enumerate()
+        .rev()
+        .fold(*leaf, |hash, (depth, sibling)| {
+            if bit(key_hash, depth) == 1 {
+                internal_hash(sibling, &hash)
+            } else {
+                internal_hash(&hash, sibling)
+            }
+        })
+}
+
+pub fn decode_hash(hex_hash: &str) -> Result<Hash, VerifyError> {
+    let bytes = hex::decode(hex_hash)?;
+    bytes.try_into().map_err(|_| VerifyError::Encoding(format!("Hash must be 32 bytes: {}", hex_hash)))
+}
+
@@ -0,0 +1,19 @@
+//! Verification of Weave account data without running the Weave server.
+//!
+//! A bundle as served by `GET /api/user/:user_id/proof` holds the JMT proof of an account
+//! against a Prism commitment together with the committed account bytes. This crate checks
+//! the proof, reads the signed records from the account bytes and checks their signatures.
+//! Records, amino sign docs and signatures are decoded with `weave_core`.
+
+pub mod account;
+pub mod bundle;
+pub mod error;
+pub mod jmt;
+pub mod proof;
+
+pub use bundle::{AccountBundle, VerifiedBundle, VerifiedRecord};
+pub use error::VerifyError;
+pub use proof::{ProofOutcome, VerifyAccountProof};
+pub use weave_core::models::account_proof::AccountProof;
+pub use weave_core::models::user::{AccountRecord, RecordSigner};
+pub use weave_core::signature::{SignedRecord, TrustedKeys};
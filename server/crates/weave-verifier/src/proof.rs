@@ -0,0 +1,256 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+use weave_core::models::account_proof::AccountProof;
+
+use crate::error::VerifyError;
+use crate::jmt::{self, PLACEHOLDER_HASH};
+
+/// What a valid account proof shows
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofOutcome {
+    Included,
+    NotIncluded,
+}
+
+/// Checks of an `AccountProof` as served by the API
+pub trait VerifyAccountProof {
+    /// Verifies the proof against its own `root`. Fails when the bundle does not hash
+    /// up to the root, the leaf does not commit to the given account bytes, or a claimed
+    /// missing account ends in a leaf which is the account itself or off its path.
+    fn verify(&self) -> Result<ProofOutcome, VerifyError>;
+
+    /// Verifies the proof against a root the caller trusts, e.g. one read from the DA layer
+    fn verify_against(&self, trusted_root: &str) -> Result<ProofOutcome, VerifyError>;
+
+    fn account_bytes(&self) -> Result<Option<Vec<u8>>, VerifyError>;
+}
+
+impl VerifyAccountProof for AccountProof {
+    fn verify(&self) -> Result<ProofOutcome, VerifyError> {
+        let key_hash = jmt::key_hash(&self.account_id);
+        let root = jmt::decode_hash(&self.root)?;
+        let siblings = self.siblings.iter()
+            .map(|sibling| jmt::decode_hash(sibling))
+            .collect::<Result<Vec<_>, _>>()?;
+        if siblings.len() > 256 {
+            return Err(VerifyError::InvalidProof(
+                format!("Proof of account {} is deeper than the tree", self.account_id)
+            ));
+        }
+        let leaf = match (&self.leaf_key, &self.leaf_value_hash) {
+            (Some(leaf_key), Some(leaf_value_hash)) => {
+                Some((jmt::decode_hash(leaf_key)?, jmt::decode_hash(leaf_value_hash)?))
+            }
+            (None, None) => None,
+            _ => return Err(VerifyError::InvalidProof(
+                format!("Leaf of account {} must carry both its key and value hash", self.account_id)
+            )),
+        };
+
+        let outcome = match (self.account_bytes()?, &leaf) {
+            (Some(account_bytes), Some((leaf_key, leaf_value_hash))) => {
+                if *leaf_key != key_hash || *leaf_value_hash != jmt::value_hash(&account_bytes) {
+                    return Err(VerifyError::InvalidProof(
+                        format!("Leaf does not commit to account {}", self.account_id)
+                    ));
+                }
+                ProofOutcome::Included
+            }
+            (Some(_), None) => {
+                return Err(VerifyError::InvalidProof(
+                    format!("Proof of account {} has no leaf", self.account_id)
+                ));
+            }
+            (None, Some((leaf_key, _))) => {
+                // the path of a missing account may end in the leaf of another account,
+                // which must then be the only leaf below the proven prefix
+                if *leaf_key == key_hash {
+                    return Err(VerifyError::InvalidProof(
+                        format!("Leaf commits to account {} which is claimed missing", self.account_id)
+                    ));
+                }
+                if !jmt::shares_prefix(&key_hash, leaf_key, siblings.len()) {
+                    return Err(VerifyError::InvalidProof(
+                        format!("Leaf is not on the path of account {}", self.account_id)
+                    ));
+                }
+                ProofOutcome::NotIncluded
+            }
+            (None, None) => ProofOutcome::NotIncluded,
+        };
+
+        let leaf_hash = match &leaf {
+            Some((leaf_key, leaf_value_hash)) => jmt::leaf_hash(leaf_key, leaf_value_hash),
+            None => PLACEHOLDER_HASH,
+        };
+        if jmt::root_from_path(&key_hash, &leaf_hash, &siblings) != root {
+            return Err(VerifyError::InvalidProof(
+                format!("Proof of account {} does not match root {}", self.account_id, self.root)
+            ));
+        }
+        Ok(outcome)
+    }
+
+    fn verify_against(&self, trusted_root: &str) -> Result<ProofOutcome, VerifyError> {
+        if !self.root.eq_ignore_ascii_case(trusted_root) {
+            return Err(VerifyError::InvalidProof(
+                format!("Proof root {} is not the trusted root {}", self.root, trusted_root)
+            ));
+        }
+        self.verify()
+    }
+
+    fn account_bytes(&self) -> Result<Option<Vec<u8>>, VerifyError> {
+        match &self.account_bytes {
+            Some(account_bytes) => Ok(Some(STANDARD.decode(account_bytes)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jmt::Hash;
+
+    /// Sparse Merkle tree as Prism's JMT lays it out: empty subtrees are placeholders
+    /// and a subtree holding a single account collapses into its leaf
+    struct Tree {
+        accounts: Vec<(String, Vec<u8>)>,
+    }
+
+    impl Tree {
+        fn new(ids: &[&str]) -> Self {
+            let accounts = ids.iter()
+                .map(|id| (id.to_string(), format!("account of {}", id).into_bytes()))
+                .collect();
+            Self { accounts }
+        }
+
+        fn leaves(&self) -> Vec<(Hash, Hash)> {
+            self.accounts.iter()
+                .map(|(id, bytes)| (jmt::key_hash(id), jmt::value_hash(bytes)))
+                .collect()
+        }
+
+        fn subtree(leaves: &[(Hash, Hash)], depth: usize) -> Hash {
+            match leaves {
+                [] => PLACEHOLDER_HASH,
+                [(key, value)] => jmt::leaf_hash(key, value),
+                _ => {
+                    let (right, left): (Vec<_>, Vec<_>) = leaves.iter().partition(|(key, _)| jmt::bit(key, depth) == 1);
+                    jmt::internal_hash(&Self::subtree(&left, depth + 1), &Self::subtree(&right, depth + 1))
+                }
+            }
+        }
+
+        fn root(&self) -> Hash {
+            Self::subtree(&self.leaves(), 0)
+        }
+
+        fn prove(&self, account_id: &str) -> AccountProof {
+            let key_hash = jmt::key_hash(account_id);
+            let mut leaves = self.leaves();
+            let mut siblings = Vec::new();
+            let mut depth = 0;
+            while leaves.len() > 1 {
+                let (own, other): (Vec<_>, Vec<_>) = leaves.into_iter()
+                    .partition(|(key, _)| jmt::bit(key, depth) == jmt::bit(&key_hash, depth));
+                siblings.push(hex::encode(Self::subtree(&other, depth + 1)));
+                leaves = own;
+                depth += 1;
+            }
+            let account_bytes = self.accounts.iter()
+                .find(|(id, _)| id == account_id)
+                .map(|(_, bytes)| STANDARD.encode(bytes));
+            AccountProof {
+                account_id: account_id.to_string(),
+                account_bytes,
+                leaf_key: leaves.first().map(|(key, _)| hex::encode(key)),
+                leaf_value_hash: leaves.first().map(|(_, value)| hex::encode(value)),
+                siblings,
+                root: hex::encode(self.root()),
+            }
+        }
+    }
+
+    fn tree() -> Tree {
+        Tree::new(&["alice", "bob", "carol", "dave", "erin"])
+    }
+
+    /// Missing account whose path ends in the leaf of another account, or in an empty subtree
+    fn missing(tree: &Tree, ends_in_leaf: bool) -> AccountProof {
+        (0..1000)
+            .map(|n| tree.prove(&format!("missing-{}", n)))
+            .find(|proof| proof.leaf_key.is_some() == ends_in_leaf)
+            .unwrap()
+    }
+
+    #[test]
+    fn accepts_inclusion_proofs() {
+        let tree = tree();
+        for (id, _) in &tree.accounts {
+            assert_eq!(tree.prove(id).verify().unwrap(), ProofOutcome::Included);
+        }
+        let proof = tree.prove("alice");
+        assert_eq!(proof.verify_against(&hex::encode(tree.root())).unwrap(), ProofOutcome::Included);
+    }
+
+    #[test]
+    fn accepts_non_inclusion_proofs() {
+        assert_eq!(missing(&tree(), true).verify().unwrap(), ProofOutcome::NotIncluded);
+
+        // two accounts on the same side of the root leave the other side empty
+        let side = |id: &String| jmt::bit(&jmt::key_hash(id), 0);
+        let ids: Vec<String> = (0..).map(|n| format!("account-{}", n)).take(10).collect();
+        let second = ids[1..].iter().find(|id| side(id) == side(&ids[0])).unwrap();
+        let tree = Tree::new(&[ids[0].as_str(), second.as_str()]);
+        assert_eq!(missing(&tree, false).verify().unwrap(), ProofOutcome::NotIncluded);
+    }
+
+    #[test]
+    fn rejects_tampered_account_bytes() {
+        let tree = tree();
+        let mut proof = tree.prove("alice");
+        proof.account_bytes = Some(STANDARD.encode(b"account of mallory"));
+        assert!(proof.verify().is_err());
+    }
+
+    #[test]
+    fn rejects_hiding_an_included_account() {
+        let tree = tree();
+        let mut proof = tree.prove("alice");
+        proof.account_bytes = None;
+        assert!(proof.verify().is_err());
+    }
+
+    #[test]
+    fn rejects_leaf_off_the_path() {
+        let tree = tree();
+        let mut proof = missing(&tree, true);
+        let key_hash = jmt::key_hash(&proof.account_id);
+        let (other, _) = tree.accounts.iter()
+            .find(|(id, _)| jmt::bit(&jmt::key_hash(id), 0) != jmt::bit(&key_hash, 0))
+            .unwrap();
+        let other = tree.prove(other);
+        proof.leaf_key = other.leaf_key;
+        proof.leaf_value_hash = other.leaf_value_hash;
+        assert!(proof.verify().is_err());
+    }
+
+    #[test]
+    fn rejects_leaf_without_value_hash() {
+        let tree = tree();
+        let mut proof = missing(&tree, true);
+        proof.leaf_value_hash = None;
+        assert!(proof.verify().is_err());
+    }
+
+    #[test]
+    fn rejects_untrusted_root() {
+        let tree = tree();
+        let other = Tree::new(&["alice"]);
+        assert!(tree.prove("alice").verify_against(&hex::encode(other.root())).is_err());
+    }
+}
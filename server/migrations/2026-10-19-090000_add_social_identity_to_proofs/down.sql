@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+
+ALTER TABLE proofs
+    DROP COLUMN record_version,
+    DROP COLUMN follower_count,
+    DROP COLUMN profile_url,
+    DROP COLUMN social_user_id,
+    DROP COLUMN handle,
+    DROP COLUMN platform;
@@ -0,0 +1,7 @@
+ALTER TABLE proofs
+    ADD COLUMN platform VARCHAR(64),
+    ADD COLUMN handle VARCHAR(255),
+    ADD COLUMN social_user_id VARCHAR(255),
+    ADD COLUMN profile_url VARCHAR(512),
+    ADD COLUMN follower_count BIGINT,
+    ADD COLUMN record_version INT NOT NULL DEFAULT 1; -- Identity record format version
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS identities (
+    platform VARCHAR(64) NOT NULL,
+    normalized_handle VARCHAR(255) NOT NULL, -- Handle after per-platform case/Unicode normalization
+    user_id VARCHAR(255) NOT NULL, -- Foreign key
+    provider_id VARCHAR(255) NOT NULL,
+    handle VARCHAR(255) NOT NULL,
+    social_user_id VARCHAR(255),
+    proof_identifier VARCHAR(255) NOT NULL,
+    proof_timestamp BIGINT NOT NULL,
+    created_at BIGINT NOT NULL,
+
+    PRIMARY KEY (platform, normalized_handle, user_id),
+    CONSTRAINT fk_identity_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_identities_user_id ON identities(user_id);
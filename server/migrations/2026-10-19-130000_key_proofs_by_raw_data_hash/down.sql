@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+
+-- provider_id alone can not be the key again once two wallets used the same provider,
+-- so it leads a composite key instead of failing or dropping proofs
+ALTER TABLE proofs DROP PRIMARY KEY, ADD PRIMARY KEY (provider_id, raw_data_hash);
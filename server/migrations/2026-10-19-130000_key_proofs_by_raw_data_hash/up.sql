@@ -0,0 +1,3 @@
+-- a wallet can hold several proofs of the same provider (e.g. after a revocation), and the
+-- same provider is used by many wallets; a proof itself is unique by its raw hash
+ALTER TABLE proofs DROP PRIMARY KEY, ADD PRIMARY KEY (raw_data_hash);
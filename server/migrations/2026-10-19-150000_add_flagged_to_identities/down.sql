@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+
+DROP INDEX idx_identities_handle ON identities;
+ALTER TABLE identities DROP COLUMN flagged;
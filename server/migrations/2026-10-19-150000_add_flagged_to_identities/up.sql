@@ -0,0 +1,4 @@
+ALTER TABLE identities
+    ADD COLUMN flagged BOOLEAN NOT NULL DEFAULT FALSE; -- Handle is linked to more than one wallet
+
+CREATE INDEX idx_identities_handle ON identities(platform, normalized_handle);
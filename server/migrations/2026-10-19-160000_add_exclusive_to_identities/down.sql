@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+
+DROP INDEX uq_identities_exclusive_handle ON identities;
+ALTER TABLE identities DROP COLUMN exclusive_handle, DROP COLUMN exclusive;
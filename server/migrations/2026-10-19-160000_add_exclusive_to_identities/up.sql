@@ -0,0 +1,14 @@
+ALTER TABLE identities
+    ADD COLUMN exclusive BOOLEAN NOT NULL DEFAULT FALSE, -- Handle is held by this wallet alone (reject and transfer providers)
+    ADD COLUMN exclusive_handle VARCHAR(255) AS (IF(exclusive, normalized_handle, NULL)) STORED;
+
+-- rows written before cover a handle alone when no other wallet links it
+UPDATE identities i
+JOIN (
+    SELECT platform, normalized_handle FROM identities
+    GROUP BY platform, normalized_handle
+    HAVING COUNT(*) = 1
+) single USING (platform, normalized_handle)
+SET i.exclusive = TRUE;
+
+CREATE UNIQUE INDEX uq_identities_exclusive_handle ON identities(platform, exclusive_handle);
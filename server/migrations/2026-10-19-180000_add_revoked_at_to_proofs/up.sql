@@ -0,0 +1,2 @@
+ALTER TABLE proofs
+    ADD COLUMN revoked_at BIGINT; -- Set when the user unlinks the identity
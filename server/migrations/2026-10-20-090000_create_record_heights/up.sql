@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS record_heights (
+    user_id VARCHAR(255) NOT NULL,
+    record_index INT NOT NULL, -- Position in the account's signed data
+    prism_height BIGINT NOT NULL, -- DA layer height at which the record was confirmed
+    recorded_at BIGINT NOT NULL,
+
+    PRIMARY KEY (user_id, record_index)
+);
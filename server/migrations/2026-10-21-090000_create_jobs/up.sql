@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS jobs (
+    id VARCHAR(36) PRIMARY KEY, -- uuid v4
+    kind VARCHAR(32) NOT NULL, -- create_account | apply_proof
+    status VARCHAR(16) NOT NULL, -- queued | submitted | included | failed
+    user_id VARCHAR(255) NOT NULL,
+    transaction_hash VARCHAR(64),
+    result TEXT, -- JSON response body once included
+    error TEXT, -- JSON ApiError once failed
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL,
+
+    INDEX idx_jobs_user_id (user_id)
+);
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+
+ALTER TABLE jobs
+    DROP INDEX idx_jobs_transaction_hash,
+    DROP INDEX idx_jobs_instance_status,
+    DROP COLUMN instance_id;
@@ -0,0 +1,5 @@
+-- jobs of a restarted instance are failed without touching the ones of other instances
+ALTER TABLE jobs
+    ADD COLUMN instance_id VARCHAR(255) NOT NULL DEFAULT '',
+    ADD INDEX idx_jobs_instance_status (instance_id, status),
+    ADD INDEX idx_jobs_transaction_hash (transaction_hash);
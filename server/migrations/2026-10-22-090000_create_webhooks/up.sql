@@ -0,0 +1,28 @@
+CREATE TABLE IF NOT EXISTS webhooks (
+    id VARCHAR(36) PRIMARY KEY, -- uuid v4
+    integrator VARCHAR(255) NOT NULL,
+    url VARCHAR(2048) NOT NULL,
+    secret VARCHAR(128) NOT NULL,
+    events VARCHAR(255) NOT NULL, -- comma separated event types
+    created_at BIGINT NOT NULL,
+
+    INDEX idx_webhooks_integrator (integrator)
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id VARCHAR(36) PRIMARY KEY, -- uuid v4, sent as the payload id
+    webhook_id VARCHAR(36) NOT NULL,
+    event_type VARCHAR(32) NOT NULL,
+    payload TEXT NOT NULL, -- JSON body as sent
+    status VARCHAR(16) NOT NULL, -- pending | delivered | failed
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at BIGINT,
+    last_status_code INT,
+    last_error TEXT,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL,
+
+    INDEX idx_webhook_deliveries_webhook_id (webhook_id, created_at),
+    INDEX idx_webhook_deliveries_due (status, next_attempt_at),
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    scope VARCHAR(64) NOT NULL, -- method and path, e.g. POST /api/proof
+    owner VARCHAR(255) NOT NULL, -- user id of authenticated routes, empty otherwise
+    idempotency_key VARCHAR(255) NOT NULL,
+    fingerprint VARCHAR(64) NOT NULL, -- sha256 of the request body
+    status_code INT, -- unset while the first request is running
+    content_type VARCHAR(255),
+    response_body MEDIUMBLOB,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL,
+
+    PRIMARY KEY (scope, owner, idempotency_key),
+    INDEX idx_idempotency_keys_expires_at (expires_at)
+);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS proof_reservations (
+    raw_data_hash VARCHAR(255) PRIMARY KEY, -- same hash as proofs.raw_data_hash
+    proof_identifier VARCHAR(255), -- unique where known, so a re-encoded proof is caught too
+    user_id VARCHAR(255) NOT NULL,
+    provider_id VARCHAR(255) NOT NULL,
+    status VARCHAR(16) NOT NULL, -- pending | applied
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL,
+
+    UNIQUE INDEX idx_proof_reservations_proof_identifier (proof_identifier)
+);
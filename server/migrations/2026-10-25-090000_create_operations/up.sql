@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS operations (
+    id VARCHAR(36) PRIMARY KEY, -- uuid v4
+    kind VARCHAR(32) NOT NULL, -- create_account | apply_proof
+    status VARCHAR(16) NOT NULL, -- pending | submitted | committed | compensated | failed
+    user_id VARCHAR(255) NOT NULL,
+    effects MEDIUMTEXT NOT NULL, -- JSON of the database writes to apply once Prism is updated
+    transaction_hash VARCHAR(64),
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at BIGINT,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL,
+
+    INDEX idx_operations_due (status, next_attempt_at),
+    INDEX idx_operations_created_at (created_at)
+);
@@ -0,0 +1,4 @@
+-- nonce of the posted transaction, the worker compensates an operation once the account
+-- nonce moved past it without the write
+ALTER TABLE operations
+    ADD COLUMN transaction_nonce BIGINT AFTER transaction_hash;
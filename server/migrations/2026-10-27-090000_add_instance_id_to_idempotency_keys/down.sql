@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+
+ALTER TABLE idempotency_keys
+    DROP INDEX idx_idempotency_keys_instance_status,
+    DROP COLUMN instance_id;
@@ -0,0 +1,4 @@
+-- unfinished keys of a restarted instance are released without touching the ones of other instances
+ALTER TABLE idempotency_keys
+    ADD COLUMN instance_id VARCHAR(255) NOT NULL DEFAULT '',
+    ADD INDEX idx_idempotency_keys_instance_status (instance_id, status_code);
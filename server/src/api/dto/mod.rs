@@ -1,2 +1 @@
-pub mod request;
-pub mod response;
\ No newline at end of file
+pub use weave_core::dto::{request, response};
\ No newline at end of file
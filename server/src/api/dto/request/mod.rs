@@ -1,2 +0,0 @@
-pub mod auth_req;
-pub mod proof_req;
\ No newline at end of file
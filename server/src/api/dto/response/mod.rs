@@ -1,3 +0,0 @@
-pub mod auth_res;
-pub mod proof_res;
-pub mod user_res;
\ No newline at end of file
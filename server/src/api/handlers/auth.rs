@@ -5,14 +5,22 @@ use axum::{
     Json,
     http::{StatusCode, HeaderMap},
 };
+use crate::api::extract::{ApiJson};
+use prism_da::DataAvailabilityLayer;
 use prism_prover::Prover;
-use serde_json::json;
 use crate::{
+    config::{integrator_config::IntegratorRegistry, provider_config::ProviderRegistry},
+    domain::errors::auth_errors::AuthError,
     api::dto::request::auth_req::{
         PrepareAuthRequestDto, 
         AuthWalletRequestDto
     },
-    domain::models::user::UserAminoSignedRecord, 
+    domain::errors::api_error::{ApiError, ErrorCode},
+    domain::errors::user_errors::UserError,
+    domain::models::{
+        job::JobKind, operation::OperationKind, receipt::TransactionReceipt,
+        user::{UserAminoSignedRecord, UserRecord},
+    },
     entities::{
         user::UserEntity, 
         user_repo::UserRepo
@@ -20,20 +28,40 @@ use crate::{
     services::auth_service::AuthService,
     utils::common::get_current_time
 };
-use crate::api::dto::response::auth_res::{ AuthWalletResponseDto, PrepareAuthDataResponseDto };
+use crate::api::dto::response::auth_res::{
+    AuthWalletResponseDto, PrepareAuthDataResponseDto, RefreshTokensResponseDto,
+};
 use crate::services::user_service::UserService;
-use crate::utils::jwt::{create_access_token, create_refresh_token, decode_token, TokenType};
+use crate::services::history_service::HistoryService;
+use crate::services::job_service::JobService;
+use crate::services::operation_service::{OperationEffects, OperationService};
+use super::job::prefers_async;
+use serde_json::json;
+use crate::utils::jwt::{create_access_token, create_refresh_token, extract_token, TokenType};
 use chrono::Utc;
 
 #[derive(Clone)]
 pub struct AppState {
     pub prover: Arc<Prover>,
     pub user_repo: UserRepo,
+    pub providers: Arc<ProviderRegistry>,
+    pub integrators: Arc<IntegratorRegistry>,
+    pub da_layer: Arc<dyn DataAvailabilityLayer>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/auth/prepare",
+    tag = "auth",
+    request_body = PrepareAuthRequestDto,
+    responses(
+        (status = 200, description = "Login message to sign with the wallet", body = PrepareAuthDataResponseDto),
+        (status = 400, description = "Invalid signer or public key", body = ApiError),
+    )
+)]
 pub async fn prepare_auth_data (
     State(state): State<AppState>,
-    Json(body): Json<PrepareAuthRequestDto>
+    ApiJson(body): ApiJson<PrepareAuthRequestDto>
 ) -> Response {
     let auth_service = AuthService::new(state.prover);
     let signer = body.signer.clone();
@@ -50,9 +78,30 @@ pub async fn prepare_auth_data (
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/auth",
+    tag = "auth",
+    request_body = AuthWalletRequestDto,
+    params(
+        ("Prefer" = Option<String>, Header, description = "`respond-async` to return before the account is created"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key and body get the first response replayed"),
+    ),
+    responses(
+        (status = 200, description = "Account created or found, tokens issued", body = AuthWalletResponseDto),
+        (status = 202, description = "Tokens issued, the account is created by the returned job", body = AuthWalletResponseDto),
+        (status = 400, description = "Malformed public key or invalid signature", body = ApiError),
+        (status = 409, description = "A request with the same Idempotency-Key is still running", body = ApiError),
+        (status = 413, description = "Body sent with an Idempotency-Key is larger than 2 MB", body = ApiError),
+        (status = 422, description = "Idempotency-Key reused with a different body", body = ApiError),
+        (status = 500, description = "Prism transaction failed", body = ApiError),
+        (status = 504, description = "Prism transaction was not included in time", body = ApiError),
+    )
+)]
 pub async fn auth_wallet(
     State(state): State<AppState>,
-    Json(body): Json<AuthWalletRequestDto>
+    headers: HeaderMap,
+    ApiJson(body): ApiJson<AuthWalletRequestDto>
 ) -> Response {
     let user_amino_signed_record = UserAminoSignedRecord::new(
         body.public_key.clone(), 
@@ -60,82 +109,129 @@ pub async fn auth_wallet(
         body.signer.clone(), 
         body.data.clone()
     );
-    let user_record = user_amino_signed_record.to_user_record();
-    let user_service = UserService::new(state.prover, body.signer.clone());
-
-    match user_service.create_user_account(user_record).await {
-        Ok(_) => {
-            // Get the current timestamp safely
-            let created_at = get_current_time();
-            let signer = body.signer.clone();
-            let user = UserEntity {
-                id: signer,
-                public_key: body.public_key.clone(),
-                created_at,
-            };
-
-            // Handle database insert result properly
-            match state.user_repo.insert_user(&user) {
-                Ok(_) => println!("User inserted successfully"),
-                Err(e) => eprintln!("Failed to insert user: {}", e),
+    let user_record = match user_amino_signed_record.to_user_record() {
+        Ok(user_record) => user_record,
+        Err(e) => return e.into_response(),
+    };
+    let user_service = UserService::new(state.prover.clone(), body.signer.clone());
+
+    if prefers_async(&headers) {
+        // the signature authenticates the login, so it is checked before issuing tokens
+        if let Err(e) = user_service.verify_user_record(&user_record) {
+            return e.into_response();
+        }
+        let (access_token, refresh_token) = match issue_tokens(&body.signer) {
+            Ok(tokens) => tokens,
+            Err(e) => return e.into_response(),
+        };
+        let job_service = JobService::new(state.user_repo.clone());
+        let job = match job_service.create(JobKind::CreateAccount, &body.signer) {
+            Ok(job) => job,
+            Err(e) => return ApiError::new(ErrorCode::DatabaseError, e.to_string()).into_response(),
+        };
+        let job_id = job.id.clone();
+        let user_service = user_service.with_job(job_service.clone(), job_id.clone());
+        tokio::spawn(async move {
+            match create_account(state, user_service, body.public_key, user_record).await {
+                Ok(receipt) => job_service.complete(&job_id, &body.signer, &json!({ "receipt": receipt })),
+                Err(e) => job_service.fail(&job_id, &body.signer, &e),
             }
+        });
 
-            let access_token = match create_access_token(body.signer.clone()) {
-                Ok(token) => token,
-                Err(e) => return e.into_response(),
-            };
-
-            let refresh_token = match create_refresh_token(body.signer) {
-                Ok(token) => token,
-                Err(e) => return e.into_response(),
-            };
-
-            (
-                StatusCode::OK,
-                Json(AuthWalletResponseDto {
-                    success: true,
-                    message: Some("Successfully logged in".to_string()),
-                    access_token,
-                    refresh_token,
-                })
-            ).into_response()
-        },
-        Err(e) => e.into_response(),
+        return (
+            StatusCode::ACCEPTED,
+            Json(AuthWalletResponseDto {
+                success: true,
+                message: Some("Login accepted, the account is being created".to_string()),
+                access_token,
+                refresh_token,
+                receipt: None,
+                job: Some(job),
+            })
+        ).into_response();
     }
+
+    let receipt = match create_account(state, user_service, body.public_key, user_record).await {
+        Ok(receipt) => receipt,
+        Err(e) => return e.into_response(),
+    };
+    let (access_token, refresh_token) = match issue_tokens(&body.signer) {
+        Ok(tokens) => tokens,
+        Err(e) => return e.into_response(),
+    };
+
+    (
+        StatusCode::OK,
+        Json(AuthWalletResponseDto {
+            success: true,
+            message: Some("Successfully logged in".to_string()),
+            access_token,
+            refresh_token,
+            receipt,
+            job: None,
+        })
+    ).into_response()
+}
+
+/// Creates the Prism account when it does not exist yet and registers the user in the database.
+/// The user is recorded as an outbox operation first, so the worker registers it even when
+/// the request does not get to.
+async fn create_account(
+    state: AppState,
+    user_service: UserService,
+    public_key: String,
+    user_record: UserRecord,
+) -> Result<Option<TransactionReceipt>, ApiError> {
+    let user = UserEntity {
+        id: user_record.user_id.clone(),
+        public_key,
+        created_at: get_current_time(),
+    };
+    let operations = OperationService::new(state.prover.clone(), state.user_repo.clone());
+    let operation_id = operations
+        .begin(OperationKind::CreateAccount, &user.id, &OperationEffects::CreateUser { user: user.clone() })
+        .map_err(|e| ApiError::new(ErrorCode::DatabaseError, e.to_string()))?;
+    let user_service = user_service.with_operation(operations.clone(), operation_id.clone());
+
+    let confirmed = match user_service.create_user_account(user_record).await {
+        Ok(confirmed) => confirmed,
+        // the transaction may still be included, the worker resolves the operation
+        Err(e @ UserError::TransactionTimeout(_)) => return Err(e.into()),
+        Err(e) => {
+            operations.abort(&operation_id, &e.to_string());
+            return Err(e.into());
+        }
+    };
+    operations.commit(&operation_id);
+
+    let history_service = HistoryService::new(state.prover, state.user_repo.clone(), state.da_layer);
+    Ok(history_service.confirm(&confirmed).await)
 }
 
+fn issue_tokens(signer: &str) -> Result<(String, String), AuthError> {
+    Ok((create_access_token(signer.to_string())?, create_refresh_token(signer.to_string())?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/refresh",
+    tag = "auth",
+    responses(
+        (status = 200, description = "New token pair", body = RefreshTokensResponseDto),
+        (status = 401, description = "Missing, invalid or expired refresh token", body = ApiError),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn refresh_tokens(headers: HeaderMap) -> Response {
-    let refresh_token = match headers.get("Authorization") {
-        Some(auth_header) => {
-            let auth_str = auth_header.to_str().unwrap_or("");
-            if auth_str.starts_with("Bearer ") {
-                auth_str[7..].to_string()
-            } else {
-                return (
-                    StatusCode::UNAUTHORIZED,
-                    Json(json!({
-                        "success": false,
-                        "message": "Invalid authorization header format"
-                    }))
-                ).into_response();
-            }
-        },
-        None => return (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({
-                "success": false,
-                "message": "No refresh token provided"
-            }))
-        ).into_response(),
+    let auth_header = match headers.get("Authorization").and_then(|header| header.to_str().ok()) {
+        Some(auth_header) => auth_header,
+        None => return AuthError::MissingToken.into_response(),
     };
 
     // Verify refresh token and get claims
-    let claims = match decode_token(refresh_token) {
+    let claims = match extract_token(auth_header) {
         Ok(claims) => claims,
-        Err(_) => return (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({ "error": "Invalid refresh token" }))
-        ).into_response(),
+        Err(e) => return e.into_response(),
     };
 
     // Check if the last login was too long ago
@@ -144,21 +240,12 @@ pub async fn refresh_tokens(headers: HeaderMap) -> Response {
     let max_inactivity = 30 * 24 * 60 * 60; // 30 days in seconds
 
     if current_time - last_activity > max_inactivity {
-        return (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({
-                "error": "Session expired due to inactivity",
-                "code": "INACTIVITY_TIMEOUT"
-            }))
-        ).into_response();
+        return AuthError::SessionExpired.into_response();
     }
 
     // Verify token type
     if !matches!(claims.token_type, TokenType::Refresh) {
-        return (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({ "error": "Invalid token type" }))
-        ).into_response();
+        return AuthError::TokenValidationError("Invalid token type".to_string()).into_response();
     }
 
     // Generate new tokens
@@ -174,11 +261,11 @@ pub async fn refresh_tokens(headers: HeaderMap) -> Response {
 
     (
         StatusCode::OK,
-        Json(json!({
-            "success": true,
-            "message": "Tokens refreshed successfully",
-            "accessToken": new_access_token,
-            "refreshToken": new_refresh_token
-        }))
+        Json(RefreshTokensResponseDto {
+            success: true,
+            message: Some("Tokens refreshed successfully".to_string()),
+            access_token: new_access_token,
+            refresh_token: new_refresh_token,
+        })
     ).into_response()
 }
\ No newline at end of file
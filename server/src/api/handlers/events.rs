@@ -0,0 +1,99 @@
+use std::convert::Infallible;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Extension,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+};
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    Stream, StreamExt,
+};
+use crate::domain::errors::api_error::ApiError;
+use crate::domain::models::auth::JwtUserPayload;
+use crate::domain::models::event::UserEvent;
+use crate::utils::events;
+
+/// Sent instead of the events a slow subscriber missed, the client should
+/// reload the account and its jobs
+const RESYNC_EVENT: &str = "resync";
+
+#[utoipa::path(
+    get,
+    path = "/api/me/events",
+    tag = "user",
+    params(("access_token" = Option<String>, Query, description = "Access token, for clients which can not set the Authorization header")),
+    responses(
+        (status = 200, description = "Server-sent events of the account: account_created, record_added, record_revoked, job_updated and resync", content_type = "text/event-stream", body = String),
+        (status = 401, description = "Missing or invalid token", body = ApiError),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_me_events(
+    Extension(jwt_user): Extension<JwtUserPayload>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let user_id = jwt_user.user_id;
+    let stream = BroadcastStream::new(events::subscribe()).filter_map(move |event| match event {
+        Ok(event) if event.user_id() == user_id => Event::default()
+            .event(event.name())
+            .json_data(&event)
+            .ok()
+            .map(Ok),
+        Ok(_) => None,
+        Err(BroadcastStreamRecvError::Lagged(_)) => Some(Ok(Event::default().event(RESYNC_EVENT).data("{}"))),
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// WebSocket alternative of `/api/me/events`, every event is sent as a JSON text message
+/// with its name in `type`
+#[utoipa::path(
+    get,
+    path = "/api/me/ws",
+    tag = "user",
+    params(("access_token" = Option<String>, Query, description = "Access token, for clients which can not set the Authorization header")),
+    responses(
+        (status = 101, description = "Switching to the WebSocket protocol"),
+        (status = 401, description = "Missing or invalid token", body = ApiError),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_me_ws(
+    Extension(jwt_user): Extension<JwtUserPayload>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| forward_events(socket, jwt_user.user_id))
+}
+
+async fn forward_events(mut socket: WebSocket, user_id: String) {
+    let mut events = events::subscribe();
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let message = match event {
+                    Ok(event) if event.user_id() == user_id => serde_json::to_string::<UserEvent>(&event).ok(),
+                    Ok(_) => None,
+                    Err(RecvError::Lagged(_)) => Some(json!({ "type": RESYNC_EVENT }).to_string()),
+                    Err(RecvError::Closed) => break,
+                };
+                if let Some(message) = message {
+                    if socket.send(Message::Text(message)).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                // pings are answered by axum, anything else from the client is ignored
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
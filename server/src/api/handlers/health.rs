@@ -8,6 +8,15 @@ use serde_json::{json, Value};
 use std::env;
 
 /// Checks the server and database status
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "system",
+    responses(
+        (status = 200, description = "Server and database are up"),
+        (status = 503, description = "Database is unreachable"),
+    )
+)]
 pub async fn health_check() -> (StatusCode, Json<Value>) {
     let db_status = check_database_connection();
 
@@ -0,0 +1,47 @@
+use axum::{
+    extract::{Extension, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use crate::api::extract::{ApiPath};
+use crate::api::dto::response::job_res::JobResponseDto;
+use crate::domain::errors::api_error::{ApiError, ErrorCode};
+use crate::domain::models::auth::JwtUserPayload;
+use crate::services::job_service::JobService;
+
+use super::auth::AppState;
+
+/// Clients opt into asynchronous submission with `Prefer: respond-async` (RFC 7240)
+pub fn prefers_async(headers: &HeaderMap) -> bool {
+    headers.get_all("Prefer")
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .flat_map(|header| header.split(','))
+        .any(|preference| preference.trim().eq_ignore_ascii_case("respond-async"))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/jobs/{job_id}",
+    tag = "job",
+    params(("job_id" = String, Path, description = "Id returned with 202 Accepted")),
+    responses(
+        (status = 200, description = "Current state of the job", body = JobResponseDto),
+        (status = 404, description = "No job with this id for the user", body = ApiError),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_job(
+    State(state): State<AppState>,
+    Extension(jwt_user): Extension<JwtUserPayload>,
+    ApiPath(job_id): ApiPath<String>,
+) -> impl IntoResponse {
+    let job_service = JobService::new(state.user_repo);
+
+    match job_service.get(&job_id) {
+        Ok(Some(job)) if job.user_id == jwt_user.user_id => (StatusCode::OK, Json(job)).into_response(),
+        Ok(_) => ApiError::new(ErrorCode::NotFound, format!("Job {} not found", job_id)).into_response(),
+        Err(e) => ApiError::new(ErrorCode::DatabaseError, e.to_string()).into_response(),
+    }
+}
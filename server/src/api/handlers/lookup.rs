@@ -0,0 +1,53 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use crate::api::extract::{ApiQuery};
+use crate::api::dto::request::lookup_req::LookupRequestDto;
+use crate::api::dto::response::lookup_res::{LookupMatchDto, LookupResponseDto};
+use crate::domain::errors::lookup_errors::LookupError;
+use crate::services::lookup_service::LookupService;
+use crate::domain::errors::api_error::ApiError;
+
+use super::auth::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/api/lookup",
+    tag = "lookup",
+    params(LookupRequestDto),
+    responses(
+        (status = 200, description = "Wallets linked to the handle", body = LookupResponseDto),
+        (status = 400, description = "Unknown provider", body = ApiError),
+    )
+)]
+pub async fn lookup_identity(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<LookupRequestDto>,
+) -> impl IntoResponse {
+    let platform = match state.providers.resolve_platform(&query.provider) {
+        Some(platform) => platform,
+        None => return LookupError::UnknownProvider(query.provider).into_response(),
+    };
+    let lookup_service = LookupService::new(state.prover, state.user_repo);
+
+    match lookup_service.lookup(&platform, &query.handle).await {
+        Ok(matches) => {
+            (StatusCode::OK, Json(LookupResponseDto {
+                platform,
+                handle: query.handle,
+                matches: matches.into_iter().map(|found| LookupMatchDto {
+                    address: found.address,
+                    provider_id: found.provider_id,
+                    handle: found.handle,
+                    proof_timestamp: found.proof_timestamp,
+                    flagged: found.flagged,
+                    record: found.record,
+                }).collect(),
+            })).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
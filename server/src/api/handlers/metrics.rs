@@ -0,0 +1,22 @@
+use axum::{
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use crate::utils::metrics;
+
+/// Exposes server metrics for Prometheus
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "system",
+    responses(
+        (status = 200, description = "Prometheus text exposition", content_type = "text/plain"),
+    )
+)]
+pub async fn get_metrics() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render(),
+    )
+}
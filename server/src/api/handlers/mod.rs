@@ -2,3 +2,11 @@ pub mod auth;
 pub mod user;
 pub mod proof;
 pub mod health;
+pub mod provider;
+pub mod lookup;
+pub mod metrics;
+pub mod job;
+pub mod events;
+pub mod webhook;
+pub mod operation;
+pub mod reindex;
\ No newline at end of file
@@ -0,0 +1,113 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use crate::api::extract::{ApiPath, ApiQuery};
+use crate::api::dto::request::operation_req::OperationsRequestDto;
+use crate::api::dto::response::operation_res::{OperationResponseDto, OperationsResponseDto};
+use crate::domain::errors::api_error::{ApiError, ErrorCode};
+use crate::services::operation_service::OperationService;
+
+use super::auth::AppState;
+
+const DEFAULT_OPERATIONS_LIMIT: i64 = 50;
+const MAX_OPERATIONS_LIMIT: i64 = 500;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/operations",
+    tag = "admin",
+    params(OperationsRequestDto),
+    responses(
+        (status = 200, description = "Outbox operations, most recent first", body = OperationsResponseDto),
+        (status = 401, description = "Missing or invalid admin API key", body = ApiError),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn get_operations(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<OperationsRequestDto>,
+) -> impl IntoResponse {
+    let operation_service = OperationService::new(state.prover, state.user_repo);
+    let limit = query.limit.unwrap_or(DEFAULT_OPERATIONS_LIMIT).clamp(1, MAX_OPERATIONS_LIMIT);
+
+    match operation_service.list(query.status, limit) {
+        Ok(operations) => (StatusCode::OK, Json(OperationsResponseDto { operations })).into_response(),
+        Err(e) => ApiError::new(ErrorCode::DatabaseError, e.to_string()).into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/operations/{operation_id}",
+    tag = "admin",
+    params(("operation_id" = String, Path, description = "Id of the operation")),
+    responses(
+        (status = 200, description = "Current state of the operation", body = OperationResponseDto),
+        (status = 404, description = "No operation with this id", body = ApiError),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn get_operation(
+    State(state): State<AppState>,
+    ApiPath(operation_id): ApiPath<String>,
+) -> impl IntoResponse {
+    let operation_service = OperationService::new(state.prover, state.user_repo);
+
+    match operation_service.get(&operation_id) {
+        Ok(Some(operation)) => (StatusCode::OK, Json(operation)).into_response(),
+        Ok(None) => ApiError::new(ErrorCode::NotFound, format!("Operation {} not found", operation_id)).into_response(),
+        Err(e) => ApiError::new(ErrorCode::DatabaseError, e.to_string()).into_response(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/operations/{operation_id}/retry",
+    tag = "admin",
+    params(("operation_id" = String, Path, description = "Id of the operation")),
+    responses(
+        (status = 200, description = "Operation scheduled for the next worker run", body = OperationResponseDto),
+        (status = 404, description = "No operation with this id", body = ApiError),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn retry_operation(
+    State(state): State<AppState>,
+    ApiPath(operation_id): ApiPath<String>,
+) -> impl IntoResponse {
+    let operation_service = OperationService::new(state.prover, state.user_repo);
+
+    match operation_service.retry(&operation_id) {
+        Ok(Some(operation)) => (StatusCode::OK, Json(operation)).into_response(),
+        Ok(None) => ApiError::new(ErrorCode::NotFound, format!("Operation {} not found", operation_id)).into_response(),
+        Err(e) => ApiError::new(ErrorCode::DatabaseError, e.to_string()).into_response(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/operations/{operation_id}/compensate",
+    tag = "admin",
+    params(("operation_id" = String, Path, description = "Id of the operation")),
+    responses(
+        (status = 200, description = "Operation compensated, its proof reservation released", body = OperationResponseDto),
+        (status = 400, description = "Operation already finished or its write is on the Prism account", body = ApiError),
+        (status = 404, description = "No operation with this id", body = ApiError),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn compensate_operation(
+    State(state): State<AppState>,
+    ApiPath(operation_id): ApiPath<String>,
+) -> impl IntoResponse {
+    let operation_service = OperationService::new(state.prover, state.user_repo);
+
+    match operation_service.compensate_manually(&operation_id).await {
+        Ok(Some(operation)) => (StatusCode::OK, Json(operation)).into_response(),
+        Ok(None) => ApiError::new(ErrorCode::NotFound, format!("Operation {} not found", operation_id)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
@@ -1,33 +1,85 @@
 use axum::{
     response::{ IntoResponse, Json as AxumJson },
     http as AxumHttp,
-    Json,
-    extract::State,
+    http::HeaderMap,
+    extract::{Extension, State},
 };
-use prism_client::binary::ToBinary;
-use serde_json::json;
+use crate::api::extract::{ApiJson, ApiPath};
 use crate::{
-    api::dto::request::proof_req::{ApplyProofRequestDto, PrepareToApplyProofRequestDto}, 
-    domain::models::user::UserAminoSignedRecord, 
-    entities::user::ProofEntity, 
-    services::proof_service::ReclaimProofValidator, 
-    utils::common::{get_current_time, hash_bytes_sha256}
+    api::dto::request::proof_req::{
+        ApplyProofRequestDto, PrepareToApplyProofRequestDto, PrepareToRevokeProofRequestDto, RevokeProofRequestDto,
+    }, 
+    domain::errors::proof_errors::ProofError,
+    domain::models::{
+        attestation::{Attestation, AttestationClaim},
+        auth::JwtUserPayload,
+        job::JobKind,
+        operation::OperationKind,
+        provider::{Provider, UniquenessPolicy},
+        social_identity::SocialIdentity,
+        user::{IdentityRevocationRecord, UserAminoSignedRecord, UserRecord, IDENTITY_RECORD_VERSION},
+    }, 
+    entities::user::{IdentityEntity, ProofEntity}, 
+    services::proof_service::AttestationValidator, 
+    utils::{common::{get_current_time, hash_bytes_sha256}, handle::normalize_handle}
 };
 use crate::entities::user_repo::UserRepo;
-use crate::api::dto::response::proof_res::{PrepareToApplyProofResponseDto, ApplyProofResponseDto, AppliedProofStatsResponseDto};
+use crate::api::dto::response::proof_res::{
+    PrepareToApplyProofResponseDto, ApplyProofResponseDto, AppliedProofStatsResponseDto,
+    PrepareToRevokeProofResponseDto, RevokeProofResponseDto,
+};
 use crate::services::proof_service::ProofService;
-use crate::services::user_service::UserService;
+use crate::services::user_service::{verify_signed_record, UserService};
+use crate::services::identity_service::IdentityService;
+use crate::services::history_service::HistoryService;
+use crate::services::job_service::JobService;
+use crate::services::operation_service::{OperationEffects, OperationService};
+use crate::services::proof_reservation_service::ProofReservationService;
+use crate::domain::errors::user_errors::UserError;
+use crate::api::dto::response::job_res::JobResponseDto;
+use super::job::prefers_async;
+use crate::domain::errors::api_error::{ApiError, ErrorCode};
 
 use super::auth::AppState;
+use log::error;
+
+// Signed revocations older than this are rejected to prevent replays
+const REVOCATION_MAX_AGE_SECONDS: i64 = 10 * 60;
 
 
+#[utoipa::path(
+    post,
+    path = "/api/proof/prepare",
+    tag = "proof",
+    request_body = PrepareToApplyProofRequestDto,
+    responses(
+        (status = 200, description = "Validated proof data to sign with the wallet", body = PrepareToApplyProofResponseDto),
+        (status = 400, description = "Invalid attestation", body = ApiError),
+        (status = 409, description = "Identity already linked to another wallet", body = ApiError),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn prepare_to_apply_proof(
-    Json(payload): Json<PrepareToApplyProofRequestDto>,
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<PrepareToApplyProofRequestDto>,
 ) -> impl IntoResponse {
+    let attestation = Attestation::from(payload.proof.clone());
+    let provider = match state.providers.ensure_accepts(&payload.provider_id, &attestation) {
+        Ok(provider) => provider.clone(),
+        Err(e) => return e.into_response(),
+    };
+    let identity = match attestation.claim() {
+        Ok(claim) => SocialIdentity::extract(&provider, &claim),
+        Err(e) => return e.into_response(),
+    };
+    let identity_service = IdentityService::new(state.prover, state.user_repo);
+    if let Err(e) = identity_service.check_uniqueness(&provider, &identity, &payload.signer) {
+        return e.into_response();
+    }
     let proof_service = ProofService {
-        data: payload.proof.clone(),
-        provider_id: payload.provider_id.clone(),
-        validator: ReclaimProofValidator,
+        data: attestation,
+        provider,
+        validator: AttestationValidator::from_env(),
     };
     let data_to_sign = match proof_service.validate_and_get_data_to_sign().await {
         Ok(data) => data,   
@@ -38,61 +90,352 @@ pub async fn prepare_to_apply_proof(
     ).into_response()
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/proof",
+    tag = "proof",
+    request_body = ApplyProofRequestDto,
+    params(
+        ("Prefer" = Option<String>, Header, description = "`respond-async` to return before the proof is on Prism"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key and body get the first response replayed"),
+    ),
+    responses(
+        (status = 200, description = "Proof written to the Prism account", body = ApplyProofResponseDto),
+        (status = 202, description = "Proof accepted, follow the returned job", body = JobResponseDto),
+        (status = 400, description = "Invalid attestation or signature, or the signed record does not match the attestation", body = ApiError),
+        (status = 403, description = "Signer does not match the session", body = ApiError),
+        (status = 409, description = "Identity already linked, proof already applied, or a request with the same Idempotency-Key is still running", body = ApiError),
+        (status = 413, description = "Body sent with an Idempotency-Key is larger than 2 MB", body = ApiError),
+        (status = 422, description = "Idempotency-Key reused with a different body", body = ApiError),
+        (status = 500, description = "Prism transaction failed", body = ApiError),
+        (status = 504, description = "Prism transaction was not included in time", body = ApiError),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn apply_proof(
     State(state): State<AppState>,
-    Json(payload): Json<ApplyProofRequestDto>,
+    Extension(jwt_user): Extension<JwtUserPayload>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<ApplyProofRequestDto>,
 ) -> impl IntoResponse {
-    let user_repo: UserRepo = state.user_repo;
-    let user_service = UserService::new(state.prover, payload.signer.clone());
+    if payload.signer != jwt_user.user_id {
+        return ProofError::SignerMismatch(payload.signer).into_response();
+    }
+    let user_repo: UserRepo = state.user_repo.clone();
+    let attestation = Attestation::from(payload.proof.clone());
+    let provider = match state.providers.ensure_accepts(&payload.provider_id, &attestation) {
+        Ok(provider) => provider.clone(),
+        Err(e) => return e.into_response(),
+    };
+    // the signed data must be the record prepared for this attestation
+    let proof_service = ProofService {
+        data: attestation.clone(),
+        provider: provider.clone(),
+        validator: AttestationValidator::from_env(),
+    };
+    if let Err(e) = proof_service.validate_signed_record(&payload.data).await {
+        return e.into_response();
+    }
+    let claim = match attestation.claim() {
+        Ok(claim) => claim,
+        Err(e) => return e.into_response(),
+    };
+    let identity = SocialIdentity::extract(&provider, &claim);
+    let identity_service = IdentityService::new(state.prover.clone(), user_repo.clone());
+    let conflicts = match identity_service.check_uniqueness(&provider, &identity, &payload.signer) {
+        Ok(conflicts) => conflicts,
+        Err(e) => return e.into_response(),
+    };
     let user_amino_signed_record = UserAminoSignedRecord::new(
         payload.public_key.clone(),
         payload.signature.clone(),
         payload.signer.clone(),
         payload.data.clone(),
     );
+    let user_record = match user_amino_signed_record.to_user_record() {
+        Ok(user_record) => user_record,
+        Err(e) => return e.into_response(),
+    };
+    // a signature of another wallet must not reserve the proof for this signer
+    if let Err(e) = verify_signed_record(&user_record) {
+        return e.into_response();
+    }
+    // taken before anything is written, so a proof can only reach Prism once
+    let reservations = ProofReservationService::new(user_repo.clone());
+    let reservation = match reservations.reserve(&payload.signer, &payload.provider_id, &attestation) {
+        Ok(reservation) => reservation,
+        Err(e) => return e.into_response(),
+    };
+    // the database writes are recorded before the Prism write and applied once it is included
+    let operations = OperationService::new(state.prover.clone(), user_repo.clone());
+    let (proof, identity_entity) = match proof_entities(&payload, &provider, &attestation, &claim, &identity, &conflicts) {
+        Ok(entities) => entities,
+        Err(e) => {
+            reservations.release(&reservation);
+            return e.into_response();
+        }
+    };
+    let effects = OperationEffects::ApplyProof {
+        record_hash: hash_bytes_sha256(user_record.user_data.clone()),
+        proof,
+        identity: identity_entity,
+        conflicts: conflicts.clone(),
+        reservation: reservation.clone(),
+    };
+    let operation_id = match operations.begin(OperationKind::ApplyProof, &payload.signer, &effects) {
+        Ok(operation_id) => operation_id,
+        Err(e) => {
+            reservations.release(&reservation);
+            return ApiError::new(ErrorCode::DatabaseError, e.to_string()).into_response();
+        }
+    };
+    let submission = ProofSubmission {
+        operation_id,
+        signer: payload.signer,
+        provider,
+        conflicts,
+        user_record,
+    };
+    let user_service = UserService::new(state.prover.clone(), submission.signer.clone());
 
-    match user_service.add_data_to_user_account(
-        user_amino_signed_record.to_user_record()
-    ).await {
-        Ok(_) => {
-            // add proof to db
-            let proof_clone = payload.proof.clone();
-            let raw_data = proof_clone.encode_to_bytes().unwrap();
-            let raw_data_hash = hash_bytes_sha256(raw_data.clone());
-            let created_at = get_current_time();
-            let mut proof = ProofEntity {
-                user_id: payload.signer.clone(),
-                provider_id: payload.provider_id.clone(),
-                created_at: created_at,
-                raw_data: Some(raw_data.clone()),
-                raw_data_hash: raw_data_hash.clone(),
-            };
-
-            // check if proof is already exists
-            match user_repo.proof_exists_by_hash(&raw_data_hash) {
-                Ok(true) => {
-                    return (AxumHttp::StatusCode::CONFLICT, AxumJson(ApplyProofResponseDto { success: false })).into_response();
-                }
-                Ok(false) => {
-                    // Continue with proof insertion
-                }
-                Err(e) => {
-                    return (AxumHttp::StatusCode::INTERNAL_SERVER_ERROR, AxumJson(ApplyProofResponseDto { success: false })).into_response();
-                }
+    if prefers_async(&headers) {
+        let job_service = JobService::new(user_repo);
+        let job = match job_service.create(JobKind::ApplyProof, &submission.signer) {
+            Ok(job) => job,
+            Err(e) => {
+                operations.abort(&submission.operation_id, &e.to_string());
+                return ApiError::new(ErrorCode::DatabaseError, e.to_string()).into_response();
             }
-            
-            match user_repo.insert_proof(&proof) {
-                Ok(_) => println!("Proof created successfully"),
-                Err(e) => eprintln!("Failed to insert proof: {}", e),
+        };
+        let job_id = job.id.clone();
+        let user_service = user_service.with_job(job_service.clone(), job_id.clone());
+        let user_id = submission.signer.clone();
+        tokio::spawn(async move {
+            match submit_proof(state, user_service, submission).await {
+                Ok(response) => job_service.complete(&job_id, &user_id, &response),
+                Err(e) => job_service.fail(&job_id, &user_id, &e),
             }
-            
-            (AxumHttp::StatusCode::OK, AxumJson(ApplyProofResponseDto { success: true })).into_response()
-        },
+        });
+        return (AxumHttp::StatusCode::ACCEPTED, AxumJson(job)).into_response();
+    }
+
+    match submit_proof(state, user_service, submission).await {
+        Ok(response) => (AxumHttp::StatusCode::OK, AxumJson(response)).into_response(),
         Err(e) => e.into_response(),
     }
 }
 
+/// A validated proof with its recorded operation, ready to be written to Prism
+struct ProofSubmission {
+    operation_id: String,
+    signer: String,
+    provider: Provider,
+    conflicts: Vec<IdentityEntity>,
+    user_record: UserRecord,
+}
+
+/// Rows of an applied proof: the proof itself and the index of the linked handle
+fn proof_entities(
+    payload: &ApplyProofRequestDto,
+    provider: &Provider,
+    attestation: &Attestation,
+    claim: &AttestationClaim,
+    identity: &SocialIdentity,
+    conflicts: &[IdentityEntity],
+) -> Result<(ProofEntity, Option<IdentityEntity>), ProofError> {
+    let created_at = get_current_time();
+    let mut proof = ProofEntity {
+        user_id: payload.signer.clone(),
+        provider_id: payload.provider_id.clone(),
+        created_at: created_at,
+        raw_data: None,
+        raw_data_hash: String::new(),
+        platform: Some(identity.platform.clone()),
+        handle: identity.handle.clone(),
+        social_user_id: identity.user_id.clone(),
+        profile_url: identity.profile_url.clone(),
+        follower_count: identity.follower_count.map(|count| count as i64),
+        record_version: IDENTITY_RECORD_VERSION as i32,
+        revoked_at: None,
+    };
+    proof.set_raw_proof(attestation)?;
+
+    // index the linked handle for reverse lookups, or the user id on platforms without handles
+    let identity_entity = match (identity.uniqueness_key(), attestation.identifier()) {
+        (Some(handle), Ok(proof_identifier)) => Some(IdentityEntity {
+            platform: identity.platform.clone(),
+            normalized_handle: normalize_handle(&identity.platform, handle),
+            user_id: payload.signer.clone(),
+            provider_id: payload.provider_id.clone(),
+            handle: handle.to_string(),
+            social_user_id: identity.user_id.clone(),
+            proof_identifier,
+            proof_timestamp: claim.issued_at,
+            created_at,
+            flagged: provider.uniqueness_policy == UniquenessPolicy::Flag && !conflicts.is_empty(),
+            // only one wallet can hold the handle unless the provider flags shared handles
+            exclusive: provider.uniqueness_policy != UniquenessPolicy::Flag,
+        }),
+        _ => None,
+    };
+
+    Ok((proof, identity_entity))
+}
 
+/// Adds the proof to the Prism account, then commits its operation and transfers the identity
+async fn submit_proof(
+    state: AppState,
+    user_service: UserService,
+    submission: ProofSubmission,
+) -> Result<ApplyProofResponseDto, ApiError> {
+    let ProofSubmission { operation_id, signer, provider, conflicts, user_record } = submission;
+    let user_repo = state.user_repo.clone();
+    let identity_service = IdentityService::new(state.prover.clone(), user_repo.clone());
+    let history_service = HistoryService::new(state.prover.clone(), user_repo.clone(), state.da_layer.clone());
+
+    let operations = OperationService::new(state.prover.clone(), user_repo.clone());
+    let user_service = user_service.with_operation(operations.clone(), operation_id.clone());
+    let confirmed = match user_service.add_data_to_user_account(user_record).await {
+        Ok(confirmed) => confirmed,
+        // the transaction is posted and may still be included, the worker resolves the operation
+        Err(e @ UserError::TransactionTimeout(_)) => return Err(e.into()),
+        Err(e) => {
+            operations.abort(&operation_id, &e.to_string());
+            return Err(e.into());
+        }
+    };
+    operations.commit(&operation_id);
+    let receipt = history_service.confirm(&confirmed).await;
+
+    if provider.uniqueness_policy == UniquenessPolicy::Transfer && !conflicts.is_empty() {
+        match identity_service.transfer(conflicts, &signer).await {
+            Ok(confirmed) => {
+                for confirmed in confirmed {
+                    history_service.confirm(&confirmed).await;
+                }
+            }
+            Err(e) => error!("Failed to transfer identity to {}: {}", signer, e),
+        }
+    }
+
+    Ok(ApplyProofResponseDto { success: true, receipt })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/proof/{provider_id}/revoke/prepare",
+    tag = "proof",
+    params(("provider_id" = String, Path, description = "Provider of the proof to revoke")),
+    request_body = PrepareToRevokeProofRequestDto,
+    responses(
+        (status = 200, description = "Revocation data to sign with the wallet", body = PrepareToRevokeProofResponseDto),
+        (status = 403, description = "Signer does not match the session", body = ApiError),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn prepare_to_revoke_proof(
+    Extension(jwt_user): Extension<JwtUserPayload>,
+    ApiPath(provider_id): ApiPath<String>,
+    ApiJson(payload): ApiJson<PrepareToRevokeProofRequestDto>,
+) -> impl IntoResponse {
+    if payload.signer != jwt_user.user_id {
+        return ProofError::SignerMismatch(payload.signer).into_response();
+    }
+    let revocation = IdentityRevocationRecord::new(provider_id, payload.proof_identifier, get_current_time());
+    let data_to_sign = match serde_json::to_vec(&revocation) {
+        Ok(data) => data,
+        Err(e) => return ProofError::from(e).into_response(),
+    };
+    (AxumHttp::StatusCode::OK, AxumJson(
+        PrepareToRevokeProofResponseDto { data: data_to_sign, signer: payload.signer })
+    ).into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/proof/{provider_id}",
+    tag = "proof",
+    params(("provider_id" = String, Path, description = "Provider of the proof to revoke")),
+    request_body = RevokeProofRequestDto,
+    responses(
+        (status = 200, description = "Revocation written to the Prism account", body = RevokeProofResponseDto),
+        (status = 400, description = "Invalid or expired revocation signature", body = ApiError),
+        (status = 403, description = "Signer does not match the session", body = ApiError),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_proof(
+    State(state): State<AppState>,
+    Extension(jwt_user): Extension<JwtUserPayload>,
+    ApiPath(provider_id): ApiPath<String>,
+    ApiJson(payload): ApiJson<RevokeProofRequestDto>,
+) -> impl IntoResponse {
+    if payload.signer != jwt_user.user_id {
+        return ProofError::SignerMismatch(payload.signer).into_response();
+    }
+    let revocation = match serde_json::from_slice::<IdentityRevocationRecord>(&payload.data) {
+        Ok(revocation) => revocation,
+        Err(e) => return ProofError::InvalidRevocation(e.to_string()).into_response(),
+    };
+    if revocation.record_type != IdentityRevocationRecord::RECORD_TYPE || revocation.provider_id != provider_id {
+        return ProofError::InvalidRevocation("Signed data does not revoke this provider".to_string()).into_response();
+    }
+    if (get_current_time() - revocation.revoked_at).abs() > REVOCATION_MAX_AGE_SECONDS {
+        return ProofError::InvalidRevocation("Revocation is expired".to_string()).into_response();
+    }
+
+    let user_amino_signed_record = UserAminoSignedRecord::new(
+        payload.public_key.clone(),
+        payload.signature.clone(),
+        payload.signer.clone(),
+        payload.data.clone(),
+    );
+    let user_record = match user_amino_signed_record.to_user_record() {
+        Ok(user_record) => user_record,
+        Err(e) => return e.into_response(),
+    };
+    if let Err(e) = verify_signed_record(&user_record) {
+        return e.into_response();
+    }
+    // the proofs are marked revoked once the revocation is included, like applied proofs
+    let operations = OperationService::new(state.prover.clone(), state.user_repo.clone());
+    let effects = OperationEffects::RevokeProof {
+        record_hash: hash_bytes_sha256(user_record.user_data.clone()),
+        revocation,
+    };
+    let operation_id = match operations.begin(OperationKind::RevokeProof, &payload.signer, &effects) {
+        Ok(operation_id) => operation_id,
+        Err(e) => return ApiError::new(ErrorCode::DatabaseError, e.to_string()).into_response(),
+    };
+    let user_service = UserService::new(state.prover.clone(), payload.signer.clone())
+        .with_operation(operations.clone(), operation_id.clone());
+
+    match user_service.add_data_to_user_account(user_record).await {
+        Ok(confirmed) => {
+            operations.commit(&operation_id);
+            let history_service = HistoryService::new(state.prover, state.user_repo, state.da_layer);
+            let receipt = history_service.confirm(&confirmed).await;
+            (AxumHttp::StatusCode::OK, AxumJson(RevokeProofResponseDto { success: true, receipt })).into_response()
+        },
+        // the transaction is posted and may still be included, the worker resolves the operation
+        Err(e @ UserError::TransactionTimeout(_)) => e.into_response(),
+        Err(e) => {
+            operations.abort(&operation_id, &e.to_string());
+            e.into_response()
+        }
+    }
+}
+
+
+#[utoipa::path(
+    get,
+    path = "/api/proof-stats",
+    tag = "proof",
+    responses(
+        (status = 200, description = "Number of applied proofs per provider", body = AppliedProofStatsResponseDto),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
 pub async fn get_applied_proof_stats(
     State(state): State<AppState>,
 ) -> impl IntoResponse {
@@ -103,9 +446,6 @@ pub async fn get_applied_proof_stats(
             AxumHttp::StatusCode::OK,
             AxumJson(AppliedProofStatsResponseDto { stats }),
         ).into_response(),
-        Err(err) => (
-            AxumHttp::StatusCode::INTERNAL_SERVER_ERROR,
-            AxumJson(json!({ "error": err.to_string() })),
-        ).into_response(),
+        Err(err) => ApiError::new(ErrorCode::DatabaseError, err.to_string()).into_response(),
     }
 }
\ No newline at end of file
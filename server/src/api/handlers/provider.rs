@@ -0,0 +1,45 @@
+use axum::{
+    response::IntoResponse,
+    http::StatusCode,
+    Json,
+    extract::State,
+};
+use std::collections::HashMap;
+use crate::api::dto::response::provider_res::{ProviderResponseDto, ProvidersResponseDto};
+use crate::domain::errors::api_error::{ApiError, ErrorCode};
+
+use super::auth::AppState;
+
+#[utoipa::path(
+    get,
+    path = "/api/providers",
+    tag = "provider",
+    responses(
+        (status = 200, description = "Configured proof providers", body = ProvidersResponseDto),
+        (status = 500, description = "Database error", body = ApiError),
+    )
+)]
+pub async fn get_providers(
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let stats: HashMap<String, i64> = match state.user_repo.get_proof_stats_by_provider_id() {
+        Ok(stats) => stats.into_iter().collect(),
+        Err(err) => return ApiError::new(ErrorCode::DatabaseError, err.to_string()).into_response(),
+    };
+
+    let providers = state.providers.all()
+        .iter()
+        .map(|provider| ProviderResponseDto {
+            id: provider.id.clone(),
+            name: provider.name.clone(),
+            platform: provider.platform.clone(),
+            category: provider.category.clone(),
+            icon_url: provider.icon_url.clone(),
+            enabled: provider.enabled,
+            max_proof_age: provider.max_proof_age,
+            proofs_count: stats.get(&provider.id).copied().unwrap_or(0),
+        })
+        .collect();
+
+    (StatusCode::OK, Json(ProvidersResponseDto { providers })).into_response()
+}
@@ -0,0 +1,36 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use crate::api::extract::{ApiQuery};
+use crate::api::dto::request::reindex_req::ReindexRequestDto;
+use crate::api::dto::response::reindex_res::ReindexResponseDto;
+use crate::domain::errors::api_error::{ApiError, ErrorCode};
+use crate::services::reindex_service::ReindexService;
+
+use super::auth::AppState;
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/reindex",
+    tag = "admin",
+    params(ReindexRequestDto),
+    responses(
+        (status = 200, description = "Differences between the database and the Prism accounts", body = ReindexResponseDto),
+        (status = 401, description = "Missing or invalid admin API key", body = ApiError),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn reindex(
+    State(state): State<AppState>,
+    ApiQuery(query): ApiQuery<ReindexRequestDto>,
+) -> impl IntoResponse {
+    let reindex_service = ReindexService::new(state.prover, state.user_repo, state.da_layer);
+
+    match reindex_service.run(!query.apply.unwrap_or(false)).await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => ApiError::new(ErrorCode::DatabaseError, e.to_string()).into_response(),
+    }
+}
@@ -1,43 +1,154 @@
 use axum::{
-    extract::{Extension, Path, State}, http::StatusCode, response::IntoResponse, Json
+    extract::{Extension, State}, http::StatusCode, response::IntoResponse, Json
 };
+use crate::api::extract::{ApiPath, ApiQuery};
+use crate::api::dto::request::user_req::UserQueryDto;
 use crate::services::user_service::UserService;
 use crate::domain::models::auth::JwtUserPayload;
-use crate::api::dto::response::user_res::UserDataResponseDto;
+use crate::domain::models::user::UserReadOptions;
+use crate::api::dto::response::user_res::{
+    UserDataResponseDto, UserHistoryEntryDto, UserHistoryResponseDto, UserProofResponseDto,
+};
+use crate::services::history_service::HistoryService;
+use crate::domain::errors::api_error::ApiError;
 
 use super::auth::AppState;
 
+#[utoipa::path(
+    get,
+    path = "/api/user/{user_id}",
+    tag = "user",
+    params(("user_id" = String, Path, description = "Wallet address"), UserQueryDto),
+    responses(
+        (status = 200, description = "Verified identity records of the user", body = UserDataResponseDto),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 422, description = "Strict read found unrecognized records", body = ApiError),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_user(
     State(state): State<AppState>,
-    Path(user_id): Path<String>, // Extract user_id from the URL path
+    ApiPath(user_id): ApiPath<String>, // Extract user_id from the URL path
+    ApiQuery(query): ApiQuery<UserQueryDto>,
 ) -> impl IntoResponse {
     let user_service = UserService::new(state.prover, user_id);
 
-    match user_service.get_user().await {
+    match user_service.get_user_with_options(UserReadOptions {
+        include_revoked: query.include_revoked,
+        strict: query.strict,
+    }).await {
         Ok(user) => {
             (StatusCode::OK, Json(UserDataResponseDto {
                 id: user.id,
                 identity_records: user.identity_records,
+                unrecognized_records: user.unrecognized_records,
             })).into_response()
         }
         Err(e) => e.into_response(),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/me",
+    tag = "user",
+    params(UserQueryDto),
+    responses(
+        (status = 200, description = "Verified identity records of the logged in user", body = UserDataResponseDto),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 422, description = "Strict read found unrecognized records", body = ApiError),
+    ),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_me(
     State(state): State<AppState>,
     Extension(jwt_user): Extension<JwtUserPayload>,
+    ApiQuery(query): ApiQuery<UserQueryDto>,
 ) -> impl IntoResponse {
     let user_service = UserService::new(state.prover, jwt_user.user_id);
     
-    match user_service.get_user().await {
+    match user_service.get_user_with_options(UserReadOptions {
+        include_revoked: query.include_revoked,
+        strict: query.strict,
+    }).await {
         Ok(user) => {
             
             (StatusCode::OK, Json(UserDataResponseDto {
                 id: user.id,
-                identity_records: user.identity_records
+                identity_records: user.identity_records,
+                unrecognized_records: user.unrecognized_records,
+            })).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/user/{user_id}/history",
+    tag = "user",
+    params(("user_id" = String, Path, description = "Wallet address")),
+    responses(
+        (status = 200, description = "Every record of the account in write order", body = UserHistoryResponseDto),
+        (status = 404, description = "Account not found", body = ApiError),
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_user_history(
+    State(state): State<AppState>,
+    ApiPath(user_id): ApiPath<String>,
+) -> impl IntoResponse {
+    let history_service = HistoryService::new(state.prover, state.user_repo, state.da_layer);
+
+    match history_service.get_history(&user_id).await {
+        Ok(entries) => {
+            (StatusCode::OK, Json(UserHistoryResponseDto {
+                id: user_id,
+                entries: entries.into_iter().map(|entry| UserHistoryEntryDto {
+                    index: entry.index,
+                    record_type: entry.record.as_ref()
+                        .map_or("unrecognized", |record| record.record_type())
+                        .to_string(),
+                    prism_height: entry.prism_height,
+                    status: entry.status,
+                    signer_key: entry.signer_key,
+                    signer: entry.signer,
+                    signature_valid: entry.signature_valid,
+                    record: entry.record.as_ref().ok().cloned(),
+                    unrecognized: entry.record.err(),
+                }).collect(),
             })).into_response()
         }
         Err(e) => e.into_response(),
     }
-}
\ No newline at end of file
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/user/{user_id}/proof",
+    tag = "user",
+    params(("user_id" = String, Path, description = "Wallet address")),
+    responses(
+        (status = 200, description = "Account bundle with its JMT inclusion proof", body = UserProofResponseDto),
+        (status = 404, description = "Account not found", body = ApiError),
+        (status = 500, description = "Prism read failed", body = ApiError),
+    )
+)]
+pub async fn get_user_proof(
+    State(state): State<AppState>,
+    ApiPath(user_id): ApiPath<String>,
+) -> impl IntoResponse {
+    let user_service = UserService::new(state.prover, user_id.clone());
+
+    match user_service.get_account_proof().await {
+        Ok(bundle) => {
+            (StatusCode::OK, Json(UserProofResponseDto {
+                id: user_id,
+                account: bundle.account,
+                records: bundle.records,
+                proof: bundle.proof,
+            })).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
@@ -0,0 +1,143 @@
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use crate::api::extract::{ApiJson, ApiPath, ApiQuery};
+use crate::api::dto::request::webhook_req::{CreateWebhookRequestDto, WebhookDeliveriesRequestDto};
+use crate::api::dto::response::webhook_res::{
+    WebhookDeliveriesResponseDto, WebhookDeliveryDto, WebhookResponseDto, WebhooksResponseDto,
+};
+use crate::domain::errors::api_error::ApiError;
+use crate::domain::models::auth::IntegratorPayload;
+use crate::services::webhook_service::{WebhookService, DEFAULT_DELIVERIES_LIMIT};
+
+use super::auth::AppState;
+
+/// Upper bound of the `limit` of the delivery log
+const MAX_DELIVERIES_LIMIT: i64 = 500;
+
+#[utoipa::path(
+    post,
+    path = "/api/webhooks",
+    tag = "webhook",
+    request_body = CreateWebhookRequestDto,
+    responses(
+        (status = 201, description = "Registered webhook with its signing secret", body = WebhookResponseDto),
+        (status = 400, description = "Invalid URL", body = ApiError),
+        (status = 401, description = "Missing or invalid API key", body = ApiError),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Extension(integrator): Extension<IntegratorPayload>,
+    ApiJson(body): ApiJson<CreateWebhookRequestDto>,
+) -> impl IntoResponse {
+    let webhook_service = WebhookService::new(state.user_repo);
+
+    match webhook_service.create(&integrator.name, body).await {
+        Ok(webhook) => (StatusCode::CREATED, Json(webhook)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/webhooks",
+    tag = "webhook",
+    responses(
+        (status = 200, description = "Webhooks of the integrator", body = WebhooksResponseDto),
+        (status = 401, description = "Missing or invalid API key", body = ApiError),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn get_webhooks(
+    State(state): State<AppState>,
+    Extension(integrator): Extension<IntegratorPayload>,
+) -> impl IntoResponse {
+    let webhook_service = WebhookService::new(state.user_repo);
+
+    match webhook_service.list(&integrator.name) {
+        Ok(webhooks) => (StatusCode::OK, Json(WebhooksResponseDto { webhooks })).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{webhook_id}",
+    tag = "webhook",
+    params(("webhook_id" = String, Path, description = "Id of the webhook")),
+    responses(
+        (status = 204, description = "Webhook and its delivery log deleted"),
+        (status = 404, description = "No webhook with this id for the integrator", body = ApiError),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Extension(integrator): Extension<IntegratorPayload>,
+    ApiPath(webhook_id): ApiPath<String>,
+) -> impl IntoResponse {
+    let webhook_service = WebhookService::new(state.user_repo);
+
+    match webhook_service.delete(&integrator.name, &webhook_id) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/webhooks/{webhook_id}/deliveries",
+    tag = "webhook",
+    params(("webhook_id" = String, Path, description = "Id of the webhook"), WebhookDeliveriesRequestDto),
+    responses(
+        (status = 200, description = "Delivery log, most recent first", body = WebhookDeliveriesResponseDto),
+        (status = 404, description = "No webhook with this id for the integrator", body = ApiError),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn get_webhook_deliveries(
+    State(state): State<AppState>,
+    Extension(integrator): Extension<IntegratorPayload>,
+    ApiPath(webhook_id): ApiPath<String>,
+    ApiQuery(query): ApiQuery<WebhookDeliveriesRequestDto>,
+) -> impl IntoResponse {
+    let webhook_service = WebhookService::new(state.user_repo);
+    let limit = query.limit.unwrap_or(DEFAULT_DELIVERIES_LIMIT).clamp(1, MAX_DELIVERIES_LIMIT);
+
+    match webhook_service.deliveries(&integrator.name, &webhook_id, limit) {
+        Ok(deliveries) => (StatusCode::OK, Json(WebhookDeliveriesResponseDto { deliveries })).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/webhooks/{webhook_id}/deliveries/{delivery_id}/redeliver",
+    tag = "webhook",
+    params(
+        ("webhook_id" = String, Path, description = "Id of the webhook"),
+        ("delivery_id" = String, Path, description = "Id of the delivery, also the payload id"),
+    ),
+    responses(
+        (status = 200, description = "Delivery after the new attempt", body = WebhookDeliveryDto),
+        (status = 404, description = "No such webhook or delivery for the integrator", body = ApiError),
+    ),
+    security(("api_key" = []))
+)]
+pub async fn redeliver_webhook_delivery(
+    State(state): State<AppState>,
+    Extension(integrator): Extension<IntegratorPayload>,
+    ApiPath((webhook_id, delivery_id)): ApiPath<(String, String)>,
+) -> impl IntoResponse {
+    let webhook_service = WebhookService::new(state.user_repo);
+
+    match webhook_service.redeliver(&integrator.name, &webhook_id, &delivery_id).await {
+        Ok(delivery) => (StatusCode::OK, Json(delivery)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
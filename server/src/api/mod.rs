@@ -1,4 +1,6 @@
 pub mod dto;
+pub mod extract;
 pub mod handlers;
+pub mod openapi;
 pub mod routes;
 pub mod server;
\ No newline at end of file
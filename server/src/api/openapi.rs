@@ -0,0 +1,163 @@
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+use crate::api::dto::request::{
+    auth_req::{AuthWalletRequestDto, PrepareAuthRequestDto},
+    proof_req::{
+        ApplyProofRequestDto, PrepareToApplyProofRequestDto, PrepareToRevokeProofRequestDto,
+        RevokeProofRequestDto,
+    },
+    webhook_req::CreateWebhookRequestDto,
+};
+use crate::api::dto::response::{
+    auth_res::{AuthWalletResponseDto, PrepareAuthDataResponseDto, RefreshTokensResponseDto},
+    job_res::JobResponseDto,
+    operation_res::{OperationResponseDto, OperationsResponseDto},
+    reindex_res::{ReindexDifferenceDto, ReindexResponseDto},
+    lookup_res::{LookupMatchDto, LookupResponseDto},
+    proof_res::{
+        AppliedProofStatsResponseDto, ApplyProofResponseDto, PrepareToApplyProofResponseDto,
+        PrepareToRevokeProofResponseDto, RevokeProofResponseDto,
+    },
+    provider_res::{ProviderResponseDto, ProvidersResponseDto},
+    user_res::{UserDataResponseDto, UserHistoryEntryDto, UserHistoryResponseDto, UserProofResponseDto},
+    webhook_res::{WebhookDeliveriesResponseDto, WebhookDeliveryDto, WebhookResponseDto, WebhooksResponseDto},
+};
+use crate::api::handlers;
+use crate::domain::errors::api_error::{ApiError, ErrorCode};
+use crate::domain::models::{
+    account_state::RecordStatus,
+    attestation::AttestationKind,
+    job::{JobKind, JobStatus},
+    operation::{OperationKind, OperationStatus},
+    receipt::TransactionReceipt,
+    reindex::ReindexDifferenceKind,
+    social_identity::SocialIdentity,
+    user::{RecordSigner, UnrecognizedRecord, UserIdentityRecord, VerifiedIdentityRecord},
+    webhook::{DeliveryStatus, WebhookEventType, WebhookPayload},
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Weave API", description = "Social identity proofs stored on Prism accounts"),
+    paths(
+        handlers::auth::prepare_auth_data,
+        handlers::auth::auth_wallet,
+        handlers::auth::refresh_tokens,
+        handlers::proof::prepare_to_apply_proof,
+        handlers::proof::apply_proof,
+        handlers::proof::prepare_to_revoke_proof,
+        handlers::proof::revoke_proof,
+        handlers::proof::get_applied_proof_stats,
+        handlers::user::get_me,
+        handlers::user::get_user,
+        handlers::user::get_user_history,
+        handlers::user::get_user_proof,
+        handlers::provider::get_providers,
+        handlers::lookup::lookup_identity,
+        handlers::job::get_job,
+        handlers::events::get_me_events,
+        handlers::events::get_me_ws,
+        handlers::webhook::create_webhook,
+        handlers::webhook::get_webhooks,
+        handlers::webhook::delete_webhook,
+        handlers::webhook::get_webhook_deliveries,
+        handlers::webhook::redeliver_webhook_delivery,
+        handlers::operation::get_operations,
+        handlers::operation::get_operation,
+        handlers::operation::retry_operation,
+        handlers::operation::compensate_operation,
+        handlers::reindex::reindex,
+        handlers::health::health_check,
+        handlers::metrics::get_metrics,
+    ),
+    components(schemas(
+        ApiError,
+        ErrorCode,
+        PrepareAuthRequestDto,
+        AuthWalletRequestDto,
+        PrepareAuthDataResponseDto,
+        AuthWalletResponseDto,
+        RefreshTokensResponseDto,
+        PrepareToApplyProofRequestDto,
+        ApplyProofRequestDto,
+        PrepareToRevokeProofRequestDto,
+        RevokeProofRequestDto,
+        PrepareToApplyProofResponseDto,
+        ApplyProofResponseDto,
+        PrepareToRevokeProofResponseDto,
+        RevokeProofResponseDto,
+        AppliedProofStatsResponseDto,
+        UserDataResponseDto,
+        UserHistoryEntryDto,
+        UserHistoryResponseDto,
+        UserProofResponseDto,
+        ProviderResponseDto,
+        ProvidersResponseDto,
+        LookupMatchDto,
+        LookupResponseDto,
+        JobResponseDto,
+        JobKind,
+        JobStatus,
+        CreateWebhookRequestDto,
+        WebhookResponseDto,
+        WebhooksResponseDto,
+        WebhookDeliveryDto,
+        WebhookDeliveriesResponseDto,
+        WebhookPayload,
+        WebhookEventType,
+        DeliveryStatus,
+        OperationResponseDto,
+        OperationsResponseDto,
+        OperationKind,
+        OperationStatus,
+        ReindexResponseDto,
+        ReindexDifferenceDto,
+        ReindexDifferenceKind,
+        TransactionReceipt,
+        RecordStatus,
+        RecordSigner,
+        AttestationKind,
+        SocialIdentity,
+        UserIdentityRecord,
+        VerifiedIdentityRecord,
+        UnrecognizedRecord,
+    )),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "auth", description = "Wallet login and token refresh"),
+        (name = "proof", description = "Applying and revoking identity proofs"),
+        (name = "user", description = "Reading identity records of Prism accounts"),
+        (name = "provider", description = "Configured proof providers"),
+        (name = "lookup", description = "Reverse lookup from social handle to wallet"),
+        (name = "job", description = "Asynchronous Prism submissions"),
+        (name = "webhook", description = "Webhooks of integrators and their delivery log"),
+        (name = "admin", description = "Outbox operations and reindexing the database from Prism"),
+        (name = "system", description = "Health and metrics"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the JWT bearer scheme used by the protected routes, and the
+/// API key of the integrator and admin routes
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Api-Key"))),
+        );
+    }
+}
@@ -1,20 +1,42 @@
 use axum::{
     routing::post,
     routing::get,
+    routing::delete,
     Router,
     middleware,
 };
+use tower_http::catch_panic::CatchPanicLayer;
 use tower_http::cors::{CorsLayer, Any};
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 use http::{
     header::HeaderName,
     method::Method,
     HeaderValue,
 };
-use crate::middleware::auth::auth_middleware;
-use crate::api::handlers::proof::{prepare_to_apply_proof, apply_proof, get_applied_proof_stats};
-use crate::api::handlers::user::{get_user, get_me};
+use crate::middleware::auth::{
+    admin_auth_middleware, auth_middleware, integrator_auth_middleware, stream_auth_middleware, API_KEY_HEADER,
+};
+use crate::middleware::catch_panic::handle_panic;
+use crate::middleware::idempotency::{idempotency_middleware, IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAYED_HEADER};
+use crate::middleware::request_id::{request_id_middleware, REQUEST_ID_HEADER};
+use crate::api::handlers::proof::{
+    prepare_to_apply_proof, apply_proof, get_applied_proof_stats, prepare_to_revoke_proof, revoke_proof,
+};
+use crate::api::handlers::user::{get_user, get_me, get_user_history, get_user_proof};
 use crate::api::handlers::auth::{auth_wallet, refresh_tokens, prepare_auth_data};
 use crate::api::handlers::health::health_check;
+use crate::api::handlers::provider::get_providers;
+use crate::api::handlers::lookup::lookup_identity;
+use crate::api::handlers::metrics::get_metrics;
+use crate::api::handlers::job::get_job;
+use crate::api::handlers::events::{get_me_events, get_me_ws};
+use crate::api::handlers::webhook::{
+    create_webhook, get_webhooks, delete_webhook, get_webhook_deliveries, redeliver_webhook_delivery,
+};
+use crate::api::handlers::operation::{compensate_operation, get_operations, get_operation, retry_operation};
+use crate::api::handlers::reindex::reindex;
+use crate::api::openapi::ApiDoc;
 
 use super::handlers::auth::AppState;
 
@@ -27,26 +49,74 @@ pub fn create_router(state: AppState) -> Router {
         .allow_headers(vec![
             HeaderName::from_static("content-type"),
             HeaderName::from_static("authorization"),
+            HeaderName::from_static(REQUEST_ID_HEADER),
+            HeaderName::from_static("prefer"),
+            HeaderName::from_static(API_KEY_HEADER),
+            HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+        ])
+        .expose_headers(vec![
+            HeaderName::from_static(REQUEST_ID_HEADER),
+            HeaderName::from_static(IDEMPOTENT_REPLAYED_HEADER),
         ])
         .allow_credentials(true);
 
     let public_routes = Router::new()
         .route("/api/auth/prepare", post(prepare_auth_data))
-        .route("/api/auth", post(auth_wallet))
+        .route(
+            "/api/auth",
+            post(auth_wallet).layer(middleware::from_fn_with_state(state.clone(), idempotency_middleware)),
+        )
         .route("/api/auth/refresh", post(refresh_tokens))
         .route("/api/proof-stats", get(get_applied_proof_stats))
-        .route("/health", get(health_check));
+        .route("/api/providers", get(get_providers))
+        .route("/api/lookup", get(lookup_identity))
+        .route("/api/user/:user_id/proof", get(get_user_proof))
+        .route("/health", get(health_check))
+        .route("/metrics", get(get_metrics))
+        .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", ApiDoc::openapi()));
 
     let protected_routes = Router::new()
         .route("/api/proof/prepare", post(prepare_to_apply_proof))
-        .route("/api/proof", post(apply_proof))
+        .route(
+            "/api/proof",
+            post(apply_proof).layer(middleware::from_fn_with_state(state.clone(), idempotency_middleware)),
+        )
+        .route("/api/proof/:provider_id/revoke/prepare", post(prepare_to_revoke_proof))
+        .route("/api/proof/:provider_id", delete(revoke_proof))
         .route("/api/me", get(get_me))
         .route("/api/user/:user_id", get(get_user))
+        .route("/api/user/:user_id/history", get(get_user_history))
+        .route("/api/jobs/:job_id", get(get_job))
         .layer(middleware::from_fn(auth_middleware));
 
+    let stream_routes = Router::new()
+        .route("/api/me/events", get(get_me_events))
+        .route("/api/me/ws", get(get_me_ws))
+        .layer(middleware::from_fn(stream_auth_middleware));
+
+    let integrator_routes = Router::new()
+        .route("/api/webhooks", post(create_webhook).get(get_webhooks))
+        .route("/api/webhooks/:webhook_id", delete(delete_webhook))
+        .route("/api/webhooks/:webhook_id/deliveries", get(get_webhook_deliveries))
+        .route("/api/webhooks/:webhook_id/deliveries/:delivery_id/redeliver", post(redeliver_webhook_delivery))
+        .layer(middleware::from_fn_with_state(state.clone(), integrator_auth_middleware));
+
+    let admin_routes = Router::new()
+        .route("/api/admin/operations", get(get_operations))
+        .route("/api/admin/operations/:operation_id", get(get_operation))
+        .route("/api/admin/operations/:operation_id/retry", post(retry_operation))
+        .route("/api/admin/operations/:operation_id/compensate", post(compensate_operation))
+        .route("/api/admin/reindex", post(reindex))
+        .layer(middleware::from_fn(admin_auth_middleware));
+
     Router::new()
         .merge(public_routes)
         .merge(protected_routes)
+        .merge(stream_routes)
+        .merge(integrator_routes)
+        .merge(admin_routes)
+        .layer(CatchPanicLayer::custom(handle_panic))
+        .layer(middleware::from_fn(request_id_middleware))
         .layer(cors)
         .with_state(state)
 }
@@ -0,0 +1,43 @@
+use std::env;
+use anyhow::{anyhow, Result};
+
+use crate::utils::common::hash_bytes_sha256;
+
+/// Integrators allowed to register webhooks, identified by their API key
+pub struct IntegratorRegistry {
+    /// Integrator names with the sha256 of their key, so lookups do not
+    /// compare the secret itself
+    integrators: Vec<(String, String)>,
+}
+
+impl IntegratorRegistry {
+    /// Loads `INTEGRATOR_API_KEYS`, comma separated `name:api_key` pairs.
+    /// Without the variable no integrator can authenticate.
+    pub fn from_env() -> Result<Self> {
+        match env::var("INTEGRATOR_API_KEYS") {
+            Ok(value) => Self::parse(&value),
+            Err(_) => Ok(Self { integrators: Vec::new() }),
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        let mut integrators = Vec::new();
+        for pair in value.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (name, api_key) = pair.split_once(':')
+                .ok_or_else(|| anyhow!("Invalid INTEGRATOR_API_KEYS entry {}, expected name:api_key", pair))?;
+            if name.is_empty() || api_key.is_empty() {
+                return Err(anyhow!("Invalid INTEGRATOR_API_KEYS entry {}, expected name:api_key", pair));
+            }
+            integrators.push((name.to_string(), hash_bytes_sha256(api_key.as_bytes().to_vec())));
+        }
+        Ok(Self { integrators })
+    }
+
+    /// Name of the integrator owning the key
+    pub fn authenticate(&self, api_key: &str) -> Option<&str> {
+        let hash = hash_bytes_sha256(api_key.as_bytes().to_vec());
+        self.integrators.iter()
+            .find(|(_, key_hash)| *key_hash == hash)
+            .map(|(name, _)| name.as_str())
+    }
+}
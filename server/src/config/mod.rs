@@ -0,0 +1,2 @@
+pub mod provider_config;
+pub mod integrator_config;
\ No newline at end of file
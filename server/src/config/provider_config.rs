@@ -0,0 +1,144 @@
+use std::env;
+use std::fs;
+use anyhow::{anyhow, Result};
+
+use crate::domain::errors::proof_errors::ProofError;
+use crate::domain::models::attestation::Attestation;
+use crate::domain::models::provider::Provider;
+use crate::utils::common::get_current_time;
+
+pub static DEFAULT_PROVIDERS_CONFIG_PATH: &str = "providers.json";
+
+pub struct ProviderRegistry {
+    providers: Vec<Provider>,
+}
+
+impl ProviderRegistry {
+    pub fn new(providers: Vec<Provider>) -> Self {
+        Self { providers }
+    }
+
+    /// Loads providers from the JSON file set in `PROVIDERS_CONFIG_PATH`
+    pub fn from_env() -> Result<Self> {
+        let path = env::var("PROVIDERS_CONFIG_PATH")
+            .unwrap_or_else(|_| DEFAULT_PROVIDERS_CONFIG_PATH.to_string());
+        Self::load(&path)
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read providers config {}: {}", path, e))?;
+        let providers = serde_json::from_str::<Vec<Provider>>(&content)
+            .map_err(|e| anyhow!("Failed to parse providers config {}: {}", path, e))?;
+        Ok(Self::new(providers))
+    }
+
+    pub fn all(&self) -> &[Provider] {
+        &self.providers
+    }
+
+    pub fn get(&self, provider_id: &str) -> Option<&Provider> {
+        self.providers.iter().find(|provider| provider.id == provider_id)
+    }
+
+    /// Resolves a provider id or a platform name (e.g. `twitter`) to the platform name
+    pub fn resolve_platform(&self, provider: &str) -> Option<String> {
+        if let Some(found) = self.get(provider) {
+            return Some(found.platform.clone());
+        }
+        self.providers.iter()
+            .find(|found| found.platform.eq_ignore_ascii_case(provider))
+            .map(|found| found.platform.clone())
+    }
+
+    /// Checks that the provider is known and enabled, the attestation was issued for it
+    /// and is fresh enough
+    pub fn ensure_accepts(&self, provider_id: &str, attestation: &Attestation) -> Result<&Provider, ProofError> {
+        let provider = self.get(provider_id)
+            .ok_or_else(|| ProofError::UnknownProvider(provider_id.to_string()))?;
+
+        if !provider.enabled {
+            return Err(ProofError::ProviderDisabled(provider_id.to_string()));
+        }
+
+        let claim = attestation.claim()?;
+        if !provider.matches_claim(&claim) {
+            return Err(ProofError::ProviderMismatch(provider_id.to_string()));
+        }
+
+        if let Some(max_proof_age) = provider.max_proof_age {
+            if get_current_time() - claim.issued_at > max_proof_age {
+                return Err(ProofError::ProofTooOld(max_proof_age));
+            }
+        }
+
+        Ok(provider)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+    use super::*;
+    use crate::domain::errors::api_error::ApiError;
+    use crate::domain::models::attestation::{AttestedClaim, SignedClaimAttestation};
+
+    fn registry() -> ProviderRegistry {
+        let providers = serde_json::json!([
+            {
+                "id": "github-id",
+                "name": "Github",
+                "platform": "github",
+                "category": "developer",
+                "icon_url": null,
+                "claim_extraction": { "handle": "context.extractedParameters.username" }
+            },
+            {
+                "id": "linkedin-id",
+                "name": "Linkedin",
+                "platform": "linkedin",
+                "category": "professional",
+                "icon_url": null,
+                "claim_extraction": { "handle": "context.extractedParameters.username" },
+                "attested_providers": ["0xlinkedin"]
+            }
+        ]);
+        ProviderRegistry::new(serde_json::from_value(providers).unwrap())
+    }
+
+    fn attestation(provider: &str, context: Option<&str>) -> Attestation {
+        let claim = AttestedClaim {
+            provider: provider.to_string(),
+            parameters: "{}".to_string(),
+            context: context.map(str::to_string),
+            public_data: None,
+            issued_at: get_current_time(),
+        };
+        Attestation::Signed(SignedClaimAttestation {
+            payload: STANDARD.encode(serde_json::to_vec(&claim).unwrap()),
+            public_key: String::new(),
+            signature: String::new(),
+        })
+    }
+
+    #[test]
+    fn attestations_are_accepted_for_their_own_provider() {
+        let registry = registry();
+        let github = attestation("github", Some(r#"{"extractedParameters":{"username":"alice"}}"#));
+
+        assert_eq!(registry.ensure_accepts("github-id", &github).unwrap().id, "github-id");
+        assert!(registry.ensure_accepts("github-id", &attestation("github-id", None)).is_ok());
+        assert!(registry.ensure_accepts("linkedin-id", &attestation("http", Some(r#"{"providerHash":"0xlinkedin"}"#))).is_ok());
+    }
+
+    #[test]
+    fn github_proofs_are_rejected_as_linkedin() {
+        let github = attestation("github", Some(r#"{"extractedParameters":{"username":"alice"}}"#));
+
+        let result = registry().ensure_accepts("linkedin-id", &github);
+
+        assert!(matches!(result, Err(ProofError::ProviderMismatch(_))));
+        assert_eq!(ApiError::from(result.unwrap_err()).status(), 400);
+    }
+}
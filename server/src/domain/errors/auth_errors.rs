@@ -1,5 +1,6 @@
 use thiserror::Error;
 use prism_client::TransactionError;
+use super::api_error::{ApiError, ErrorCode};
 
 #[derive(Debug, Error)]
 pub enum AuthError {
@@ -23,27 +24,43 @@ pub enum AuthError {
 
     #[error("Failed to prepare auth data: {0}")]
     PrepareAuthDataError(String),
+
+    #[error("No token provided")]
+    MissingToken,
+
+    #[error("Session expired due to inactivity")]
+    SessionExpired,
+
+    #[error("Missing or invalid API key")]
+    InvalidApiKey,
+}
+
+impl AuthError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AuthError::KeyStoreError(_) => ErrorCode::KeyStoreError,
+            AuthError::TokenGenerationError(_) |
+            AuthError::EnvVarError(_) => ErrorCode::InternalError,
+            AuthError::Base64Error(_) |
+            AuthError::PublicKeyError => ErrorCode::InvalidPublicKey,
+            AuthError::TokenValidationError(_) => ErrorCode::InvalidToken,
+            AuthError::PrepareAuthDataError(_) => ErrorCode::InvalidRequest,
+            AuthError::MissingToken |
+            AuthError::InvalidApiKey => ErrorCode::Unauthorized,
+            AuthError::SessionExpired => ErrorCode::SessionExpired,
+        }
+    }
+}
+
+impl From<AuthError> for ApiError {
+    fn from(error: AuthError) -> Self {
+        ApiError::new(error.code(), error.to_string())
+    }
 }
 
 impl axum::response::IntoResponse for AuthError {
     fn into_response(self) -> axum::response::Response {
-        let (status, error_message) = match self {
-            AuthError::TokenGenerationError(_) => (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                self.to_string(),
-            ),
-            AuthError::TokenValidationError(_) |
-            _ => (
-                axum::http::StatusCode::BAD_REQUEST,
-                self.to_string(),
-            ),
-        };
-
-        (
-            status,
-            [(axum::http::header::CONTENT_TYPE, "application/json")],
-            axum::Json(serde_json::json!({ "error": error_message })),
-        ).into_response()
+        ApiError::from(self).into_response()
     }
 }
 
@@ -0,0 +1,46 @@
+use thiserror::Error;
+use super::api_error::{ApiError, ErrorCode};
+
+#[derive(Debug, Error)]
+pub enum LookupError {
+    #[error("Unknown provider: {0}")]
+    UnknownProvider(String),
+
+    #[error("Handle must not be empty")]
+    EmptyHandle,
+
+    #[error("No wallet found for handle {0}")]
+    NotFound(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl LookupError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            LookupError::UnknownProvider(_) => ErrorCode::UnknownProvider,
+            LookupError::EmptyHandle => ErrorCode::InvalidRequest,
+            LookupError::NotFound(_) => ErrorCode::NotFound,
+            LookupError::DatabaseError(_) => ErrorCode::DatabaseError,
+        }
+    }
+}
+
+impl From<LookupError> for ApiError {
+    fn from(error: LookupError) -> Self {
+        ApiError::new(error.code(), error.to_string())
+    }
+}
+
+impl axum::response::IntoResponse for LookupError {
+    fn into_response(self) -> axum::response::Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+impl From<anyhow::Error> for LookupError {
+    fn from(error: anyhow::Error) -> Self {
+        LookupError::DatabaseError(error.to_string())
+    }
+}
@@ -1,3 +1,5 @@
 pub mod auth_errors;
 pub mod user_errors;
-pub mod proof_errors;
\ No newline at end of file
+pub mod lookup_errors;
+pub mod webhook_errors;
+pub use weave_core::errors::{api_error, proof_errors, record_errors};
\ No newline at end of file
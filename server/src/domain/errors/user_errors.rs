@@ -1,5 +1,6 @@
 use thiserror::Error;
 use prism_client::{PrismApiError, TransactionError};
+use super::api_error::{ApiError, ErrorCode};
 
 #[derive(Debug, Error)]
 pub enum UserError {
@@ -12,36 +13,44 @@ pub enum UserError {
     #[error("Transaction error: {0}")]
     TransactionError(String),
 
+    #[error("Transaction was not included within {0} seconds")]
+    TransactionTimeout(u64),
+
     #[error("Invalid signature: {0}")]
     InvalidSignature(String),
+
+    #[error("Account contains {0} unrecognized records")]
+    UnrecognizedRecords(usize),
+}
+
+impl UserError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            UserError::AccountNotFound(_) => ErrorCode::AccountNotFound,
+            UserError::KeyStoreError(_) => ErrorCode::KeyStoreError,
+            UserError::TransactionError(_) => ErrorCode::TransactionFailed,
+            UserError::TransactionTimeout(_) => ErrorCode::TransactionTimeout,
+            UserError::InvalidSignature(_) => ErrorCode::InvalidSignature,
+            UserError::UnrecognizedRecords(_) => ErrorCode::UnrecognizedRecords,
+        }
+    }
+}
+
+impl From<UserError> for ApiError {
+    fn from(error: UserError) -> Self {
+        match &error {
+            // The message alone was returned before, keep it for existing clients
+            UserError::InvalidSignature(message) => ApiError::new(error.code(), message.clone()),
+            UserError::UnrecognizedRecords(count) => ApiError::new(error.code(), error.to_string())
+                .with_details(serde_json::json!({ "count": count })),
+            _ => ApiError::new(error.code(), error.to_string()),
+        }
+    }
 }
 
 impl axum::response::IntoResponse for UserError {
     fn into_response(self) -> axum::response::Response {
-        let (status, error_message) = match self {
-            UserError::AccountNotFound(_) => (
-                axum::http::StatusCode::NOT_FOUND,
-                self.to_string(),
-            ),
-            UserError::KeyStoreError(_) => (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                self.to_string(),
-            ),
-            UserError::TransactionError(_) => (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                self.to_string(),
-            ),
-            UserError::InvalidSignature(message) => (
-                axum::http::StatusCode::BAD_REQUEST,
-                message,
-            ),
-        };
-
-        (
-            status,
-            [(axum::http::header::CONTENT_TYPE, "application/json")],
-            axum::Json(serde_json::json!({ "error": error_message })),
-        ).into_response()
+        ApiError::from(self).into_response()
     }
 }
 
@@ -0,0 +1,46 @@
+use thiserror::Error;
+use super::api_error::{ApiError, ErrorCode};
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Invalid webhook URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("Webhook {0} not found")]
+    NotFound(String),
+
+    #[error("Delivery {0} not found")]
+    DeliveryNotFound(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl WebhookError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            WebhookError::InvalidUrl(_) => ErrorCode::InvalidRequest,
+            WebhookError::NotFound(_) |
+            WebhookError::DeliveryNotFound(_) => ErrorCode::NotFound,
+            WebhookError::DatabaseError(_) => ErrorCode::DatabaseError,
+        }
+    }
+}
+
+impl From<WebhookError> for ApiError {
+    fn from(error: WebhookError) -> Self {
+        ApiError::new(error.code(), error.to_string())
+    }
+}
+
+impl axum::response::IntoResponse for WebhookError {
+    fn into_response(self) -> axum::response::Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+impl From<anyhow::Error> for WebhookError {
+    fn from(error: anyhow::Error) -> Self {
+        WebhookError::DatabaseError(error.to_string())
+    }
+}
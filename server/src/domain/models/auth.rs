@@ -9,4 +9,16 @@ impl JwtUserPayload {
     pub fn new(user_id: String) -> Self {
         Self { user_id }
     }
-}
\ No newline at end of file
+}
+
+/// Integrator authenticated by its API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegratorPayload {
+    pub name: String,
+}
+
+impl IntegratorPayload {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
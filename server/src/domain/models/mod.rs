@@ -1,2 +1,5 @@
-pub mod user;
-pub mod auth;
\ No newline at end of file
+pub mod auth;
+pub use weave_core::models::{
+    account_state, attestation, event, job, operation, provider, receipt, record_codec, reindex, social_identity,
+    user, webhook,
+};
\ No newline at end of file
@@ -1,10 +1,12 @@
-use reclaim_rust_sdk::{ Proof as ReclaimProof };
 use serde::{Serialize, Deserialize};
 use diesel::prelude::*;
 
-use crate::{schema::{proofs, users}, utils::common::hash_bytes_sha256};
+use crate::{
+    domain::{errors::proof_errors::ProofError, models::attestation::Attestation},
+    schema::{idempotency_keys, identities, jobs, operations, proof_reservations, proofs, record_heights, users, webhook_deliveries, webhooks},
+};
 
-#[derive(Insertable, Queryable, Selectable)]
+#[derive(Debug, Serialize, Deserialize, Insertable, Queryable, Selectable, Clone)]
 #[diesel(table_name = users)]
 pub struct UserEntity {
     pub id: String, // signer
@@ -12,9 +14,9 @@ pub struct UserEntity {
     pub created_at: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Associations, Selectable)]
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Associations, Selectable, Clone)]
 #[diesel(table_name = proofs)]
-#[diesel(primary_key(provider_id, user_id))]
+#[diesel(primary_key(raw_data_hash))]
 #[diesel(belongs_to(UserEntity, foreign_key = user_id))]
 #[diesel(check_for_backend(diesel::mysql::Mysql))]
 pub struct ProofEntity {
@@ -22,17 +24,154 @@ pub struct ProofEntity {
     pub user_id: String,
     pub raw_data: Option<Vec<u8>>,
     pub raw_data_hash: String,
-    pub created_at: i64
+    pub created_at: i64,
+    pub platform: Option<String>,
+    pub handle: Option<String>,
+    pub social_user_id: Option<String>,
+    pub profile_url: Option<String>,
+    pub follower_count: Option<i64>,
+    pub record_version: i32,
+    pub revoked_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Associations, Selectable, Clone)]
+#[diesel(table_name = identities)]
+#[diesel(primary_key(platform, normalized_handle, user_id))]
+#[diesel(belongs_to(UserEntity, foreign_key = user_id))]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct IdentityEntity {
+    pub platform: String,
+    pub normalized_handle: String,
+    pub user_id: String,
+    pub provider_id: String,
+    pub handle: String,
+    pub social_user_id: Option<String>,
+    pub proof_identifier: String,
+    pub proof_timestamp: i64,
+    pub created_at: i64,
+    pub flagged: bool,
+    /// Reserves the handle for this wallet, enforced by a unique index on the handle
+    #[serde(default)]
+    pub exclusive: bool,
+}
+
+#[derive(Debug, Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = record_heights)]
+#[diesel(primary_key(user_id, record_index))]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct RecordHeightEntity {
+    pub user_id: String,
+    pub record_index: i32,
+    pub prism_height: i64,
+    pub recorded_at: i64,
+}
+
+#[derive(Debug, Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = jobs)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct JobEntity {
+    pub id: String,
+    pub kind: String,
+    pub status: String,
+    pub user_id: String,
+    pub transaction_hash: Option<String>,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Server instance running the job, see `job_service::instance_id`
+    pub instance_id: String,
+}
+
+#[derive(Debug, Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = webhooks)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct WebhookEntity {
+    pub id: String,
+    pub integrator: String,
+    pub url: String,
+    pub secret: String,
+    pub events: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = webhook_deliveries)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct WebhookDeliveryEntity {
+    pub id: String,
+    pub webhook_id: String,
+    pub event_type: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: Option<i64>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = idempotency_keys)]
+#[diesel(primary_key(scope, owner, idempotency_key))]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct IdempotencyKeyEntity {
+    pub scope: String,
+    pub owner: String,
+    pub idempotency_key: String,
+    pub fingerprint: String,
+    pub status_code: Option<i32>,
+    pub content_type: Option<String>,
+    pub response_body: Option<Vec<u8>>,
+    pub created_at: i64,
+    pub expires_at: i64,
+    /// Server instance running the request, see `job_service::instance_id`
+    pub instance_id: String,
+}
+
+#[derive(Debug, Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = proof_reservations)]
+#[diesel(primary_key(raw_data_hash))]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct ProofReservationEntity {
+    pub raw_data_hash: String,
+    pub proof_identifier: Option<String>,
+    pub user_id: String,
+    pub provider_id: String,
+    pub status: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = operations)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct OperationEntity {
+    pub id: String,
+    pub kind: String,
+    pub status: String,
+    pub user_id: String,
+    pub effects: String,
+    pub transaction_hash: Option<String>,
+    pub transaction_nonce: Option<i64>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
 }
 
 impl ProofEntity {
-    pub fn get_raw_data(&self) -> Result<ReclaimProof, bincode::Error> {
-        bincode::deserialize(self.raw_data.as_ref().unwrap())
+    pub fn get_raw_data(&self) -> Result<Attestation, ProofError> {
+        let raw_data = self.raw_data.as_ref()
+            .ok_or_else(|| ProofError::SerializationError("Proof has no raw data".to_string()))?;
+        Attestation::from_bytes(raw_data)
     }
         
-    pub fn set_raw_proof(&mut self, proof: &ReclaimProof) -> Result<(), bincode::Error> {
-        self.raw_data = Some(bincode::serialize(proof)?);
-        self.raw_data_hash = hash_bytes_sha256(self.raw_data.as_ref().unwrap().clone());
+    pub fn set_raw_proof(&mut self, attestation: &Attestation) -> Result<(), ProofError> {
+        self.raw_data = Some(attestation.to_bytes()?);
+        self.raw_data_hash = attestation.hash()?;
         Ok(())
     }
 }
@@ -1,8 +1,12 @@
 use diesel::prelude::*;
-use crate::schema::{users, proofs};
-use crate::entities::user::{UserEntity, ProofEntity};
+use crate::schema::{users, proofs, identities, record_heights, jobs, webhooks, webhook_deliveries, idempotency_keys, proof_reservations, operations};
+use crate::entities::user::{
+    UserEntity, ProofEntity, IdentityEntity, RecordHeightEntity, JobEntity, WebhookEntity, WebhookDeliveryEntity,
+    IdempotencyKeyEntity, ProofReservationEntity, OperationEntity,
+};
 use std::sync::{Arc, Mutex};
 use diesel::mysql::MysqlConnection;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use anyhow::Result; // For better error handling
 
 
@@ -53,6 +57,17 @@ impl UserRepo {
         Ok(exists)
     }
     
+    /// Whether an indexed identity was linked with the proof
+    pub fn identity_exists_by_proof_identifier(&self, proof_identifier: &str) -> Result<bool> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        let exists = diesel::select(diesel::dsl::exists(
+            identities::table.filter(identities::proof_identifier.eq(proof_identifier)),
+        ))
+        .get_result::<bool>(&mut *conn)?;
+
+        Ok(exists)
+    }
+
     pub fn get_proofs_by_user(&self, user_id: &str) -> Result<Vec<ProofEntity>> {
         let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
         proofs::table
@@ -67,11 +82,510 @@ impl UserRepo {
     
         let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
         
+        // revoked proofs no longer link an account
         proofs::table
+            .filter(proofs::revoked_at.is_null())
             .group_by(proofs::provider_id) // Ensure provider is grouped
             .select((proofs::provider_id, count(proofs::user_id))) // Select provider and count of proofs
             .load::<(String, i64)>(&mut *conn) // Execute query
             .map_err(|e| e.into())
     }
-    
-}
+
+    /// Replaces the wallet's row of the handle. Not a `REPLACE INTO`, which would also
+    /// delete the row of another wallet holding the handle exclusively.
+    pub fn upsert_identity(&self, identity: &IdentityEntity) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::delete(
+                identities::table
+                    .filter(identities::platform.eq(&identity.platform))
+                    .filter(identities::normalized_handle.eq(&identity.normalized_handle))
+                    .filter(identities::user_id.eq(&identity.user_id)),
+            )
+            .execute(conn)?;
+            diesel::insert_into(identities::table)
+                .values(identity)
+                .execute(conn)
+        })
+        .map_err(|e| e.into())
+    }
+
+    /// Marks the rows of other wallets sharing the handle, or frees the handle they hold
+    /// exclusively so it can be transferred
+    pub fn update_conflicting_identity(&self, identity: &IdentityEntity, flagged: bool, exclusive: bool) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::update(
+            identities::table
+                .filter(identities::platform.eq(&identity.platform))
+                .filter(identities::normalized_handle.eq(&identity.normalized_handle))
+                .filter(identities::user_id.eq(&identity.user_id)),
+        )
+        .set((identities::flagged.eq(flagged), identities::exclusive.eq(exclusive)))
+        .execute(&mut *conn)
+        .map_err(|e| e.into())
+    }
+
+    pub fn find_identities_by_handle(&self, platform: &str, normalized_handle: &str) -> Result<Vec<IdentityEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        identities::table
+            .filter(identities::platform.eq(platform))
+            .filter(identities::normalized_handle.eq(normalized_handle))
+            .order(identities::proof_timestamp.desc())
+            .load::<IdentityEntity>(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    /// Identities with the same handle linked to other wallets
+    pub fn find_conflicting_identities(&self, platform: &str, normalized_handle: &str, user_id: &str) -> Result<Vec<IdentityEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        identities::table
+            .filter(identities::platform.eq(platform))
+            .filter(identities::normalized_handle.eq(normalized_handle))
+            .filter(identities::user_id.ne(user_id))
+            .load::<IdentityEntity>(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn delete_identity(&self, platform: &str, normalized_handle: &str, user_id: &str) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::delete(
+            identities::table
+                .filter(identities::platform.eq(platform))
+                .filter(identities::normalized_handle.eq(normalized_handle))
+                .filter(identities::user_id.eq(user_id)),
+        )
+        .execute(&mut *conn)
+        .map_err(|e| e.into())
+    }
+
+    pub fn revoke_proofs(&self, user_id: &str, provider_id: &str, revoked_at: i64) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::update(
+            proofs::table
+                .filter(proofs::user_id.eq(user_id))
+                .filter(proofs::provider_id.eq(provider_id))
+                .filter(proofs::revoked_at.is_null()),
+        )
+        .set(proofs::revoked_at.eq(Some(revoked_at)))
+        .execute(&mut *conn)
+        .map_err(|e| e.into())
+    }
+
+    pub fn revoke_proofs_by_hash(&self, raw_data_hashes: &[String], revoked_at: i64) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::update(
+            proofs::table
+                .filter(proofs::raw_data_hash.eq_any(raw_data_hashes))
+                .filter(proofs::revoked_at.is_null()),
+        )
+        .set(proofs::revoked_at.eq(Some(revoked_at)))
+        .execute(&mut *conn)
+        .map_err(|e| e.into())
+    }
+
+    /// Deletes the provider's identities of the wallet, only the one of the proof when
+    /// `proof_identifier` is set
+    pub fn delete_identities_by_provider(&self, user_id: &str, provider_id: &str, proof_identifier: Option<&str>) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        let mut query = diesel::delete(identities::table)
+            .filter(identities::user_id.eq(user_id))
+            .filter(identities::provider_id.eq(provider_id))
+            .into_boxed();
+        if let Some(proof_identifier) = proof_identifier {
+            query = query.filter(identities::proof_identifier.eq(proof_identifier));
+        }
+        query.execute(&mut *conn).map_err(|e| e.into())
+    }
+
+    pub fn insert_record_heights(&self, heights: &[RecordHeightEntity]) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::insert_or_ignore_into(record_heights::table)
+            .values(heights)
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn get_record_heights(&self, user_id: &str) -> Result<Vec<RecordHeightEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        record_heights::table
+            .filter(record_heights::user_id.eq(user_id))
+            .order(record_heights::record_index.asc())
+            .load::<RecordHeightEntity>(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn insert_job(&self, job: &JobEntity) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::insert_into(jobs::table)
+            .values(job)
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn get_job(&self, id: &str) -> Result<Option<JobEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        jobs::table
+            .filter(jobs::id.eq(id))
+            .first::<JobEntity>(&mut *conn)
+            .optional()
+            .map_err(|e| e.into())
+    }
+
+    pub fn set_job_submitted(&self, id: &str, transaction_hash: &str, updated_at: i64) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::update(jobs::table.filter(jobs::id.eq(id)))
+            .set((
+                jobs::status.eq("submitted"),
+                jobs::transaction_hash.eq(Some(transaction_hash)),
+                jobs::updated_at.eq(updated_at),
+            ))
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    /// Sets the final status with either the result or the error
+    pub fn finish_job(&self, id: &str, status: &str, result: Option<String>, error: Option<String>, updated_at: i64) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::update(jobs::table.filter(jobs::id.eq(id)))
+            .set((
+                jobs::status.eq(status),
+                jobs::result.eq(result),
+                jobs::error.eq(error),
+                jobs::updated_at.eq(updated_at),
+            ))
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn get_submitted_jobs_by_transaction(&self, transaction_hash: &str) -> Result<Vec<JobEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        jobs::table
+            .filter(jobs::transaction_hash.eq(transaction_hash))
+            .filter(jobs::status.eq("submitted"))
+            .load::<JobEntity>(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    /// Fails the jobs of an instance which never submitted their transaction, e.g. after
+    /// a restart dropped their tasks
+    pub fn fail_queued_jobs(&self, instance_id: &str, error: &str, updated_at: i64) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::update(
+            jobs::table
+                .filter(jobs::instance_id.eq(instance_id))
+                .filter(jobs::status.eq("queued"))
+        )
+            .set((
+                jobs::status.eq("failed"),
+                jobs::error.eq(Some(error)),
+                jobs::updated_at.eq(updated_at),
+            ))
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn insert_webhook(&self, webhook: &WebhookEntity) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::insert_into(webhooks::table)
+            .values(webhook)
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn get_webhook(&self, id: &str) -> Result<Option<WebhookEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        webhooks::table
+            .filter(webhooks::id.eq(id))
+            .first::<WebhookEntity>(&mut *conn)
+            .optional()
+            .map_err(|e| e.into())
+    }
+
+    pub fn get_webhooks_by_integrator(&self, integrator: &str) -> Result<Vec<WebhookEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        webhooks::table
+            .filter(webhooks::integrator.eq(integrator))
+            .order(webhooks::created_at.asc())
+            .load::<WebhookEntity>(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn get_all_webhooks(&self) -> Result<Vec<WebhookEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        webhooks::table
+            .load::<WebhookEntity>(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    /// Deletes the webhook, its deliveries are deleted by the foreign key
+    pub fn delete_webhook(&self, id: &str) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::delete(webhooks::table.filter(webhooks::id.eq(id)))
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn insert_webhook_deliveries(&self, deliveries: &[WebhookDeliveryEntity]) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::insert_into(webhook_deliveries::table)
+            .values(deliveries)
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn get_webhook_delivery(&self, id: &str) -> Result<Option<WebhookDeliveryEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        webhook_deliveries::table
+            .filter(webhook_deliveries::id.eq(id))
+            .first::<WebhookDeliveryEntity>(&mut *conn)
+            .optional()
+            .map_err(|e| e.into())
+    }
+
+    /// Most recent deliveries of the webhook first
+    pub fn get_webhook_deliveries(&self, webhook_id: &str, limit: i64) -> Result<Vec<WebhookDeliveryEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        webhook_deliveries::table
+            .filter(webhook_deliveries::webhook_id.eq(webhook_id))
+            .order(webhook_deliveries::created_at.desc())
+            .limit(limit)
+            .load::<WebhookDeliveryEntity>(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    /// Pending deliveries whose next attempt is due at `now`, oldest first
+    pub fn get_due_webhook_deliveries(&self, now: i64, limit: i64) -> Result<Vec<WebhookDeliveryEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        webhook_deliveries::table
+            .filter(webhook_deliveries::status.eq("pending"))
+            .filter(webhook_deliveries::next_attempt_at.le(now))
+            .order(webhook_deliveries::next_attempt_at.asc())
+            .limit(limit)
+            .load::<WebhookDeliveryEntity>(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    /// Stores the outcome of a delivery attempt
+    pub fn update_webhook_delivery(&self, delivery: &WebhookDeliveryEntity) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::update(webhook_deliveries::table.filter(webhook_deliveries::id.eq(&delivery.id)))
+            .set((
+                webhook_deliveries::status.eq(&delivery.status),
+                webhook_deliveries::attempts.eq(delivery.attempts),
+                webhook_deliveries::next_attempt_at.eq(delivery.next_attempt_at),
+                webhook_deliveries::last_status_code.eq(delivery.last_status_code),
+                webhook_deliveries::last_error.eq(&delivery.last_error),
+                webhook_deliveries::updated_at.eq(delivery.updated_at),
+            ))
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    /// Inserts the key unless a row with the same scope, owner and key exists.
+    /// Returns false when the key is taken.
+    pub fn reserve_idempotency_key(&self, key: &IdempotencyKeyEntity) -> Result<bool> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        match diesel::insert_into(idempotency_keys::table).values(key).execute(&mut *conn) {
+            Ok(_) => Ok(true),
+            Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn get_idempotency_key(&self, scope: &str, owner: &str, idempotency_key: &str) -> Result<Option<IdempotencyKeyEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        idempotency_keys::table
+            .filter(idempotency_keys::scope.eq(scope))
+            .filter(idempotency_keys::owner.eq(owner))
+            .filter(idempotency_keys::idempotency_key.eq(idempotency_key))
+            .first::<IdempotencyKeyEntity>(&mut *conn)
+            .optional()
+            .map_err(|e| e.into())
+    }
+
+    /// Stores the response of the request which reserved the key
+    pub fn complete_idempotency_key(
+        &self,
+        scope: &str,
+        owner: &str,
+        idempotency_key: &str,
+        status_code: i32,
+        content_type: Option<String>,
+        response_body: Vec<u8>,
+    ) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::update(
+            idempotency_keys::table
+                .filter(idempotency_keys::scope.eq(scope))
+                .filter(idempotency_keys::owner.eq(owner))
+                .filter(idempotency_keys::idempotency_key.eq(idempotency_key)),
+        )
+        .set((
+            idempotency_keys::status_code.eq(Some(status_code)),
+            idempotency_keys::content_type.eq(content_type),
+            idempotency_keys::response_body.eq(Some(response_body)),
+        ))
+        .execute(&mut *conn)
+        .map_err(|e| e.into())
+    }
+
+    pub fn delete_idempotency_key(&self, scope: &str, owner: &str, idempotency_key: &str) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::delete(
+            idempotency_keys::table
+                .filter(idempotency_keys::scope.eq(scope))
+                .filter(idempotency_keys::owner.eq(owner))
+                .filter(idempotency_keys::idempotency_key.eq(idempotency_key)),
+        )
+        .execute(&mut *conn)
+        .map_err(|e| e.into())
+    }
+
+    /// Deletes the keys past their retention
+    pub fn purge_idempotency_keys(&self, now: i64) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::delete(idempotency_keys::table.filter(idempotency_keys::expires_at.lt(now)))
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    /// Deletes the keys of an instance whose request never completed, e.g. after a restart
+    /// dropped it
+    pub fn release_unfinished_idempotency_keys(&self, instance_id: &str) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::delete(
+            idempotency_keys::table
+                .filter(idempotency_keys::instance_id.eq(instance_id))
+                .filter(idempotency_keys::status_code.is_null())
+        )
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    /// Inserts the reservation unless the hash or the proof identifier is reserved already.
+    /// Returns false when the proof is taken.
+    pub fn reserve_proof(&self, reservation: &ProofReservationEntity) -> Result<bool> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        match diesel::insert_into(proof_reservations::table).values(reservation).execute(&mut *conn) {
+            Ok(_) => Ok(true),
+            Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn set_proof_reservation_status(&self, raw_data_hash: &str, status: &str, updated_at: i64) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::update(proof_reservations::table.filter(proof_reservations::raw_data_hash.eq(raw_data_hash)))
+            .set((
+                proof_reservations::status.eq(status),
+                proof_reservations::updated_at.eq(updated_at),
+            ))
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn delete_proof_reservation(&self, raw_data_hash: &str) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::delete(proof_reservations::table.filter(proof_reservations::raw_data_hash.eq(raw_data_hash)))
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn insert_operation(&self, operation: &OperationEntity) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::insert_into(operations::table)
+            .values(operation)
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn get_operation(&self, id: &str) -> Result<Option<OperationEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        operations::table
+            .filter(operations::id.eq(id))
+            .first::<OperationEntity>(&mut *conn)
+            .optional()
+            .map_err(|e| e.into())
+    }
+
+    /// Most recent operations in one of the statuses first
+    pub fn get_operations_by_status(&self, statuses: &[&str], limit: i64) -> Result<Vec<OperationEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        operations::table
+            .filter(operations::status.eq_any(statuses))
+            .order(operations::created_at.desc())
+            .limit(limit)
+            .load::<OperationEntity>(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    /// Pending and submitted operations whose next check is due at `now`, oldest first
+    pub fn get_due_operations(&self, now: i64, limit: i64) -> Result<Vec<OperationEntity>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        operations::table
+            .filter(operations::status.eq_any(["pending", "submitted"]))
+            .filter(operations::next_attempt_at.le(now))
+            .order(operations::next_attempt_at.asc())
+            .limit(limit)
+            .load::<OperationEntity>(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    /// Stores the progress of an operation
+    pub fn update_operation(&self, operation: &OperationEntity) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::update(operations::table.filter(operations::id.eq(&operation.id)))
+            .set((
+                operations::status.eq(&operation.status),
+                operations::transaction_hash.eq(&operation.transaction_hash),
+                operations::transaction_nonce.eq(operation.transaction_nonce),
+                operations::attempts.eq(operation.attempts),
+                operations::last_error.eq(&operation.last_error),
+                operations::next_attempt_at.eq(operation.next_attempt_at),
+                operations::updated_at.eq(operation.updated_at),
+            ))
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    /// Whether the account has operations the worker or an operator still has to resolve
+    pub fn has_unresolved_operations(&self, user_id: &str) -> Result<bool> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        let count: i64 = operations::table
+            .filter(operations::user_id.eq(user_id))
+            .filter(operations::status.eq_any(["pending", "submitted", "failed"]))
+            .count()
+            .get_result(&mut *conn)?;
+        Ok(count > 0)
+    }
+
+    /// Every account id the database knows of: users and the owners of proofs, identities,
+    /// recorded heights and operations. Prism can not list the accounts of a service,
+    /// so this is what a reindex walks.
+    pub fn get_known_account_ids(&self) -> Result<Vec<String>> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        let mut ids: Vec<String> = users::table.select(users::id).load::<String>(&mut *conn)?;
+        ids.extend(proofs::table.select(proofs::user_id).distinct().load::<String>(&mut *conn)?);
+        ids.extend(identities::table.select(identities::user_id).distinct().load::<String>(&mut *conn)?);
+        ids.extend(record_heights::table.select(record_heights::user_id).distinct().load::<String>(&mut *conn)?);
+        ids.extend(operations::table.select(operations::user_id).distinct().load::<String>(&mut *conn)?);
+        ids.sort();
+        ids.dedup();
+        Ok(ids)
+    }
+
+    pub fn set_proof_revoked_at(&self, raw_data_hash: &str, revoked_at: Option<i64>) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::update(proofs::table.filter(proofs::raw_data_hash.eq(raw_data_hash)))
+            .set(proofs::revoked_at.eq(revoked_at))
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+
+    pub fn delete_proof(&self, raw_data_hash: &str) -> Result<usize> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Failed to lock DB connection: {}", e))?;
+        diesel::delete(proofs::table.filter(proofs::raw_data_hash.eq(raw_data_hash)))
+            .execute(&mut *conn)
+            .map_err(|e| e.into())
+    }
+}
\ No newline at end of file
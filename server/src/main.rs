@@ -1,11 +1,14 @@
 mod middleware;
 mod api;
+mod config;
 mod entities;
 mod domain;
 mod utils;
 mod schema;
 mod services;
 use api::handlers::auth::AppState;
+use config::provider_config::ProviderRegistry;
+use config::integrator_config::IntegratorRegistry;
 use diesel::MysqlConnection;
 use diesel::Connection;
 use keystore_rs::KeyStore;
@@ -83,12 +86,69 @@ fn init_keystore() -> Result<()> {
 
 
 
+/// Subcommands of the binary, `serve` when none is given
+enum Command {
+    Serve,
+    /// Rebuilds the database of the running server from its Prism accounts,
+    /// only reporting the differences unless `apply` is set
+    Reindex { apply: bool },
+}
+
+fn parse_command(mut args: impl Iterator<Item = String>) -> Result<Command> {
+    let command = match args.next().as_deref() {
+        None | Some("serve") => Command::Serve,
+        Some("reindex") => Command::Reindex { apply: false },
+        Some(other) => return Err(anyhow!("Unknown command {}, expected serve or reindex [--apply]", other)),
+    };
+    match (command, args.next().as_deref()) {
+        (command, None) => Ok(command),
+        (Command::Reindex { .. }, Some("--apply")) => Ok(Command::Reindex { apply: true }),
+        (_, Some(other)) => Err(anyhow!("Unknown argument {}", other)),
+    }
+}
+
+/// The Prism node lives in the server process, so the reindex runs there and this
+/// command only triggers it through the admin route and prints the report
+async fn reindex(apply: bool) -> Result<()> {
+    let server_url = env::var("WEAVE_SERVER_URL").unwrap_or_else(|_| "http://127.0.0.1:8080".to_string());
+    let admin_key = env::var("ADMIN_API_KEY").map_err(|_| anyhow!("ADMIN_API_KEY must be set"))?;
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/admin/reindex?apply={}", server_url.trim_end_matches('/'), apply))
+        .header(middleware::auth::API_KEY_HEADER, admin_key)
+        .send()
+        .await?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        return Err(anyhow!("Reindex failed with {}: {}", status, body));
+    }
+    let report: serde_json::Value = serde_json::from_str(&body)?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv().ok();
+    match parse_command(env::args().skip(1))? {
+        Command::Serve => serve().await,
+        Command::Reindex { apply } => reindex(apply).await,
+    }
+}
+
+async fn serve() -> Result<()> {
     init_keystore()?;
     let conn = establish_connection();
     let repo = entities::user_repo::UserRepo::new(conn);
+    match services::job_service::JobService::new(repo.clone()).fail_interrupted() {
+        Ok(0) => {}
+        Ok(count) => println!("Marked {} interrupted jobs as failed", count),
+        Err(e) => eprintln!("Failed to clean up interrupted jobs: {}", e),
+    }
+    // the requests of this instance which reserved these keys are gone, retries must be able to run again
+    if let Err(e) = repo.release_unfinished_idempotency_keys(services::job_service::instance_id()) {
+        eprintln!("Failed to clean up idempotency keys: {}", e);
+    }
 
     std::env::set_var(
         "RUST_LOG",
@@ -98,6 +158,7 @@ async fn main() -> Result<()> {
 
     let db = InMemoryDatabase::new();
     let (da_layer, _, _) = InMemoryDataAvailabilityLayer::new(5);
+    let da_layer = Arc::new(da_layer) as Arc<dyn DataAvailabilityLayer>;
 
 
     let sk = FileStore::new(KEYSTORE_PATH).unwrap()
@@ -121,7 +182,7 @@ async fn main() -> Result<()> {
     let prover = Arc::new(
         Prover::new(
             Arc::new(Box::new(db)),
-            Arc::new(da_layer) as Arc<dyn DataAvailabilityLayer>,
+            da_layer.clone(),
             &cfg,
         )
         .unwrap(),
@@ -134,9 +195,16 @@ async fn main() -> Result<()> {
             log::error!("Error occurred while running prover: {:?}", e);
         }
     });
+    let providers = Arc::new(ProviderRegistry::from_env()?);
+    let integrators = Arc::new(IntegratorRegistry::from_env()?);
+    services::webhook_service::WebhookService::new(repo.clone()).start();
+    services::operation_service::OperationService::new(prover.clone(), repo.clone()).start();
     let state = AppState{
         prover: prover.clone(),
         user_repo: repo,
+        providers,
+        integrators,
+        da_layer,
     };
     let api_server_runner_handle = spawn(async move {
         debug!("registering service");
@@ -1,37 +1,102 @@
+use std::env;
 use axum::{
-    http::{Request, StatusCode, header},
+    extract::{Query, State},
+    http::{Request, header},
     middleware::Next,
     response::Response,
 };
-use crate::domain::models::auth::JwtUserPayload;
-use crate::utils::jwt;
+use serde::Deserialize;
+use crate::domain::errors::auth_errors::AuthError;
+use crate::api::handlers::auth::AppState;
+use crate::domain::models::auth::{IntegratorPayload, JwtUserPayload};
+use crate::utils::{common::hash_bytes_sha256, jwt};
 
 pub async fn auth_middleware<B>(
     mut request: Request<B>,
     next: Next<B>,
-) -> Result<Response, StatusCode> {
+) -> Result<Response, AuthError> {
     let auth_header = request
         .headers()
         .get(header::AUTHORIZATION)
         .and_then(|header| header.to_str().ok())
-        .and_then(|auth_str| {
-            if auth_str.starts_with("Bearer ") {
-                Some(auth_str[7..].to_string())
-            } else {
-                None
-            }
-        });
-
-    match auth_header {
-        Some(token) => {
-            match jwt::decode_token(token) {
-                Ok(claims) => {
-                    request.extensions_mut().insert(JwtUserPayload::new(claims.sub));
-                    Ok(next.run(request).await)
-                }
-                Err(_) => Err(StatusCode::UNAUTHORIZED),
-            }
+        .ok_or(AuthError::MissingToken)?;
+
+    let claims = jwt::extract_token(auth_header)?;
+    request.extensions_mut().insert(JwtUserPayload::new(claims.sub));
+    Ok(next.run(request).await)
+}
+
+#[derive(Deserialize)]
+struct StreamAuthQuery {
+    access_token: Option<String>,
+}
+
+/// Auth of the event stream routes. Browsers can not set headers on `EventSource`
+/// and WebSocket requests, so the access token may also be passed as `access_token`
+/// query parameter.
+pub async fn stream_auth_middleware<B>(
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, AuthError> {
+    let auth_header = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|header| header.to_str().ok());
+
+    let claims = match auth_header {
+        Some(auth_header) => jwt::extract_token(auth_header)?,
+        None => {
+            let token = Query::<StreamAuthQuery>::try_from_uri(request.uri())
+                .ok()
+                .and_then(|query| query.0.access_token)
+                .ok_or(AuthError::MissingToken)?;
+            jwt::decode_token(token)?
         }
-        None => Err(StatusCode::UNAUTHORIZED),
+    };
+    request.extensions_mut().insert(JwtUserPayload::new(claims.sub));
+    Ok(next.run(request).await)
+}
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Auth of the integrator routes by the `X-Api-Key` header
+pub async fn integrator_auth_middleware<B>(
+    State(state): State<AppState>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, AuthError> {
+    let name = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|api_key| state.integrators.authenticate(api_key))
+        .ok_or(AuthError::InvalidApiKey)?
+        .to_string();
+
+    request.extensions_mut().insert(IntegratorPayload::new(name));
+    Ok(next.run(request).await)
+}
+
+/// Auth of the admin routes by the `X-Api-Key` header, compared to `ADMIN_API_KEY`.
+/// Without the variable the admin routes reject every request.
+pub async fn admin_auth_middleware<B>(
+    request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, AuthError> {
+    let admin_key_hash = env::var("ADMIN_API_KEY")
+        .ok()
+        .filter(|admin_key| !admin_key.is_empty())
+        .map(|admin_key| hash_bytes_sha256(admin_key.into_bytes()))
+        .ok_or(AuthError::InvalidApiKey)?;
+    let api_key_hash = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|header| header.to_str().ok())
+        .map(|api_key| hash_bytes_sha256(api_key.as_bytes().to_vec()))
+        .ok_or(AuthError::InvalidApiKey)?;
+    // hashes are compared so the comparison time does not depend on the key
+    if api_key_hash != admin_key_hash {
+        return Err(AuthError::InvalidApiKey);
     }
+    Ok(next.run(request).await)
 }
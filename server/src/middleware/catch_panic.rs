@@ -0,0 +1,22 @@
+use std::any::Any;
+use std::sync::atomic::Ordering;
+use axum::response::{IntoResponse, Response};
+use log::error;
+use crate::domain::errors::api_error::{ApiError, ErrorCode};
+use crate::utils::metrics::HANDLER_PANICS_TOTAL;
+
+/// Turns a panicking handler into a 500 `ApiError` instead of dropping the connection.
+/// Used with `tower_http::catch_panic::CatchPanicLayer::custom`.
+pub fn handle_panic(panic: Box<dyn Any + Send + 'static>) -> Response {
+    let message = if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else {
+        "unknown panic payload".to_string()
+    };
+    error!("Handler panicked: {}", message);
+    HANDLER_PANICS_TOTAL.fetch_add(1, Ordering::Relaxed);
+
+    ApiError::new(ErrorCode::InternalError, "Internal server error").into_response()
+}
@@ -0,0 +1,269 @@
+use std::env;
+use axum::{
+    body::{boxed, Body, Full},
+    extract::State,
+    http::{header, Extensions, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http_body::{LengthLimitError, Limited};
+use hyper::body::Bytes;
+use log::error;
+use crate::api::handlers::auth::AppState;
+use crate::domain::errors::api_error::{ApiError, ErrorCode};
+use crate::domain::models::auth::JwtUserPayload;
+use crate::entities::{user::IdempotencyKeyEntity, user_repo::UserRepo};
+use crate::services::job_service::instance_id;
+use crate::utils::common::{get_current_time, hash_bytes_sha256};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// Set on responses replayed from a previous request with the same key
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Default of `IDEMPOTENCY_RETENTION_HOURS`
+const DEFAULT_RETENTION_HOURS: i64 = 24;
+const MAX_KEY_LENGTH: usize = 255;
+/// Largest request body read to fingerprint it
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Makes retries of a request with the same `Idempotency-Key` header safe: the first
+/// request runs and its response is stored, later ones get the stored response without
+/// running the handler again. Reusing a key with a different body is rejected, as is a
+/// retry while the first request is still running. Requests without the header are
+/// passed through. Server errors raised before anything was written are not stored, the key
+/// is released so a retry runs again. Other server errors, e.g. a timed out transaction which
+/// may still be included, are stored like any other response.
+pub async fn idempotency_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let idempotency_key = match request.headers().get(IDEMPOTENCY_KEY_HEADER) {
+        Some(header) => match header.to_str() {
+            Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key.to_string(),
+            _ => return ApiError::new(
+                ErrorCode::InvalidRequest,
+                format!("Idempotency-Key must be 1 to {} visible ASCII characters", MAX_KEY_LENGTH),
+            ).into_response(),
+        },
+        None => return next.run(request).await,
+    };
+    let scope = format!("{} {}", request.method(), request.uri().path());
+    // keys of authenticated routes are per user, so users can not replay each other's responses
+    let owner = request.extensions().get::<JwtUserPayload>()
+        .map(|jwt_user| jwt_user.user_id.clone())
+        .unwrap_or_default();
+
+    let (parts, body) = request.into_parts();
+    let body = match read_body(body).await {
+        Ok(body) => body,
+        Err(e) => return e.into_response(),
+    };
+    let fingerprint = hash_bytes_sha256(body.to_vec());
+    let request = Request::from_parts(parts, Body::from(body));
+
+    let now = get_current_time();
+    let reservation = IdempotencyKeyEntity {
+        scope: scope.clone(),
+        owner: owner.clone(),
+        idempotency_key: idempotency_key.clone(),
+        fingerprint: fingerprint.clone(),
+        status_code: None,
+        content_type: None,
+        response_body: None,
+        created_at: now,
+        expires_at: now + retention_hours() * 60 * 60,
+        instance_id: instance_id().to_string(),
+    };
+    let reserved = state.user_repo.purge_idempotency_keys(now)
+        .and_then(|_| state.user_repo.reserve_idempotency_key(&reservation));
+    match reserved {
+        Ok(true) => {}
+        Ok(false) => return replay(&state.user_repo, &reservation),
+        Err(e) => return ApiError::new(ErrorCode::DatabaseError, e.to_string()).into_response(),
+    }
+
+    // released when the request is dropped before completing (panic or disconnect),
+    // so the client can retry
+    let mut guard = Reservation { user_repo: &state.user_repo, key: &reservation, completed: false };
+    let response = next.run(request).await;
+
+    let (parts, body) = response.into_parts();
+    let body = match hyper::body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(e) => return ApiError::new(ErrorCode::InternalError, format!("Failed to read response: {}", e)).into_response(),
+    };
+    // nothing was written, the guard releases the key so the retry runs again
+    if write_failed(&parts.extensions) {
+        return Response::from_parts(parts, boxed(Full::from(body)));
+    }
+    let content_type = parts.headers.get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    match state.user_repo.complete_idempotency_key(
+        &scope, &owner, &idempotency_key, parts.status.as_u16() as i32, content_type, body.to_vec(),
+    ) {
+        Ok(_) => guard.completed = true,
+        Err(e) => error!("Failed to store response of idempotency key {}: {}", idempotency_key, e),
+    }
+
+    Response::from_parts(parts, boxed(Full::from(body)))
+}
+
+/// Whether the response is an error after which the request certainly wrote nothing
+fn write_failed(extensions: &Extensions) -> bool {
+    matches!(
+        extensions.get::<ApiError>().map(|api_error| api_error.code),
+        Some(ErrorCode::TransactionFailed | ErrorCode::DatabaseError | ErrorCode::KeyStoreError)
+    )
+}
+
+/// Answer to a request whose key was reserved before
+fn replay(user_repo: &UserRepo, request: &IdempotencyKeyEntity) -> Response {
+    let stored = match user_repo.get_idempotency_key(&request.scope, &request.owner, &request.idempotency_key) {
+        Ok(Some(stored)) => stored,
+        // released in the meantime, e.g. the first request was dropped
+        Ok(None) => return ApiError::new(
+            ErrorCode::RequestInProgress,
+            "A request with this Idempotency-Key was just cancelled, retry it",
+        ).into_response(),
+        Err(e) => return ApiError::new(ErrorCode::DatabaseError, e.to_string()).into_response(),
+    };
+    if stored.fingerprint != request.fingerprint {
+        return ApiError::new(
+            ErrorCode::IdempotencyKeyReused,
+            "Idempotency-Key was already used with a different request body",
+        ).into_response();
+    }
+    let (Some(status_code), Some(response_body)) = (stored.status_code, stored.response_body) else {
+        return ApiError::new(
+            ErrorCode::RequestInProgress,
+            "A request with this Idempotency-Key is still in progress",
+        ).into_response();
+    };
+
+    stored_response(status_code, stored.content_type, response_body)
+}
+
+/// Reads the request body, rejecting bodies larger than `MAX_BODY_BYTES`
+async fn read_body(body: Body) -> Result<Bytes, ApiError> {
+    hyper::body::to_bytes(Limited::new(body, MAX_BODY_BYTES)).await.map_err(|e| {
+        if e.downcast_ref::<LengthLimitError>().is_some() {
+            ApiError::new(
+                ErrorCode::PayloadTooLarge,
+                format!("Request body is larger than {} bytes", MAX_BODY_BYTES),
+            )
+        } else {
+            ApiError::new(ErrorCode::InvalidRequest, format!("Failed to read body: {}", e))
+        }
+    })
+}
+
+/// Rebuilds a stored response. Stored errors are answered as `ApiError`s again, so they get
+/// the id of the retry like any other error.
+fn stored_response(status_code: i32, content_type: Option<String>, body: Vec<u8>) -> Response {
+    let status = StatusCode::from_u16(status_code as u16).unwrap_or(StatusCode::OK);
+    let api_error = (status.is_client_error() || status.is_server_error())
+        .then(|| serde_json::from_slice::<ApiError>(&body).ok())
+        .flatten();
+    let mut response = match api_error {
+        Some(api_error) => {
+            let mut response = api_error.into_response();
+            *response.status_mut() = status;
+            response
+        }
+        None => {
+            let mut response = Response::new(boxed(Full::from(body)));
+            *response.status_mut() = status;
+            if let Some(content_type) = content_type.and_then(|value| HeaderValue::from_str(&value).ok()) {
+                response.headers_mut().insert(header::CONTENT_TYPE, content_type);
+            }
+            response
+        }
+    };
+    response.headers_mut().insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+/// Hours a key and its response are kept, from `IDEMPOTENCY_RETENTION_HOURS`
+fn retention_hours() -> i64 {
+    env::var("IDEMPOTENCY_RETENTION_HOURS")
+        .ok()
+        .and_then(|value| value.parse::<i64>().ok())
+        .filter(|hours| *hours > 0)
+        .unwrap_or(DEFAULT_RETENTION_HOURS)
+}
+
+struct Reservation<'a> {
+    user_repo: &'a UserRepo,
+    key: &'a IdempotencyKeyEntity,
+    completed: bool,
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if self.completed {
+            return;
+        }
+        if let Err(e) = self.user_repo.delete_idempotency_key(&self.key.scope, &self.key.owner, &self.key.idempotency_key) {
+            error!("Failed to release idempotency key {}: {}", self.key.idempotency_key, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn bodies_over_the_limit_are_rejected() {
+        let body = read_body(Body::from(vec![b'a'; MAX_BODY_BYTES])).await.unwrap();
+        assert_eq!(body.len(), MAX_BODY_BYTES);
+
+        let error = read_body(Body::from(vec![b'a'; MAX_BODY_BYTES + 1])).await.unwrap_err();
+        assert_eq!(error.code, ErrorCode::PayloadTooLarge);
+        assert_eq!(error.into_response().status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn stored_responses_are_replayed() {
+        let body = serde_json::to_vec(&json!({ "success": true })).unwrap();
+        let response = stored_response(200, Some("application/json".to_string()), body.clone());
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(response.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert!(response.extensions().get::<ApiError>().is_none());
+        assert_eq!(hyper::body::to_bytes(response.into_body()).await.unwrap(), body);
+    }
+
+    #[test]
+    fn keys_are_released_only_when_nothing_was_written() {
+        let released = |code: ErrorCode| write_failed(ApiError::new(code, "failed").into_response().extensions());
+
+        assert!(released(ErrorCode::TransactionFailed));
+        assert!(released(ErrorCode::DatabaseError));
+        assert!(released(ErrorCode::KeyStoreError));
+        // the transaction may still be included, a retry must not post it again
+        assert!(!released(ErrorCode::TransactionTimeout));
+        assert!(!released(ErrorCode::InternalError));
+        assert!(!released(ErrorCode::DuplicateProof));
+        assert!(!write_failed(&Extensions::new()));
+    }
+
+    #[test]
+    fn stored_errors_are_replayed_as_api_errors() {
+        let stored = ApiError::new(ErrorCode::DuplicateProof, "Proof was already applied")
+            .with_request_id("first-request");
+        let body = serde_json::to_vec(&stored).unwrap();
+        let response = stored_response(409, Some("application/json".to_string()), body);
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(response.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        // the request id middleware stamps the id of the retry on it
+        let api_error = response.extensions().get::<ApiError>().unwrap();
+        assert_eq!(api_error.code, ErrorCode::DuplicateProof);
+        assert_eq!(api_error.message, "Proof was already applied");
+    }
+}
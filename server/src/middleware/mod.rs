@@ -1 +1,4 @@
 pub mod auth;
+pub mod catch_panic;
+pub mod idempotency;
+pub mod request_id;
\ No newline at end of file
@@ -0,0 +1,54 @@
+use axum::{
+    http::{header, HeaderValue, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use log::{error, warn};
+use uuid::Uuid;
+use crate::domain::errors::api_error::ApiError;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Id of the current request, available to handlers as an extension
+#[derive(Clone, Debug)]
+pub struct RequestId(pub String);
+
+/// Tags every request with an id, reusing the caller's `x-request-id` when present.
+/// The id is returned as a header and stamped on `ApiError` bodies so a client report
+/// can be matched with the server log.
+pub async fn request_id_middleware<B>(
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|header| header.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= 128)
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    request.extensions_mut().insert(RequestId(request_id.clone()));
+
+    let mut response = next.run(request).await;
+
+    if let Some(api_error) = response.extensions_mut().remove::<ApiError>() {
+        if api_error.status() >= 500 {
+            error!("[{}] {} {} failed with {:?}: {}", request_id, method, path, api_error.code, api_error.message);
+        } else {
+            warn!("[{}] {} {} rejected with {:?}: {}", request_id, method, path, api_error.code, api_error.message);
+        }
+        // headers set by handlers or other middleware, e.g. `idempotent-replayed`, are kept
+        let mut headers = std::mem::take(response.headers_mut());
+        headers.remove(header::CONTENT_LENGTH);
+        let status = response.status();
+        response = api_error.with_request_id(request_id.clone()).into_response();
+        *response.status_mut() = status;
+        response.headers_mut().extend(headers);
+    }
+    if let Ok(header) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, header);
+    }
+    response
+}
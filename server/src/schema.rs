@@ -1,7 +1,111 @@
 // @generated automatically by Diesel CLI.
 
 diesel::table! {
-    proofs (provider_id) {
+    idempotency_keys (scope, owner, idempotency_key) {
+        #[max_length = 64]
+        scope -> Varchar,
+        #[max_length = 255]
+        owner -> Varchar,
+        #[max_length = 255]
+        idempotency_key -> Varchar,
+        #[max_length = 64]
+        fingerprint -> Varchar,
+        status_code -> Nullable<Integer>,
+        #[max_length = 255]
+        content_type -> Nullable<Varchar>,
+        response_body -> Nullable<Mediumblob>,
+        created_at -> Bigint,
+        expires_at -> Bigint,
+        #[max_length = 255]
+        instance_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    identities (platform, normalized_handle, user_id) {
+        #[max_length = 64]
+        platform -> Varchar,
+        #[max_length = 255]
+        normalized_handle -> Varchar,
+        #[max_length = 255]
+        user_id -> Varchar,
+        #[max_length = 255]
+        provider_id -> Varchar,
+        #[max_length = 255]
+        handle -> Varchar,
+        #[max_length = 255]
+        social_user_id -> Nullable<Varchar>,
+        #[max_length = 255]
+        proof_identifier -> Varchar,
+        proof_timestamp -> Bigint,
+        created_at -> Bigint,
+        flagged -> Bool,
+        exclusive -> Bool,
+    }
+}
+
+diesel::table! {
+    jobs (id) {
+        #[max_length = 36]
+        id -> Varchar,
+        #[max_length = 32]
+        kind -> Varchar,
+        #[max_length = 16]
+        status -> Varchar,
+        #[max_length = 255]
+        user_id -> Varchar,
+        #[max_length = 64]
+        transaction_hash -> Nullable<Varchar>,
+        result -> Nullable<Text>,
+        error -> Nullable<Text>,
+        created_at -> Bigint,
+        updated_at -> Bigint,
+        #[max_length = 255]
+        instance_id -> Varchar,
+    }
+}
+
+diesel::table! {
+    operations (id) {
+        #[max_length = 36]
+        id -> Varchar,
+        #[max_length = 32]
+        kind -> Varchar,
+        #[max_length = 16]
+        status -> Varchar,
+        #[max_length = 255]
+        user_id -> Varchar,
+        effects -> Mediumtext,
+        #[max_length = 64]
+        transaction_hash -> Nullable<Varchar>,
+        transaction_nonce -> Nullable<Bigint>,
+        attempts -> Integer,
+        last_error -> Nullable<Text>,
+        next_attempt_at -> Nullable<Bigint>,
+        created_at -> Bigint,
+        updated_at -> Bigint,
+    }
+}
+
+diesel::table! {
+    proof_reservations (raw_data_hash) {
+        #[max_length = 255]
+        raw_data_hash -> Varchar,
+        #[max_length = 255]
+        proof_identifier -> Nullable<Varchar>,
+        #[max_length = 255]
+        user_id -> Varchar,
+        #[max_length = 255]
+        provider_id -> Varchar,
+        #[max_length = 16]
+        status -> Varchar,
+        created_at -> Bigint,
+        updated_at -> Bigint,
+    }
+}
+
+diesel::table! {
+    proofs (raw_data_hash) {
         #[max_length = 255]
         provider_id -> Varchar,
         #[max_length = 255]
@@ -10,6 +114,27 @@ diesel::table! {
         #[max_length = 255]
         raw_data_hash -> Varchar,
         created_at -> Bigint,
+        #[max_length = 64]
+        platform -> Nullable<Varchar>,
+        #[max_length = 255]
+        handle -> Nullable<Varchar>,
+        #[max_length = 255]
+        social_user_id -> Nullable<Varchar>,
+        #[max_length = 512]
+        profile_url -> Nullable<Varchar>,
+        follower_count -> Nullable<Bigint>,
+        record_version -> Integer,
+        revoked_at -> Nullable<Bigint>,
+    }
+}
+
+diesel::table! {
+    record_heights (user_id, record_index) {
+        #[max_length = 255]
+        user_id -> Varchar,
+        record_index -> Integer,
+        prism_height -> Bigint,
+        recorded_at -> Bigint,
     }
 }
 
@@ -23,9 +148,55 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    webhook_deliveries (id) {
+        #[max_length = 36]
+        id -> Varchar,
+        #[max_length = 36]
+        webhook_id -> Varchar,
+        #[max_length = 32]
+        event_type -> Varchar,
+        payload -> Text,
+        #[max_length = 16]
+        status -> Varchar,
+        attempts -> Integer,
+        next_attempt_at -> Nullable<Bigint>,
+        last_status_code -> Nullable<Integer>,
+        last_error -> Nullable<Text>,
+        created_at -> Bigint,
+        updated_at -> Bigint,
+    }
+}
+
+diesel::table! {
+    webhooks (id) {
+        #[max_length = 36]
+        id -> Varchar,
+        #[max_length = 255]
+        integrator -> Varchar,
+        #[max_length = 2048]
+        url -> Varchar,
+        #[max_length = 128]
+        secret -> Varchar,
+        #[max_length = 255]
+        events -> Varchar,
+        created_at -> Bigint,
+    }
+}
+
+diesel::joinable!(identities -> users (user_id));
 diesel::joinable!(proofs -> users (user_id));
+diesel::joinable!(webhook_deliveries -> webhooks (webhook_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    idempotency_keys,
+    identities,
+    jobs,
+    operations,
+    proof_reservations,
     proofs,
+    record_heights,
     users,
+    webhook_deliveries,
+    webhooks,
 );
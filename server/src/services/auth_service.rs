@@ -1,3 +1,4 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use tendermint::PublicKey;
 use crate::{domain::errors::auth_errors::AuthError, SERVICE_ID};
 use prism_keys::{
@@ -29,18 +30,20 @@ impl AuthService {
     }
 
     pub fn prepare_auth_data(self: &Self, signer: String, public_key: String) -> Result<PreparedAuthData, AuthError> {
-        let service_sk = FileStore::new(KEYSTORE_PATH).unwrap()
+        let service_sk = FileStore::new(KEYSTORE_PATH)
+            .map_err(|e| AuthError::KeyStoreError(e.to_string()))?
             .get_or_create_signing_key(SERVICE_ID)
             .map_err(|e| AuthError::KeyStoreError(e.to_string()))?;
         let service_sk = SigningKey::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, &service_sk.to_bytes())?;
 
         let user_pk = PublicKey::from_raw_secp256k1(
-            base64::decode(&public_key)?.as_slice()
+            STANDARD.decode(&public_key)?.as_slice()
         ).ok_or(AuthError::PublicKeyError)?;
         
         let vk  = user_pk.secp256k1()
             .ok_or_else(|| AuthError::PublicKeyError)?;
-        let user_vk = VerifyingKey::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, vk.to_bytes().as_slice()).unwrap();
+        let user_vk = VerifyingKey::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, vk.to_bytes().as_slice())
+            .map_err(|_| AuthError::PublicKeyError)?;
 
         let unsigned_tx = self.prover
             .build_request()
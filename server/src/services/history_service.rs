@@ -0,0 +1,155 @@
+use log::warn;
+use prism_da::DataAvailabilityLayer;
+use prism_prover::Prover;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{
+    domain::{
+        errors::user_errors::UserError,
+        models::{
+            account_state::{AccountState, RecordStatus},
+            receipt::TransactionReceipt,
+            user::{AccountRecord, RecordSigner, UnrecognizedRecord},
+        },
+    },
+    entities::{user::RecordHeightEntity, user_repo::UserRepo},
+    services::user_service::{self, ConfirmedTransaction, UserService},
+    utils::common::get_current_time,
+};
+
+/// DA blocks searched back from the latest height for the one including a transaction
+const INCLUSION_SEARCH_DEPTH: u64 = 256;
+
+pub struct HistoryService {
+    prover: Arc<Prover>,
+    user_repo: UserRepo,
+    da_layer: Arc<dyn DataAvailabilityLayer>,
+}
+
+pub struct HistoryEntry {
+    pub index: usize,
+    pub prism_height: Option<u64>,
+    pub status: Option<RecordStatus>,
+    pub signer_key: String,
+    pub signer: RecordSigner,
+    pub signature_valid: bool,
+    pub record: Result<AccountRecord, UnrecognizedRecord>,
+}
+
+impl HistoryService {
+    pub fn new(prover: Arc<Prover>, user_repo: UserRepo, da_layer: Arc<dyn DataAvailabilityLayer>) -> Self {
+        Self { prover, user_repo, da_layer }
+    }
+
+    /// Height of the DA block holding the transaction, searched back from the latest height
+    pub async fn inclusion_height(self: &Self, transaction_hash: &str) -> Result<Option<u64>, UserError> {
+        let latest = self.da_layer.get_latest_height().await?;
+        for height in (latest.saturating_sub(INCLUSION_SEARCH_DEPTH)..=latest).rev() {
+            for tx in self.da_layer.get_transactions(height).await? {
+                if user_service::transaction_hash(&tx)? == transaction_hash {
+                    return Ok(Some(height));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Root of the first epoch finalized at or after the height, the commitment covering
+    /// a transaction included there. `None` while that epoch is not posted yet
+    pub async fn commitment_at(self: &Self, height: u64) -> Result<Option<String>, UserError> {
+        let latest = self.da_layer.get_latest_height().await?;
+        for height in height..=latest {
+            if let Some(epoch) = self.da_layer.get_finalized_epoch(height).await? {
+                return Ok(Some(epoch.current_commitment.to_hex()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Stores the height at which the record appended by the transaction was included
+    pub fn record_height(self: &Self, confirmed: &ConfirmedTransaction, height: u64) -> Result<(), UserError> {
+        let Some(record_index) = confirmed.record_index else {
+            return Ok(());
+        };
+        self.user_repo.insert_record_heights(&[RecordHeightEntity {
+            user_id: confirmed.account.id().to_string(),
+            record_index: record_index as i32,
+            prism_height: height as i64,
+            recorded_at: get_current_time(),
+        }])?;
+        Ok(())
+    }
+
+    /// Receipt of the confirmed transaction, `None` when no transaction was posted
+    pub async fn receipt(self: &Self, confirmed: &ConfirmedTransaction) -> Result<Option<TransactionReceipt>, UserError> {
+        let Some(transaction_hash) = &confirmed.transaction_hash else {
+            return Ok(None);
+        };
+        let height = self.inclusion_height(transaction_hash).await?
+            .ok_or_else(|| UserError::TransactionError(format!("Transaction {} is not on the DA layer", transaction_hash)))?;
+        let commitment = self.commitment_at(height).await?;
+
+        Ok(Some(TransactionReceipt {
+            transaction_hash: transaction_hash.clone(),
+            account_id: confirmed.account.id().to_string(),
+            nonce: confirmed.account.nonce(),
+            height,
+            commitment,
+        }))
+    }
+
+    /// Builds the receipt of the confirmed transaction and records the height of the
+    /// record it appended. Failures are logged only, the transaction is included either way.
+    pub async fn confirm(self: &Self, confirmed: &ConfirmedTransaction) -> Option<TransactionReceipt> {
+        let receipt = match self.receipt(confirmed).await {
+            Ok(receipt) => receipt?,
+            Err(e) => {
+                warn!("Failed to build receipt of account {}: {}", confirmed.account.id(), e);
+                return None;
+            }
+        };
+        if let Err(e) = self.record_height(confirmed, receipt.height) {
+            warn!("Failed to record height of account {}: {}", confirmed.account.id(), e);
+        }
+        Some(receipt)
+    }
+
+    /// Full chronological log of the account with the status of every identity record.
+    /// Records written before heights were tracked have no height.
+    pub async fn get_history(self: &Self, user_id: &str) -> Result<Vec<HistoryEntry>, UserError> {
+        let user_service = UserService::new(self.prover.clone(), user_id.to_string());
+        let entries = user_service.get_account_entries().await?;
+        let heights: HashMap<usize, u64> = self.user_repo.get_record_heights(user_id)?
+            .into_iter()
+            .map(|height| (height.record_index as usize, height.prism_height as u64))
+            .collect();
+
+        let state = AccountState::new(
+            entries.iter()
+                .filter(|entry| entry.is_trusted())
+                .filter_map(|entry| entry.record.as_ref().ok().cloned())
+                .collect()
+        );
+        let mut statuses = state.statuses().into_iter();
+
+        Ok(entries.into_iter()
+            .map(|entry| {
+                // statuses are computed over the recognized and verified records only
+                let status = match entry.record {
+                    Ok(_) if entry.is_trusted() => statuses.next().flatten(),
+                    _ => None,
+                };
+                HistoryEntry {
+                    index: entry.index,
+                    prism_height: heights.get(&entry.index).copied(),
+                    status,
+                    signer_key: entry.signer_key,
+                    signer: entry.signer,
+                    signature_valid: entry.signature_valid,
+                    record: entry.record,
+                }
+            })
+            .collect())
+    }
+}
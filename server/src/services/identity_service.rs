@@ -0,0 +1,182 @@
+use prism_prover::Prover;
+use std::sync::Arc;
+use log::warn;
+
+use crate::{
+    domain::{
+        errors::{proof_errors::ProofError, user_errors::UserError},
+        models::{
+            operation::OperationKind,
+            provider::{Provider, UniquenessPolicy},
+            social_identity::SocialIdentity,
+            user::{IdentityRevocationRecord, IdentitySupersedeRecord},
+        },
+    },
+    entities::{user::{IdentityEntity, ProofEntity}, user_repo::UserRepo},
+    services::{
+        operation_service::{OperationEffects, OperationService},
+        user_service::{service_record_data, ConfirmedTransaction, UserService},
+    },
+    utils::{common::{get_current_time, hash_bytes_sha256}, handle::normalize_handle},
+};
+
+pub struct IdentityService {
+    prover: Arc<Prover>,
+    user_repo: UserRepo,
+}
+
+impl IdentityService {
+    pub fn new(prover: Arc<Prover>, user_repo: UserRepo) -> Self {
+        Self { prover, user_repo }
+    }
+
+    /// Returns identities of other wallets linked to the same handle, or the same user id
+    /// when the platform has no handles. Fails when the provider's uniqueness policy
+    /// rejects a second link.
+    pub fn check_uniqueness(
+        self: &Self,
+        provider: &Provider,
+        identity: &SocialIdentity,
+        user_id: &str,
+    ) -> Result<Vec<IdentityEntity>, ProofError> {
+        let handle = match identity.uniqueness_key() {
+            Some(handle) => handle,
+            None => return Ok(Vec::new()),
+        };
+        let normalized_handle = normalize_handle(&identity.platform, handle);
+        let conflicts = self.user_repo.find_conflicting_identities(&identity.platform, &normalized_handle, user_id)?;
+
+        match (provider.uniqueness_policy, conflicts.first()) {
+            (UniquenessPolicy::Reject, Some(existing)) => {
+                Err(ProofError::IdentityAlreadyLinked(existing.user_id.clone()))
+            }
+            _ => Ok(conflicts),
+        }
+    }
+
+    /// Moves the identities to the new owner: writes a supersede record to each old
+    /// account on Prism and, once it is included, removes the old link from the lookup index
+    pub async fn transfer(self: &Self, conflicts: Vec<IdentityEntity>, new_owner: &str) -> Result<Vec<ConfirmedTransaction>, ProofError> {
+        let operations = OperationService::new(self.prover.clone(), self.user_repo.clone());
+        let mut transactions = Vec::with_capacity(conflicts.len());
+        for conflict in conflicts {
+            let record = serde_json::to_vec(&IdentitySupersedeRecord::new(
+                conflict.provider_id.clone(),
+                conflict.platform.clone(),
+                conflict.handle.clone(),
+                conflict.proof_identifier.clone(),
+                new_owner.to_string(),
+                get_current_time(),
+            ))?;
+            let effects = OperationEffects::TransferIdentity {
+                record_hash: hash_bytes_sha256(service_record_data(&record)),
+                identity: conflict.clone(),
+            };
+            let operation_id = operations.begin(OperationKind::TransferIdentity, &conflict.user_id, &effects)?;
+            let user_service = UserService::new(self.prover.clone(), conflict.user_id.clone())
+                .with_operation(operations.clone(), operation_id.clone());
+            let confirmed = match user_service.add_service_record_to_user_account(record).await {
+                Ok(confirmed) => confirmed,
+                // the transaction may still be included, the worker resolves the operation
+                Err(e @ UserError::TransactionTimeout(_)) => return Err(ProofError::TransactionError(e.to_string())),
+                Err(e) => {
+                    operations.abort(&operation_id, &e.to_string());
+                    return Err(ProofError::TransactionError(e.to_string()));
+                }
+            };
+            operations.commit(&operation_id);
+
+            warn!("Identity {}:{} transferred from {} to {}", conflict.platform, conflict.handle, conflict.user_id, new_owner);
+            transactions.push(confirmed);
+        }
+        Ok(transactions)
+    }
+
+    /// Marks the revoked proofs in the database and removes them from the lookup index.
+    /// A revocation with a proof identifier only covers the proof it names.
+    pub fn revoke(self: &Self, user_id: &str, revocation: &IdentityRevocationRecord) -> Result<(), ProofError> {
+        let proof_identifier = revocation.proof_identifier.as_deref();
+        match proof_identifier {
+            None => {
+                self.user_repo.revoke_proofs(user_id, &revocation.provider_id, revocation.revoked_at)?;
+            }
+            Some(proof_identifier) => {
+                let raw_data_hashes = revoked_proof_hashes(
+                    self.user_repo.get_proofs_by_user(user_id)?,
+                    &revocation.provider_id,
+                    proof_identifier,
+                );
+                self.user_repo.revoke_proofs_by_hash(&raw_data_hashes, revocation.revoked_at)?;
+            }
+        }
+        self.user_repo.delete_identities_by_provider(user_id, &revocation.provider_id, proof_identifier)?;
+        Ok(())
+    }
+}
+
+/// Hashes of the provider's proofs whose attestation has the identifier. Rows without
+/// a readable attestation can not be matched and are left alone.
+fn revoked_proof_hashes(proofs: Vec<ProofEntity>, provider_id: &str, proof_identifier: &str) -> Vec<String> {
+    proofs.into_iter()
+        .filter(|proof| proof.provider_id == provider_id)
+        .filter(|proof| {
+            proof.get_raw_data()
+                .and_then(|attestation| attestation.identifier())
+                .is_ok_and(|identifier| identifier == proof_identifier)
+        })
+        .map(|proof| proof.raw_data_hash)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+    use super::*;
+    use crate::domain::models::attestation::{Attestation, SignedClaimAttestation};
+
+    fn proof(provider_id: &str, payload: &str) -> ProofEntity {
+        let mut proof = ProofEntity {
+            provider_id: provider_id.to_string(),
+            user_id: "cosmos1user".to_string(),
+            raw_data: None,
+            raw_data_hash: String::new(),
+            created_at: 0,
+            platform: None,
+            handle: None,
+            social_user_id: None,
+            profile_url: None,
+            follower_count: None,
+            record_version: 2,
+            revoked_at: None,
+        };
+        let attestation = Attestation::Signed(SignedClaimAttestation {
+            payload: STANDARD.encode(payload),
+            public_key: String::new(),
+            signature: String::new(),
+        });
+        proof.set_raw_proof(&attestation).unwrap();
+        proof
+    }
+
+    #[test]
+    fn only_the_named_proof_is_revoked() {
+        let kept = proof("github", "first");
+        let revoked = proof("github", "second");
+        let other_provider = proof("twitter", "second");
+        let identifier = revoked.get_raw_data().unwrap().identifier().unwrap();
+
+        let hashes = revoked_proof_hashes(vec![kept, revoked.clone(), other_provider], "github", &identifier);
+
+        assert_eq!(hashes, vec![revoked.raw_data_hash]);
+    }
+
+    #[test]
+    fn rows_without_an_attestation_are_not_revoked() {
+        let mut rebuilt = proof("github", "first");
+        let identifier = rebuilt.get_raw_data().unwrap().identifier().unwrap();
+        rebuilt.raw_data = None;
+
+        assert!(revoked_proof_hashes(vec![rebuilt], "github", &identifier).is_empty());
+    }
+}
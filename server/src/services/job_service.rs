@@ -0,0 +1,138 @@
+use std::{env, sync::OnceLock};
+use anyhow::{anyhow, Result};
+use log::{error, info};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    api::dto::response::job_res::JobResponseDto,
+    domain::{
+        errors::api_error::{ApiError, ErrorCode},
+        models::{event::UserEvent, job::{JobKind, JobStatus}},
+    },
+    entities::{user::JobEntity, user_repo::UserRepo},
+    utils::{common::get_current_time, events},
+};
+
+/// Tracks asynchronous Prism submissions in the `jobs` table
+#[derive(Clone)]
+pub struct JobService {
+    user_repo: UserRepo,
+}
+
+impl JobService {
+    pub fn new(user_repo: UserRepo) -> Self {
+        Self { user_repo }
+    }
+
+    pub fn create(self: &Self, kind: JobKind, user_id: &str) -> Result<JobResponseDto> {
+        let now = get_current_time();
+        let job = JobEntity {
+            id: Uuid::new_v4().to_string(),
+            kind: kind.as_str().to_string(),
+            status: JobStatus::Queued.as_str().to_string(),
+            user_id: user_id.to_string(),
+            transaction_hash: None,
+            result: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+            instance_id: instance_id().to_string(),
+        };
+        self.user_repo.insert_job(&job)?;
+        publish_status(&job.id, user_id, JobStatus::Queued);
+        to_dto(job)
+    }
+
+    pub fn get(self: &Self, id: &str) -> Result<Option<JobResponseDto>> {
+        self.user_repo.get_job(id)?.map(to_dto).transpose()
+    }
+
+    /// Failures to update a job are logged only, they must not fail the submission itself
+    pub fn mark_submitted(self: &Self, id: &str, user_id: &str, transaction_hash: &str) {
+        if let Err(e) = self.user_repo.set_job_submitted(id, transaction_hash, get_current_time()) {
+            error!("Failed to mark job {} as submitted: {}", id, e);
+        }
+        publish_status(id, user_id, JobStatus::Submitted);
+    }
+
+    pub fn complete<T: Serialize>(self: &Self, id: &str, user_id: &str, result: &T) {
+        let result = serde_json::to_string(result).ok();
+        if let Err(e) = self.user_repo.finish_job(id, JobStatus::Included.as_str(), result, None, get_current_time()) {
+            error!("Failed to complete job {}: {}", id, e);
+        }
+        publish_status(id, user_id, JobStatus::Included);
+    }
+
+    /// A timed out wait leaves the job submitted: the transaction was posted and may still be
+    /// included, the outbox worker finishes the job once its operation is resolved
+    pub fn fail(self: &Self, id: &str, user_id: &str, api_error: &ApiError) {
+        if api_error.code == ErrorCode::TransactionTimeout {
+            info!("Job {} timed out waiting for its transaction, leaving it to the outbox worker", id);
+            return;
+        }
+        let error = serde_json::to_string(api_error).ok();
+        if let Err(e) = self.user_repo.finish_job(id, JobStatus::Failed.as_str(), None, error, get_current_time()) {
+            error!("Failed to mark job {} as failed: {}", id, e);
+        }
+        publish_status(id, user_id, JobStatus::Failed);
+    }
+
+    /// Finishes the submitted jobs of a transaction resolved by the outbox worker,
+    /// as included when `error` is `None`. Their result is only set by the request.
+    pub fn finish_submitted(self: &Self, transaction_hash: &str, error: Option<&ApiError>) -> Result<()> {
+        let (status, error) = match error {
+            None => (JobStatus::Included, None),
+            Some(api_error) => (JobStatus::Failed, Some(serde_json::to_string(api_error)?)),
+        };
+        for job in self.user_repo.get_submitted_jobs_by_transaction(transaction_hash)? {
+            self.user_repo.finish_job(&job.id, status.as_str(), None, error.clone(), get_current_time())?;
+            publish_status(&job.id, &job.user_id, status);
+        }
+        Ok(())
+    }
+
+    /// Jobs run as tasks of the process which created them, so the ones a previous run of
+    /// this instance left queued will never finish. Submitted jobs are finished by the
+    /// outbox worker and jobs of other instances are left alone.
+    pub fn fail_interrupted(self: &Self) -> Result<usize> {
+        let api_error = ApiError::new(
+            ErrorCode::TransactionFailed,
+            "Server restarted before the job submitted its transaction",
+        );
+        self.user_repo.fail_queued_jobs(instance_id(), &serde_json::to_string(&api_error)?, get_current_time())
+    }
+}
+
+/// Identifies this server among the ones sharing the database: `INSTANCE_ID`, else the
+/// host name, which stays the same across restarts of the instance
+pub fn instance_id() -> &'static str {
+    static INSTANCE_ID: OnceLock<String> = OnceLock::new();
+    INSTANCE_ID.get_or_init(|| {
+        env::var("INSTANCE_ID")
+            .or_else(|_| env::var("HOSTNAME"))
+            .unwrap_or_else(|_| "weave".to_string())
+    })
+}
+
+fn publish_status(id: &str, user_id: &str, status: JobStatus) {
+    events::publish(UserEvent::JobUpdated {
+        user_id: user_id.to_string(),
+        job_id: id.to_string(),
+        status,
+    });
+}
+
+fn to_dto(job: JobEntity) -> Result<JobResponseDto> {
+    Ok(JobResponseDto {
+        kind: JobKind::parse(&job.kind).ok_or_else(|| anyhow!("Unknown job kind {}", job.kind))?,
+        status: JobStatus::parse(&job.status).ok_or_else(|| anyhow!("Unknown job status {}", job.status))?,
+        result: job.result.as_deref().map(serde_json::from_str).transpose()?,
+        error: job.error.as_deref().map(serde_json::from_str).transpose()?,
+        id: job.id,
+        user_id: job.user_id,
+        transaction_hash: job.transaction_hash,
+        created_at: job.created_at,
+        updated_at: job.updated_at,
+    })
+}
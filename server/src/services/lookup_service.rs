@@ -0,0 +1,73 @@
+use prism_prover::Prover;
+use std::sync::Arc;
+use log::debug;
+
+use crate::{
+    domain::{
+        errors::lookup_errors::LookupError,
+        models::user::UserIdentityRecord,
+    },
+    entities::user_repo::UserRepo,
+    services::user_service::UserService,
+    utils::handle::normalize_handle,
+};
+
+pub struct LookupService {
+    prover: Arc<Prover>,
+    user_repo: UserRepo,
+}
+
+pub struct IdentityLookupMatch {
+    pub address: String,
+    pub provider_id: String,
+    pub handle: String,
+    pub proof_timestamp: i64,
+    pub flagged: bool,
+    pub record: Option<UserIdentityRecord>,
+}
+
+impl LookupService {
+    pub fn new(prover: Arc<Prover>, user_repo: UserRepo) -> Self {
+        Self { prover, user_repo }
+    }
+
+    /// Finds wallets which linked the handle on the platform, newest proof first.
+    /// The matching identity record is read from the wallet's Prism account.
+    pub async fn lookup(self: &Self, platform: &str, handle: &str) -> Result<Vec<IdentityLookupMatch>, LookupError> {
+        let normalized_handle = normalize_handle(platform, handle);
+        if normalized_handle.is_empty() {
+            return Err(LookupError::EmptyHandle);
+        }
+
+        let identities = self.user_repo.find_identities_by_handle(platform, &normalized_handle)?;
+        if identities.is_empty() {
+            return Err(LookupError::NotFound(handle.to_string()));
+        }
+
+        let mut matches = Vec::with_capacity(identities.len());
+        for identity in identities {
+            let user_service = UserService::new(self.prover.clone(), identity.user_id.clone());
+            let record = match user_service.get_user().await {
+                Ok(user) => user.identity_records
+                    .into_iter()
+                    .find(|verified| verified.record.proof_identifier() == identity.proof_identifier)
+                    .map(|verified| verified.record),
+                Err(e) => {
+                    debug!("Failed to read Prism account {}: {:?}", identity.user_id, e);
+                    None
+                }
+            };
+
+            matches.push(IdentityLookupMatch {
+                address: identity.user_id,
+                provider_id: identity.provider_id,
+                handle: identity.handle,
+                proof_timestamp: identity.proof_timestamp,
+                flagged: identity.flagged,
+                record,
+            });
+        }
+
+        Ok(matches)
+    }
+}
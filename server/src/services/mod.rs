@@ -1,3 +1,11 @@
 pub mod proof_service;
 pub mod user_service;
-pub mod auth_service;
\ No newline at end of file
+pub mod auth_service;
+pub mod lookup_service;
+pub mod identity_service;
+pub mod history_service;
+pub mod job_service;
+pub mod webhook_service;
+pub mod proof_reservation_service;
+pub mod operation_service;
+pub mod reindex_service;
\ No newline at end of file
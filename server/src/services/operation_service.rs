@@ -0,0 +1,532 @@
+use std::sync::{atomic::Ordering, Arc};
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use prism_client::{Account, PrismApi};
+use prism_prover::Prover;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    api::dto::response::operation_res::OperationResponseDto,
+    domain::{
+        errors::api_error::{ApiError, ErrorCode},
+        models::{
+            event::UserEvent,
+            operation::{OperationKind, OperationStatus},
+            user::{IdentityRevocationRecord, IdentitySupersedeRecord, UserIdentityRecord},
+        },
+    },
+    entities::{
+        user::{IdentityEntity, OperationEntity, ProofEntity, UserEntity},
+        user_repo::UserRepo,
+    },
+    services::{
+        identity_service::IdentityService,
+        job_service::JobService,
+        proof_reservation_service::{ProofReservation, ProofReservationService},
+        user_service::transaction_timeout,
+        webhook_service::WebhookService,
+    },
+    utils::{common::{get_current_time, hash_bytes_sha256}, metrics},
+};
+
+/// Attempts of the worker before an operation is marked failed
+const MAX_ATTEMPTS: i32 = 10;
+/// Delay before the first retry of a commit, doubled on every further retry
+const RETRY_BASE_SECONDS: i64 = 10;
+const RETRY_MAX_SECONDS: i64 = 60 * 60;
+/// Extra time after the transaction timeout before the worker takes over an operation,
+/// so it does not race the request still waiting for the transaction
+const TAKEOVER_GRACE_SECONDS: i64 = 60;
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const BATCH_SIZE: i64 = 50;
+
+/// Database writes of an operation, applied once its Prism transaction is included.
+/// Every write is idempotent so the request and the worker can both apply them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OperationEffects {
+    CreateUser {
+        user: UserEntity,
+    },
+    ApplyProof {
+        /// sha256 of the signed data added to the account, to find it on Prism
+        record_hash: String,
+        proof: ProofEntity,
+        identity: Option<IdentityEntity>,
+        /// Rows of other wallets linked to the same handle, flagged or freed for the new one
+        #[serde(default)]
+        conflicts: Vec<IdentityEntity>,
+        reservation: ProofReservation,
+    },
+    RevokeProof {
+        /// sha256 of the signed revocation added to the account
+        record_hash: String,
+        revocation: IdentityRevocationRecord,
+    },
+    TransferIdentity {
+        /// sha256 of the supersede record added to the account of the previous owner
+        record_hash: String,
+        /// Link of the previous owner, removed from the lookup index
+        identity: IdentityEntity,
+    },
+}
+
+/// Outbox of writes spanning Prism and the database. The intent is recorded before the
+/// Prism transaction is posted and the database is only written once it is included;
+/// the worker finishes operations whose request did not, by committing them when the
+/// Prism write happened and compensating them once it can no longer happen.
+#[derive(Clone)]
+pub struct OperationService {
+    prover: Arc<Prover>,
+    user_repo: UserRepo,
+}
+
+impl OperationService {
+    pub fn new(prover: Arc<Prover>, user_repo: UserRepo) -> Self {
+        Self { prover, user_repo }
+    }
+
+    /// Records the intent, returns the id of the operation
+    pub fn begin(self: &Self, kind: OperationKind, user_id: &str, effects: &OperationEffects) -> Result<String> {
+        let now = get_current_time();
+        let operation = OperationEntity {
+            id: Uuid::new_v4().to_string(),
+            kind: kind.as_str().to_string(),
+            status: OperationStatus::Pending.as_str().to_string(),
+            user_id: user_id.to_string(),
+            effects: serde_json::to_string(effects)?,
+            transaction_hash: None,
+            transaction_nonce: None,
+            attempts: 0,
+            last_error: None,
+            next_attempt_at: Some(now + takeover_delay()),
+            created_at: now,
+            updated_at: now,
+        };
+        self.user_repo.insert_operation(&operation)?;
+        Ok(operation.id)
+    }
+
+    pub fn get(self: &Self, id: &str) -> Result<Option<OperationResponseDto>> {
+        self.user_repo.get_operation(id)?.map(to_dto).transpose()
+    }
+
+    /// Most recent operations in the status, or the ones needing attention
+    pub fn list(self: &Self, status: Option<OperationStatus>, limit: i64) -> Result<Vec<OperationResponseDto>> {
+        let statuses = match status {
+            Some(status) => vec![status.as_str()],
+            None => vec![
+                OperationStatus::Pending.as_str(),
+                OperationStatus::Submitted.as_str(),
+                OperationStatus::Failed.as_str(),
+            ],
+        };
+        self.user_repo.get_operations_by_status(&statuses, limit)?
+            .into_iter()
+            .map(to_dto)
+            .collect()
+    }
+
+    /// Failures to update an operation are logged only, the worker finds it either way
+    pub fn mark_submitted(self: &Self, id: &str, transaction_hash: &str, nonce: u64) {
+        let result = self.update(id, |operation| {
+            operation.status = OperationStatus::Submitted.as_str().to_string();
+            operation.transaction_hash = Some(transaction_hash.to_string());
+            operation.transaction_nonce = Some(nonce as i64);
+        });
+        if let Err(e) = result {
+            error!("Failed to mark operation {} as submitted: {}", id, e);
+        }
+    }
+
+    /// Applies the database writes after the Prism transaction was included.
+    /// A failed commit is left to the worker, which retries it with backoff.
+    pub fn commit(self: &Self, id: &str) {
+        let result = self.user_repo.get_operation(id)
+            .and_then(|operation| operation.ok_or_else(|| anyhow!("Operation {} not found", id)))
+            .and_then(|operation| self.commit_operation(operation).map(|_| ()));
+        if let Err(e) = result {
+            error!("Failed to commit operation {}, the worker will retry: {}", id, e);
+        }
+    }
+
+    /// Undoes the reservations of an operation whose Prism transaction certainly failed
+    pub fn abort(self: &Self, id: &str, reason: &str) {
+        let result = self.user_repo.get_operation(id)
+            .and_then(|operation| operation.ok_or_else(|| anyhow!("Operation {} not found", id)))
+            .and_then(|operation| self.compensate(operation, reason));
+        if let Err(e) = result {
+            error!("Failed to compensate operation {}: {}", id, e);
+        }
+    }
+
+    /// Makes a failed or waiting operation due for the worker right away
+    pub fn retry(self: &Self, id: &str) -> Result<Option<OperationResponseDto>> {
+        let Some(mut operation) = self.user_repo.get_operation(id)? else {
+            return Ok(None);
+        };
+        let status = parse_status(&operation)?;
+        if status == OperationStatus::Failed {
+            operation.status = match operation.transaction_hash {
+                Some(_) => OperationStatus::Submitted.as_str().to_string(),
+                None => OperationStatus::Pending.as_str().to_string(),
+            };
+            operation.attempts = 0;
+        }
+        if !status.is_finished() || status == OperationStatus::Failed {
+            operation.next_attempt_at = Some(get_current_time());
+            operation.updated_at = get_current_time();
+            self.user_repo.update_operation(&operation)?;
+        }
+        to_dto(operation).map(Some)
+    }
+
+    /// Compensates a failed operation, once an operator knows that
+    /// its transaction will not be included. Refused while the write is on the account.
+    pub async fn compensate_manually(self: &Self, id: &str) -> Result<Option<OperationResponseDto>, ApiError> {
+        let database_error = |e: anyhow::Error| ApiError::new(ErrorCode::DatabaseError, e.to_string());
+        let Some(operation) = self.user_repo.get_operation(id).map_err(database_error)? else {
+            return Ok(None);
+        };
+        let status = parse_status(&operation).map_err(database_error)?;
+        // pending and submitted operations still belong to their request or the worker
+        if status != OperationStatus::Failed {
+            return Err(ApiError::new(
+                ErrorCode::InvalidRequest,
+                format!("Operation {} is {}, only failed operations are compensated on request", id, status.as_str()),
+            ));
+        }
+        let effects: OperationEffects = serde_json::from_str(&operation.effects)
+            .map_err(|e| ApiError::new(ErrorCode::InternalError, e.to_string()))?;
+        let account = self.prover.get_account(&operation.user_id).await
+            .map_err(|e| ApiError::new(ErrorCode::InternalError, e.to_string()))?
+            .account;
+        if write_landed(&effects, account.as_ref()) {
+            return Err(ApiError::new(
+                ErrorCode::InvalidRequest,
+                format!("The Prism write of operation {} is on the account, retry it instead", id),
+            ));
+        }
+
+        warn!("Compensating operation {} on request", id);
+        let transaction_hash = operation.transaction_hash.clone();
+        let reason = "Compensated by an operator";
+        self.compensate(operation, reason).map_err(database_error)?;
+        self.finish_jobs(transaction_hash.as_deref(), Some(&ApiError::new(ErrorCode::TransactionFailed, reason)));
+        self.get(id).map_err(database_error)
+    }
+
+    /// Finishes the due operations until the process exits
+    pub fn start(self: Self) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.resolve_due().await {
+                    error!("Failed to process outbox operations: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn resolve_due(self: &Self) -> Result<()> {
+        for operation in self.user_repo.get_due_operations(get_current_time(), BATCH_SIZE)? {
+            let id = operation.id.clone();
+            if let Err(e) = self.resolve(operation).await {
+                error!("Failed to resolve operation {}: {}", id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Commits the operation when its Prism write is on the account, and compensates it
+    /// once the transaction can no longer be included. While neither is certain, e.g. the
+    /// transaction may still be waiting for a batch, it is looked at again with backoff
+    /// and marked failed after `MAX_ATTEMPTS`.
+    async fn resolve(self: &Self, operation: OperationEntity) -> Result<()> {
+        let effects: OperationEffects = serde_json::from_str(&operation.effects)?;
+        let account = match self.prover.get_account(&operation.user_id).await {
+            Ok(response) => response.account,
+            Err(e) => return self.schedule_retry(operation, &e.to_string()),
+        };
+        let transaction_hash = operation.transaction_hash.clone();
+        let write = prism_write(
+            write_landed(&effects, account.as_ref()),
+            account.as_ref().map(|account| account.nonce()),
+            operation.transaction_nonce,
+        );
+
+        match write {
+            PrismWrite::Landed => {
+                info!("Operation {} is on Prism, committing it", operation.id);
+                if self.commit_operation(operation)? {
+                    self.finish_jobs(transaction_hash.as_deref(), None);
+                }
+            }
+            PrismWrite::Dropped => {
+                warn!("Transaction of operation {} was dropped, compensating it", operation.id);
+                let reason = "The Prism transaction was dropped, the account nonce moved past it";
+                self.compensate(operation, reason)?;
+                self.finish_jobs(transaction_hash.as_deref(), Some(&ApiError::new(ErrorCode::TransactionFailed, reason)));
+            }
+            PrismWrite::Unknown => {
+                let error = match &transaction_hash {
+                    Some(transaction_hash) => format!("Transaction {} is not included yet", transaction_hash),
+                    // the request stopped around posting, the transaction may be on its way
+                    None => "No transaction was recorded as posted and the write is not on the account".to_string(),
+                };
+                self.schedule_retry(operation, &error)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Jobs whose request gave up waiting are left submitted until their operation is resolved
+    fn finish_jobs(self: &Self, transaction_hash: Option<&str>, error: Option<&ApiError>) {
+        let Some(transaction_hash) = transaction_hash else { return };
+        if let Err(e) = JobService::new(self.user_repo.clone()).finish_submitted(transaction_hash, error) {
+            error!("Failed to finish the jobs of transaction {}: {}", transaction_hash, e);
+        }
+    }
+
+    /// Returns whether the writes were committed, a failed attempt is scheduled for retry
+    fn commit_operation(self: &Self, mut operation: OperationEntity) -> Result<bool> {
+        if parse_status(&operation)? == OperationStatus::Committed {
+            return Ok(true);
+        }
+        let effects: OperationEffects = serde_json::from_str(&operation.effects)?;
+        if let Err(e) = self.apply(&operation.user_id, &effects) {
+            self.schedule_retry(operation, &e.to_string())?;
+            return Ok(false);
+        }
+        operation.status = OperationStatus::Committed.as_str().to_string();
+        operation.last_error = None;
+        operation.next_attempt_at = None;
+        operation.updated_at = get_current_time();
+        self.user_repo.update_operation(&operation)?;
+
+        // webhooks announce committed rows, not transactions that may never reach the database
+        let event = match &effects {
+            OperationEffects::CreateUser { user } => UserEvent::AccountCreated {
+                user_id: user.id.clone(),
+                transaction_hash: operation.transaction_hash.clone(),
+            },
+            OperationEffects::ApplyProof { proof, .. } => UserEvent::RecordAdded {
+                user_id: operation.user_id.clone(),
+                record_type: UserIdentityRecord::RECORD_TYPE.to_string(),
+                provider_id: proof.provider_id.clone(),
+                transaction_hash: operation.transaction_hash.clone(),
+            },
+            OperationEffects::RevokeProof { revocation, .. } => UserEvent::RecordRevoked {
+                user_id: operation.user_id.clone(),
+                provider_id: revocation.provider_id.clone(),
+                transaction_hash: operation.transaction_hash.clone(),
+            },
+            OperationEffects::TransferIdentity { identity, .. } => UserEvent::RecordAdded {
+                user_id: identity.user_id.clone(),
+                record_type: IdentitySupersedeRecord::RECORD_TYPE.to_string(),
+                provider_id: identity.provider_id.clone(),
+                transaction_hash: operation.transaction_hash.clone(),
+            },
+        };
+        WebhookService::new(self.user_repo.clone()).notify(&event);
+        Ok(true)
+    }
+
+    fn apply(self: &Self, user_id: &str, effects: &OperationEffects) -> Result<()> {
+        match effects {
+            OperationEffects::CreateUser { user } => {
+                if self.user_repo.get_user(&user.id)?.is_none() {
+                    self.user_repo.insert_user(user)?;
+                }
+            }
+            OperationEffects::ApplyProof { proof, identity, conflicts, reservation, .. } => {
+                if !self.user_repo.proof_exists_by_hash(&proof.raw_data_hash)? {
+                    self.user_repo.insert_proof(proof)?;
+                }
+                if let Some(identity) = identity {
+                    for conflict in conflicts {
+                        // a transferred handle stays listed under the old wallet until the
+                        // supersede record is written, without holding it
+                        let flagged = conflict.flagged || identity.flagged;
+                        let exclusive = conflict.exclusive && !identity.exclusive;
+                        self.user_repo.update_conflicting_identity(conflict, flagged, exclusive)?;
+                    }
+                    self.user_repo.upsert_identity(identity)?;
+                }
+                ProofReservationService::new(self.user_repo.clone()).confirm(reservation);
+            }
+            OperationEffects::RevokeProof { revocation, .. } => {
+                IdentityService::new(self.prover.clone(), self.user_repo.clone()).revoke(user_id, revocation)?;
+            }
+            OperationEffects::TransferIdentity { identity, .. } => {
+                self.user_repo.delete_identity(&identity.platform, &identity.normalized_handle, &identity.user_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn compensate(self: &Self, mut operation: OperationEntity, reason: &str) -> Result<()> {
+        let effects: OperationEffects = serde_json::from_str(&operation.effects)?;
+        if let OperationEffects::ApplyProof { reservation, .. } = &effects {
+            ProofReservationService::new(self.user_repo.clone()).release(reservation);
+        }
+        operation.status = OperationStatus::Compensated.as_str().to_string();
+        operation.last_error = Some(reason.to_string());
+        operation.next_attempt_at = None;
+        operation.updated_at = get_current_time();
+        self.user_repo.update_operation(&operation)?;
+        metrics::OPERATIONS_COMPENSATED_TOTAL.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn schedule_retry(self: &Self, mut operation: OperationEntity, error: &str) -> Result<()> {
+        operation.attempts += 1;
+        operation.last_error = Some(error.to_string());
+        operation.updated_at = get_current_time();
+        if operation.attempts >= MAX_ATTEMPTS {
+            error!("Giving up operation {} after {} attempts: {}", operation.id, operation.attempts, error);
+            operation.status = OperationStatus::Failed.as_str().to_string();
+            operation.next_attempt_at = None;
+            metrics::OPERATIONS_FAILED_TOTAL.fetch_add(1, Ordering::Relaxed);
+        } else {
+            operation.next_attempt_at = Some(operation.updated_at + retry_delay(operation.attempts));
+        }
+        self.user_repo.update_operation(&operation)?;
+        Ok(())
+    }
+
+    fn update<F: FnOnce(&mut OperationEntity)>(self: &Self, id: &str, change: F) -> Result<()> {
+        let mut operation = self.user_repo.get_operation(id)?
+            .ok_or_else(|| anyhow!("Operation {} not found", id))?;
+        change(&mut operation);
+        operation.updated_at = get_current_time();
+        self.user_repo.update_operation(&operation)?;
+        Ok(())
+    }
+}
+
+/// Whether the write of the operation is on the Prism account
+fn write_landed(effects: &OperationEffects, account: Option<&Account>) -> bool {
+    match (effects, account) {
+        (_, None) => false,
+        (OperationEffects::CreateUser { .. }, Some(_)) => true,
+        (
+            OperationEffects::ApplyProof { record_hash, .. }
+            | OperationEffects::RevokeProof { record_hash, .. }
+            | OperationEffects::TransferIdentity { record_hash, .. },
+            Some(account),
+        ) => account.signed_data().iter()
+            .any(|signed| hash_bytes_sha256(signed.data.clone()) == *record_hash),
+    }
+}
+
+/// What the worker knows about the Prism write of an operation
+#[derive(Debug, PartialEq, Eq)]
+enum PrismWrite {
+    Landed,
+    /// The transaction can not be included anymore
+    Dropped,
+    /// The transaction may still be included, or its nonce is not known
+    Unknown,
+}
+
+/// A transaction is only valid for the account nonce it was signed for, so once the
+/// account nonce moved past it without the write, the transaction was dropped or rejected.
+/// Accounts that do not exist yet have no nonce to tell.
+fn prism_write(landed: bool, account_nonce: Option<u64>, transaction_nonce: Option<i64>) -> PrismWrite {
+    match (account_nonce, transaction_nonce) {
+        _ if landed => PrismWrite::Landed,
+        (Some(account_nonce), Some(transaction_nonce)) if account_nonce as i64 > transaction_nonce => PrismWrite::Dropped,
+        _ => PrismWrite::Unknown,
+    }
+}
+
+/// Seconds after which the worker takes over an operation from its request
+fn takeover_delay() -> i64 {
+    transaction_timeout().as_secs() as i64 + TAKEOVER_GRACE_SECONDS
+}
+
+/// Seconds to wait after the given number of failed attempts
+fn retry_delay(attempts: i32) -> i64 {
+    let exponent = (attempts - 1).clamp(0, 20) as u32;
+    (RETRY_BASE_SECONDS * 2_i64.pow(exponent)).min(RETRY_MAX_SECONDS)
+}
+
+fn parse_status(operation: &OperationEntity) -> Result<OperationStatus> {
+    OperationStatus::parse(&operation.status).ok_or_else(|| anyhow!("Unknown operation status {}", operation.status))
+}
+
+fn to_dto(operation: OperationEntity) -> Result<OperationResponseDto> {
+    Ok(OperationResponseDto {
+        kind: OperationKind::parse(&operation.kind).ok_or_else(|| anyhow!("Unknown operation kind {}", operation.kind))?,
+        status: parse_status(&operation)?,
+        id: operation.id,
+        user_id: operation.user_id,
+        transaction_hash: operation.transaction_hash,
+        transaction_nonce: operation.transaction_nonce.map(|nonce| nonce as u64),
+        attempts: operation.attempts,
+        last_error: operation.last_error,
+        next_attempt_at: operation.next_attempt_at,
+        created_at: operation.created_at,
+        updated_at: operation.updated_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_back_off_up_to_an_hour() {
+        let delays: Vec<i64> = (1..=MAX_ATTEMPTS).map(retry_delay).collect();
+        assert_eq!(delays, vec![10, 20, 40, 80, 160, 320, 640, 1280, 2560, 3600]);
+        assert_eq!(retry_delay(0), RETRY_BASE_SECONDS);
+        assert_eq!(retry_delay(40), RETRY_MAX_SECONDS);
+    }
+
+    #[test]
+    fn operations_wait_for_the_request_to_time_out() {
+        assert!(takeover_delay() > transaction_timeout().as_secs() as i64);
+    }
+
+    #[test]
+    fn revocations_are_stored_with_their_kind() {
+        let effects = OperationEffects::RevokeProof {
+            record_hash: "hash".to_string(),
+            revocation: IdentityRevocationRecord::new("github".to_string(), None, 1),
+        };
+        let stored = serde_json::to_value(&effects).unwrap();
+        assert_eq!(stored["type"], "revoke_proof");
+
+        let OperationEffects::RevokeProof { record_hash, revocation } = serde_json::from_value(stored).unwrap() else {
+            panic!("effects of another kind");
+        };
+        assert_eq!(record_hash, "hash");
+        assert_eq!(revocation.provider_id, "github");
+    }
+
+    #[test]
+    fn writes_on_the_account_are_committed() {
+        assert_eq!(prism_write(true, Some(3), Some(2)), PrismWrite::Landed);
+        assert_eq!(prism_write(true, Some(1), None), PrismWrite::Landed);
+    }
+
+    #[test]
+    fn transactions_are_dropped_once_the_nonce_moved_past_them() {
+        assert_eq!(prism_write(false, Some(3), Some(2)), PrismWrite::Dropped);
+        assert_eq!(prism_write(false, Some(1), Some(0)), PrismWrite::Dropped);
+    }
+
+    #[test]
+    fn transactions_which_may_still_land_are_not_compensated() {
+        // the nonce is still open, the transaction may be waiting for a batch
+        assert_eq!(prism_write(false, Some(2), Some(2)), PrismWrite::Unknown);
+        // the account is not created yet
+        assert_eq!(prism_write(false, None, Some(0)), PrismWrite::Unknown);
+        // the request stopped before recording the transaction
+        assert_eq!(prism_write(false, Some(5), None), PrismWrite::Unknown);
+    }
+}
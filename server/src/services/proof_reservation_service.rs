@@ -0,0 +1,138 @@
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{errors::proof_errors::ProofError, models::attestation::Attestation},
+    entities::{user::ProofReservationEntity, user_repo::UserRepo},
+    utils::common::get_current_time,
+};
+
+/// Guarantees that a proof reaches Prism at most once. A proof is reserved by its raw
+/// hash and its identifier before the Prism write; the reservation is kept once the
+/// write was included and released when it failed.
+pub struct ProofReservationService {
+    user_repo: UserRepo,
+}
+
+/// Reserved proof, passed back to confirm or release it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofReservation {
+    pub raw_data_hash: String,
+    pub proof_identifier: Option<String>,
+}
+
+impl ProofReservation {
+    /// Keys of the attestation: the raw hash catches resubmissions of the same bytes, the
+    /// identifier the same claim signed again
+    fn of(attestation: &Attestation) -> Result<Self, ProofError> {
+        Ok(Self {
+            raw_data_hash: attestation.hash()?,
+            proof_identifier: attestation.identifier().ok(),
+        })
+    }
+}
+
+impl ProofReservationService {
+    pub fn new(user_repo: UserRepo) -> Self {
+        Self { user_repo }
+    }
+
+    /// Fails with `DuplicateProof` when the proof was applied before or is being applied
+    pub fn reserve(self: &Self, user_id: &str, provider_id: &str, attestation: &Attestation) -> Result<ProofReservation, ProofError> {
+        let ProofReservation { raw_data_hash, proof_identifier } = ProofReservation::of(attestation)?;
+
+        // proofs applied before reservations existed are only in these tables
+        if self.user_repo.proof_exists_by_hash(&raw_data_hash)? {
+            return Err(ProofError::DuplicateProof(format!("hash {}", raw_data_hash)));
+        }
+        if let Some(proof_identifier) = &proof_identifier {
+            if self.user_repo.identity_exists_by_proof_identifier(proof_identifier)? {
+                return Err(ProofError::DuplicateProof(format!("identifier {}", proof_identifier)));
+            }
+        }
+
+        let now = get_current_time();
+        let reservation = ProofReservationEntity {
+            raw_data_hash: raw_data_hash.clone(),
+            proof_identifier: proof_identifier.clone(),
+            user_id: user_id.to_string(),
+            provider_id: provider_id.to_string(),
+            status: "pending".to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+        if !self.user_repo.reserve_proof(&reservation)? {
+            return Err(ProofError::DuplicateProof(format!("hash {}", raw_data_hash)));
+        }
+        Ok(ProofReservation { raw_data_hash, proof_identifier })
+    }
+
+    /// Keeps the reservation for good, the proof is on the account.
+    /// Failures are logged only, the reservation blocks the proof either way.
+    pub fn confirm(self: &Self, reservation: &ProofReservation) {
+        if let Err(e) = self.user_repo.set_proof_reservation_status(&reservation.raw_data_hash, "applied", get_current_time()) {
+            error!("Failed to confirm reservation of proof {}: {}", reservation.raw_data_hash, e);
+        }
+    }
+
+    /// Frees the proof after a Prism write which certainly did not happen
+    pub fn release(self: &Self, reservation: &ProofReservation) {
+        if let Err(e) = self.user_repo.delete_proof_reservation(&reservation.raw_data_hash) {
+            error!("Failed to release reservation of proof {}: {}", reservation.raw_data_hash, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use crate::domain::models::attestation::SignedClaimAttestation;
+
+    fn attestation(signature: &str) -> Attestation {
+        let claim = serde_json::json!({
+            "provider": "github",
+            "parameters": r#"{"username":"alice"}"#,
+            "context": null,
+            "public_data": null,
+            "issued_at": 1_700_000_000,
+        });
+        Attestation::Signed(SignedClaimAttestation {
+            payload: STANDARD.encode(serde_json::to_vec(&claim).unwrap()),
+            public_key: "key".to_string(),
+            signature: signature.to_string(),
+        })
+    }
+
+    #[test]
+    fn resigned_claims_share_the_identifier() {
+        let first = ProofReservation::of(&attestation("first")).unwrap();
+        let again = ProofReservation::of(&attestation("first")).unwrap();
+        let resigned = ProofReservation::of(&attestation("second")).unwrap();
+
+        assert_eq!(first.raw_data_hash, again.raw_data_hash);
+        assert_ne!(first.raw_data_hash, resigned.raw_data_hash);
+        assert!(first.proof_identifier.is_some());
+        assert_eq!(first.proof_identifier, resigned.proof_identifier);
+    }
+
+    #[test]
+    fn unidentifiable_attestations_are_reserved_by_hash() {
+        let Attestation::Signed(mut signed) = attestation("first") else { unreachable!() };
+        signed.payload = "not base64!".to_string();
+        let reservation = ProofReservation::of(&Attestation::Signed(signed)).unwrap();
+
+        assert_eq!(reservation.raw_data_hash.len(), 64);
+        assert!(reservation.proof_identifier.is_none());
+    }
+
+    /// Reservations are stored in the outbox effects and read back by the worker
+    #[test]
+    fn reservations_round_trip_through_json() {
+        let reservation = ProofReservation::of(&attestation("first")).unwrap();
+        let decoded: ProofReservation = serde_json::from_str(&serde_json::to_string(&reservation).unwrap()).unwrap();
+
+        assert_eq!(decoded.raw_data_hash, reservation.raw_data_hash);
+        assert_eq!(decoded.proof_identifier, reservation.proof_identifier);
+    }
+}
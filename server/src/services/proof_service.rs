@@ -1,25 +1,30 @@
 use chrono::Utc;
 use reclaim_rust_sdk::Proof as ReclaimProof;
+use prism_keys::VerifyingKey;
+use serde_json::Value;
 use std::string::ToString;
+use std::env;
 use crate::domain::errors::proof_errors::ProofError;
+use crate::domain::models::attestation::{Attestation, AttestationKind, SignedClaimAttestation};
+use crate::domain::models::provider::Provider;
 use crate::domain::models::user::UserIdentityRecord;
+use crate::utils::keys::{decode_secp256k1_signature, decode_secp256k1_verifying_key};
 
 #[async_trait::async_trait]
 pub trait ProofValidator {
-    async fn validate(&self, proof: &ReclaimProof) -> Result<bool, ProofError>;
+    async fn validate(&self, attestation: &Attestation) -> Result<bool, ProofError>;
 }
 
 pub struct ProofService<V: ProofValidator> {
-    pub data: ReclaimProof,
+    pub data: Attestation,
     pub validator: V,
-    pub provider_id: String,
+    pub provider: Provider,
 }
 
 pub struct ReclaimProofValidator;
 
-#[async_trait::async_trait]
-impl ProofValidator for ReclaimProofValidator {
-    async fn validate(&self, proof: &ReclaimProof) -> Result<bool, ProofError> {
+impl ReclaimProofValidator {
+    async fn validate_reclaim_proof(&self, proof: &ReclaimProof) -> Result<bool, ProofError> {
         match reclaim_rust_sdk::verify_proof(proof).await {
             Ok(is_valid) => {
                 if is_valid {
@@ -35,6 +40,107 @@ impl ProofValidator for ReclaimProofValidator {
     }
 }
 
+#[async_trait::async_trait]
+impl ProofValidator for ReclaimProofValidator {
+    async fn validate(&self, attestation: &Attestation) -> Result<bool, ProofError> {
+        match attestation {
+            Attestation::Reclaim(proof) => self.validate_reclaim_proof(proof).await,
+            _ => Err(ProofError::UnsupportedAttestation(attestation.kind().to_string())),
+        }
+    }
+}
+
+/// Validates attestations signed by a secp256k1 key from a trusted key list.
+/// Used both for TLSNotary-style notary attestations and for server-side signed attestations.
+pub struct SignedAttestationValidator {
+    kind: AttestationKind,
+    trusted_keys: Vec<VerifyingKey>,
+}
+
+impl SignedAttestationValidator {
+    pub fn new(kind: AttestationKind, trusted_keys: Vec<VerifyingKey>) -> Self {
+        Self { kind, trusted_keys }
+    }
+
+    /// Reads trusted keys as comma separated base64 public keys from the environment variable
+    pub fn from_env(kind: AttestationKind, var_name: &str) -> Self {
+        let trusted_keys = env::var(var_name)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .filter_map(|key| match decode_secp256k1_verifying_key(key) {
+                Ok(vk) => Some(vk),
+                Err(e) => {
+                    log::warn!("Ignoring invalid trusted key in {}: {}", var_name, e);
+                    None
+                }
+            })
+            .collect();
+        Self::new(kind, trusted_keys)
+    }
+
+    fn validate_signed_claim(&self, attestation: &SignedClaimAttestation) -> Result<bool, ProofError> {
+        let vk = decode_secp256k1_verifying_key(&attestation.public_key)
+            .map_err(|e| ProofError::ProofNotVerifiedError(e.to_string()))?;
+        if !self.trusted_keys.contains(&vk) {
+            return Err(ProofError::UntrustedSigner(attestation.public_key.clone()));
+        }
+
+        let signature = decode_secp256k1_signature(&attestation.signature)
+            .map_err(|e| ProofError::ProofNotVerifiedError(e.to_string()))?;
+        vk.verify_signature(&attestation.payload_bytes()?, &signature)
+            .map_err(|e| ProofError::ProofNotVerifiedError(format!("Invalid attestation signature: {}", e)))?;
+
+        // make sure the signed payload is a claim we can work with
+        attestation.claim()?;
+        Ok(true)
+    }
+}
+
+#[async_trait::async_trait]
+impl ProofValidator for SignedAttestationValidator {
+    async fn validate(&self, attestation: &Attestation) -> Result<bool, ProofError> {
+        match attestation {
+            Attestation::TlsNotary(signed) if self.kind == AttestationKind::TlsNotary => {
+                self.validate_signed_claim(signed)
+            }
+            Attestation::Signed(signed) if self.kind == AttestationKind::Signed => {
+                self.validate_signed_claim(signed)
+            }
+            _ => Err(ProofError::UnsupportedAttestation(attestation.kind().to_string())),
+        }
+    }
+}
+
+/// Dispatches validation to the validator of the attestation kind
+pub struct AttestationValidator {
+    reclaim: ReclaimProofValidator,
+    tls_notary: SignedAttestationValidator,
+    signed: SignedAttestationValidator,
+}
+
+impl AttestationValidator {
+    pub fn from_env() -> Self {
+        Self {
+            reclaim: ReclaimProofValidator,
+            tls_notary: SignedAttestationValidator::from_env(AttestationKind::TlsNotary, "TLSN_NOTARY_KEYS"),
+            signed: SignedAttestationValidator::from_env(AttestationKind::Signed, "ATTESTATION_ISSUER_KEYS"),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ProofValidator for AttestationValidator {
+    async fn validate(&self, attestation: &Attestation) -> Result<bool, ProofError> {
+        match attestation.kind() {
+            AttestationKind::Reclaim => self.reclaim.validate(attestation).await,
+            AttestationKind::TlsNotary => self.tls_notary.validate(attestation).await,
+            AttestationKind::Signed => self.signed.validate(attestation).await,
+        }
+    }
+}
+
 impl<V: ProofValidator> ProofService<V> {
     async fn validate(&self) -> Result<bool, ProofError> {
         self.validator.validate(&self.data).await
@@ -44,13 +150,115 @@ impl<V: ProofValidator> ProofService<V> {
         self.validate().await?;
 
         let identity_record: UserIdentityRecord = UserIdentityRecord::new(
-            self.data.clone(), 
-            Utc::now().timestamp(), 
-            self.provider_id.clone()
-        );
-        let data = serde_json::to_vec(&identity_record).unwrap();
+            &self.data,
+            Utc::now().timestamp(),
+            &self.provider
+        )?;
+        let data = serde_json::to_vec(&identity_record)?;
 
         Ok(data)
     }
+
+    /// Validates the attestation and checks that the signed data is the record
+    /// `validate_and_get_data_to_sign` builds for it, apart from its creation time
+    pub async fn validate_signed_record(&self, data: &[u8]) -> Result<UserIdentityRecord, ProofError> {
+        self.validate().await?;
+
+        let signed: Value = serde_json::from_slice(data)
+            .map_err(|e| ProofError::RecordMismatch(e.to_string()))?;
+        let created_at = signed.get("created_at")
+            .and_then(Value::as_i64)
+            .ok_or_else(|| ProofError::RecordMismatch("created_at is missing".to_string()))?;
+        let expected = UserIdentityRecord::new(&self.data, created_at, &self.provider)?;
+        if serde_json::to_value(&expected)? != signed {
+            return Err(ProofError::RecordMismatch(format!("record of proof {} differs", expected.proof_identifier())));
+        }
+        Ok(expected)
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+    use super::*;
+    use crate::domain::models::attestation::AttestedClaim;
+    use crate::domain::models::provider::{ClaimExtractionRules, UniquenessPolicy};
+
+    struct AcceptAll;
+
+    #[async_trait::async_trait]
+    impl ProofValidator for AcceptAll {
+        async fn validate(&self, _attestation: &Attestation) -> Result<bool, ProofError> {
+            Ok(true)
+        }
+    }
+
+    fn attestation(username: &str) -> Attestation {
+        let claim = AttestedClaim {
+            provider: "github".to_string(),
+            parameters: format!(r#"{{"username":"{}"}}"#, username),
+            context: None,
+            public_data: None,
+            issued_at: Utc::now().timestamp(),
+        };
+        Attestation::Signed(SignedClaimAttestation {
+            payload: STANDARD.encode(serde_json::to_vec(&claim).unwrap()),
+            public_key: String::new(),
+            signature: String::new(),
+        })
+    }
+
+    fn service(attestation: Attestation) -> ProofService<AcceptAll> {
+        ProofService {
+            data: attestation,
+            validator: AcceptAll,
+            provider: Provider {
+                id: "github".to_string(),
+                name: "GitHub".to_string(),
+                platform: "github".to_string(),
+                category: "developer".to_string(),
+                icon_url: None,
+                enabled: true,
+                claim_extraction: ClaimExtractionRules {
+                    handle: Some("parameters.username".to_string()),
+                    ..Default::default()
+                },
+                max_proof_age: None,
+                uniqueness_policy: UniquenessPolicy::Reject,
+                attested_providers: Vec::new(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn prepared_record_is_accepted() {
+        let service = service(attestation("alice"));
+        let data = service.validate_and_get_data_to_sign().await.unwrap();
+
+        let record = service.validate_signed_record(&data).await.unwrap();
+
+        assert_eq!(record.identity().and_then(|identity| identity.handle.as_deref()), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn record_of_another_attestation_is_rejected() {
+        let data = service(attestation("mallory")).validate_and_get_data_to_sign().await.unwrap();
+
+        let result = service(attestation("alice")).validate_signed_record(&data).await;
+
+        assert!(matches!(result, Err(ProofError::RecordMismatch(_))));
+    }
+
+    #[tokio::test]
+    async fn edited_record_is_rejected() {
+        let service = service(attestation("alice"));
+        let data = service.validate_and_get_data_to_sign().await.unwrap();
+        let mut record: Value = serde_json::from_slice(&data).unwrap();
+        record["identity"]["follower_count"] = Value::from(1_000_000);
+
+        let result = service.validate_signed_record(&serde_json::to_vec(&record).unwrap()).await;
+
+        assert!(matches!(result, Err(ProofError::RecordMismatch(_))));
+    }
+}
@@ -0,0 +1,411 @@
+use std::{collections::BTreeSet, sync::Arc};
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use prism_client::{Operation, PrismApi};
+use prism_da::DataAvailabilityLayer;
+use prism_prover::Prover;
+
+use crate::{
+    api::dto::response::reindex_res::{ReindexDifferenceDto, ReindexResponseDto},
+    domain::models::{
+        account_state::{AccountState, RecordStatus},
+        attestation::Attestation,
+        reindex::ReindexDifferenceKind,
+        user::{AccountEntry, AccountRecord, RecordSigner, UserIdentityRecord},
+    },
+    entities::{
+        user::{ProofEntity, UserEntity},
+        user_repo::UserRepo,
+    },
+    services::user_service::UserService,
+    utils::common::get_current_time,
+    SERVICE_ID,
+};
+
+/// Rebuilds the `users` and `proofs` tables from the Prism accounts, which are the
+/// source of truth. Prism can not list the accounts of a service, so the accounts are
+/// the ones created for the service in the DA history together with every account id
+/// the database references; the first finds accounts missing in the database, the
+/// second rows of accounts missing on Prism. Accounts with unresolved outbox operations
+/// are skipped, their rows are written by the operation.
+pub struct ReindexService {
+    prover: Arc<Prover>,
+    user_repo: UserRepo,
+    da_layer: Arc<dyn DataAvailabilityLayer>,
+}
+
+/// Identity record of an account as a `proofs` row is expected to look
+struct ExpectedProof {
+    record: UserIdentityRecord,
+    /// sha256 of the signed account entry, the key of rows rebuilt from it
+    data_hash: String,
+    revoked_at: Option<i64>,
+}
+
+impl ReindexService {
+    pub fn new(prover: Arc<Prover>, user_repo: UserRepo, da_layer: Arc<dyn DataAvailabilityLayer>) -> Self {
+        Self { prover, user_repo, da_layer }
+    }
+
+    /// Compares every account with its rows and fixes the rows unless `dry_run` is set
+    pub async fn run(self: &Self, dry_run: bool) -> Result<ReindexResponseDto> {
+        let known_ids = self.user_repo.get_known_account_ids()?;
+        let created_ids = self.created_account_ids().await?;
+        let (account_ids, unknown_to_database) = accounts_to_walk(known_ids, created_ids);
+        info!(
+            "Reindexing {} accounts, {} of them unknown to the database{}",
+            account_ids.len(), unknown_to_database, if dry_run { " (dry run)" } else { "" },
+        );
+
+        let mut report = ReindexResponseDto {
+            dry_run,
+            accounts_checked: account_ids.len(),
+            accounts_unknown_to_database: unknown_to_database,
+            accounts_found: 0,
+            differences: Vec::new(),
+            errors: 0,
+        };
+        for user_id in account_ids {
+            if self.user_repo.has_unresolved_operations(&user_id)? {
+                report.differences.push(Difference::new(ReindexDifferenceKind::OperationInProgress, &user_id).dto);
+                continue;
+            }
+            let differences = match self.reindex_account(&user_id).await {
+                Ok(Some(differences)) => {
+                    report.accounts_found += 1;
+                    differences
+                }
+                Ok(None) => vec![Difference::new(ReindexDifferenceKind::MissingAccount, &user_id)],
+                Err(e) => vec![Difference::new(ReindexDifferenceKind::UnreadableAccount, &user_id).detail(e.to_string())],
+            };
+            for difference in differences {
+                let mut dto = difference.dto;
+                if !dry_run {
+                    if let Some(fix) = difference.fix {
+                        if let Err(e) = self.apply(fix) {
+                            warn!("Failed to fix {:?} of account {}: {}", dto.kind, dto.user_id, e);
+                            dto.detail = Some(e.to_string());
+                            report.errors += 1;
+                        }
+                    }
+                }
+                report.differences.push(dto);
+            }
+        }
+        info!("Reindex found {} differences, {} could not be fixed", report.differences.len(), report.errors);
+        Ok(report)
+    }
+
+    /// Ids of the accounts created for the service, read from every block of the DA layer
+    async fn created_account_ids(self: &Self) -> Result<Vec<String>> {
+        let latest = self.da_layer.get_latest_height().await?;
+        let mut ids = Vec::new();
+        for height in 1..=latest {
+            let transactions = self.da_layer.get_transactions(height).await
+                .map_err(|e| anyhow!("Failed to read DA block {}: {}", height, e))?;
+            ids.extend(transactions.into_iter().filter_map(|tx| match tx.operation {
+                Operation::CreateAccount { id, service_id, .. } if service_id == SERVICE_ID => Some(id),
+                _ => None,
+            }));
+        }
+        Ok(ids)
+    }
+
+    /// Differences of one account, `None` when it is not on Prism
+    async fn reindex_account(self: &Self, user_id: &str) -> Result<Option<Vec<Difference>>> {
+        if self.prover.get_account(user_id).await?.account.is_none() {
+            return Ok(None);
+        }
+        let entries = UserService::new(self.prover.clone(), user_id.to_string()).get_account_entries().await?;
+        let mut differences = Vec::new();
+
+        if self.user_repo.get_user(user_id)?.is_none() {
+            let public_key = wallet_public_key(&entries);
+            let mut difference = Difference::new(ReindexDifferenceKind::MissingUser, user_id);
+            if public_key.is_none() {
+                difference = difference.detail("No record signed by the wallet, the public key is left empty".to_string());
+            }
+            let user = UserEntity {
+                id: user_id.to_string(),
+                public_key: public_key.unwrap_or_default(),
+                created_at: get_current_time(),
+            };
+            differences.push(difference.fix(Fix::InsertUser(user)));
+        }
+
+        let mut expected = expected_proofs(entries);
+        let mut unreadable_providers = Vec::new();
+        for row in self.user_repo.get_proofs_by_user(user_id)? {
+            let position = expected.iter().position(|proof| row_matches(&row, proof));
+            let Some(proof) = position.map(|position| expected.swap_remove(position)) else {
+                let difference = unmatched_row(&row);
+                if difference.dto.kind == ReindexDifferenceKind::UnreadableProof {
+                    unreadable_providers.push(row.provider_id.clone());
+                }
+                differences.push(difference);
+                continue;
+            };
+            let kind = match (row.revoked_at, proof.revoked_at) {
+                (None, Some(_)) => ReindexDifferenceKind::MissingRevocation,
+                (Some(_), None) => ReindexDifferenceKind::UnexpectedRevocation,
+                _ => continue,
+            };
+            differences.push(
+                Difference::for_row(kind, &row)
+                    .fix(Fix::SetRevokedAt(row.raw_data_hash.clone(), proof.revoked_at)),
+            );
+        }
+        // records left over have no row, unless it is one of the rows which can not be read
+        for proof in expected {
+            let row = rebuilt_proof(user_id, proof);
+            let difference = Difference::for_row(ReindexDifferenceKind::MissingProof, &row);
+            differences.push(match unreadable_providers.contains(&row.provider_id) {
+                true => difference.detail("The account has an unreadable row of this provider, nothing is inserted".to_string()),
+                false => difference.fix(Fix::InsertProof(row)),
+            });
+        }
+        Ok(Some(differences))
+    }
+
+    fn apply(self: &Self, fix: Fix) -> Result<()> {
+        match fix {
+            Fix::InsertUser(user) => self.user_repo.insert_user(&user)?,
+            Fix::InsertProof(proof) => self.user_repo.insert_proof(&proof)?,
+            Fix::DeleteProof(raw_data_hash) => self.user_repo.delete_proof(&raw_data_hash)?,
+            Fix::SetRevokedAt(raw_data_hash, revoked_at) => self.user_repo.set_proof_revoked_at(&raw_data_hash, revoked_at)?,
+        };
+        Ok(())
+    }
+}
+
+enum Fix {
+    InsertUser(UserEntity),
+    InsertProof(ProofEntity),
+    DeleteProof(String),
+    SetRevokedAt(String, Option<i64>),
+}
+
+/// A reported difference with the change which removes it
+struct Difference {
+    dto: ReindexDifferenceDto,
+    fix: Option<Fix>,
+}
+
+impl Difference {
+    fn new(kind: ReindexDifferenceKind, user_id: &str) -> Self {
+        Self {
+            dto: ReindexDifferenceDto {
+                kind,
+                user_id: user_id.to_string(),
+                provider_id: None,
+                raw_data_hash: None,
+                detail: None,
+            },
+            fix: None,
+        }
+    }
+
+    fn for_row(kind: ReindexDifferenceKind, row: &ProofEntity) -> Self {
+        let mut difference = Self::new(kind, &row.user_id);
+        difference.dto.provider_id = Some(row.provider_id.clone());
+        difference.dto.raw_data_hash = Some(row.raw_data_hash.clone());
+        difference
+    }
+
+    fn detail(mut self, detail: String) -> Self {
+        self.dto.detail = Some(detail);
+        self
+    }
+
+    fn fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+/// Accounts to compare, sorted and without duplicates, with the number of created accounts
+/// the database does not reference
+fn accounts_to_walk(known_ids: Vec<String>, created_ids: Vec<String>) -> (Vec<String>, usize) {
+    let known: BTreeSet<String> = known_ids.into_iter().collect();
+    let created: BTreeSet<String> = created_ids.into_iter().collect();
+    let unknown_to_database = created.difference(&known).count();
+    (known.union(&created).cloned().collect(), unknown_to_database)
+}
+
+/// Identity records of the trusted entries with the time they were revoked, if they were.
+/// Replaced and superseded records keep their rows, as when they are written.
+fn expected_proofs(entries: Vec<AccountEntry>) -> Vec<ExpectedProof> {
+    let (records, data_hashes): (Vec<AccountRecord>, Vec<String>) = entries.into_iter()
+        .filter(|entry| entry.is_trusted())
+        .filter_map(|entry| entry.record.ok().map(|record| (record, entry.data_hash)))
+        .unzip();
+    let state = AccountState::new(records);
+    let statuses = state.statuses();
+
+    let revocations: Vec<_> = state.records().iter()
+        .filter_map(|record| match record {
+            AccountRecord::Revocation(revocation) => Some(revocation),
+            _ => None,
+        })
+        .collect();
+    state.records().iter()
+        .zip(data_hashes)
+        .zip(statuses)
+        .filter_map(|((record, data_hash), status)| match record {
+            AccountRecord::Identity(identity) => {
+                let revoked_at = match status {
+                    Some(RecordStatus::Revoked) => revocations.iter()
+                        .filter(|revocation| revocation.revokes(identity))
+                        .map(|revocation| revocation.revoked_at)
+                        .min(),
+                    _ => None,
+                };
+                Some(ExpectedProof { record: identity.clone(), data_hash, revoked_at })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Rows holding the proof are matched by its identifier, rebuilt rows by their record
+fn row_matches(row: &ProofEntity, proof: &ExpectedProof) -> bool {
+    if row.provider_id != proof.record.provider_id() {
+        return false;
+    }
+    match row.raw_data.as_deref().map(Attestation::from_bytes) {
+        Some(Ok(attestation)) => attestation.identifier().ok().as_deref() == Some(proof.record.proof_identifier()),
+        _ => row.raw_data_hash == proof.data_hash,
+    }
+}
+
+/// Difference of a row matching no record. Rows whose raw proof can not be decoded, e.g.
+/// ones written in a format this build does not read, are never deleted.
+fn unmatched_row(row: &ProofEntity) -> Difference {
+    let identifier = row.raw_data.as_deref()
+        .map(|raw_data| Attestation::from_bytes(raw_data).and_then(|attestation| attestation.identifier()));
+    match identifier {
+        Some(Err(e)) => Difference::for_row(ReindexDifferenceKind::UnreadableProof, row)
+            .detail(format!("The raw proof could not be decoded, the row is kept: {}", e)),
+        _ => Difference::for_row(ReindexDifferenceKind::UnknownProof, row)
+            .fix(Fix::DeleteProof(row.raw_data_hash.clone())),
+    }
+}
+
+/// Row of a record without one. The attestation itself is not on the account, so the row
+/// has no raw proof and is keyed by the hash of the account entry instead.
+fn rebuilt_proof(user_id: &str, proof: ExpectedProof) -> ProofEntity {
+    let identity = proof.record.identity();
+    ProofEntity {
+        provider_id: proof.record.provider_id().to_string(),
+        user_id: user_id.to_string(),
+        raw_data: None,
+        raw_data_hash: proof.data_hash,
+        created_at: proof.record.created_at(),
+        platform: identity.map(|identity| identity.platform.clone()),
+        handle: identity.and_then(|identity| identity.handle.clone()),
+        social_user_id: identity.and_then(|identity| identity.user_id.clone()),
+        profile_url: identity.and_then(|identity| identity.profile_url.clone()),
+        follower_count: identity.and_then(|identity| identity.follower_count).map(|count| count as i64),
+        record_version: proof.record.version() as i32,
+        revoked_at: proof.revoked_at,
+    }
+}
+
+/// Key of the first record signed by the wallet, the one it logged in with is not on the account
+fn wallet_public_key(entries: &[AccountEntry]) -> Option<String> {
+    entries.iter()
+        .find(|entry| entry.signer == RecordSigner::Wallet && entry.signature_valid)
+        .map(|entry| entry.signer_key.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use crate::domain::models::attestation::SignedClaimAttestation;
+
+    fn attestation() -> Attestation {
+        let claim = serde_json::json!({
+            "provider": "github",
+            "parameters": r#"{"username":"alice"}"#,
+            "context": null,
+            "public_data": null,
+            "issued_at": 1_700_000_000,
+        });
+        Attestation::Signed(SignedClaimAttestation {
+            payload: STANDARD.encode(serde_json::to_vec(&claim).unwrap()),
+            public_key: "key".to_string(),
+            signature: "signature".to_string(),
+        })
+    }
+
+    fn row(raw_data: Option<Vec<u8>>, raw_data_hash: &str) -> ProofEntity {
+        ProofEntity {
+            provider_id: "github".to_string(),
+            user_id: "cosmos1alice".to_string(),
+            raw_data,
+            raw_data_hash: raw_data_hash.to_string(),
+            created_at: 0,
+            platform: None,
+            handle: None,
+            social_user_id: None,
+            profile_url: None,
+            follower_count: None,
+            record_version: 1,
+            revoked_at: None,
+        }
+    }
+
+    fn expected(proof_identifier: &str, data_hash: &str) -> ExpectedProof {
+        let record = serde_json::from_value(serde_json::json!({
+            "version": 1,
+            "proof_identifier": proof_identifier,
+            "public_data": null,
+            "provider_id": "github",
+            "claim_data_params": "",
+            "created_at": 0,
+        })).unwrap();
+        ExpectedProof { record, data_hash: data_hash.to_string(), revoked_at: None }
+    }
+
+    #[test]
+    fn accounts_of_prism_and_the_database_are_both_walked() {
+        let known = vec!["b".to_string(), "a".to_string(), "b".to_string()];
+        let created = vec!["c".to_string(), "a".to_string()];
+
+        let (account_ids, unknown_to_database) = accounts_to_walk(known, created);
+
+        assert_eq!(account_ids, vec!["a", "b", "c"]);
+        assert_eq!(unknown_to_database, 1);
+    }
+
+    #[test]
+    fn rows_match_by_proof_identifier_or_entry_hash() {
+        let attestation = attestation();
+        let identifier = attestation.identifier().unwrap();
+        let stored = row(Some(attestation.to_bytes().unwrap()), "proof-hash");
+
+        assert!(row_matches(&stored, &expected(&identifier, "entry-hash")));
+        assert!(!row_matches(&stored, &expected("other", "proof-hash")));
+        assert!(row_matches(&row(None, "entry-hash"), &expected(&identifier, "entry-hash")));
+        assert!(!row_matches(&row(None, "entry-hash"), &expected(&identifier, "other")));
+    }
+
+    #[test]
+    fn unknown_rows_are_deleted() {
+        let attestation = attestation();
+        for stored in [row(Some(attestation.to_bytes().unwrap()), "proof-hash"), row(None, "entry-hash")] {
+            let difference = unmatched_row(&stored);
+            assert_eq!(difference.dto.kind, ReindexDifferenceKind::UnknownProof);
+            assert!(matches!(difference.fix, Some(Fix::DeleteProof(hash)) if hash == stored.raw_data_hash));
+        }
+    }
+
+    #[test]
+    fn unreadable_rows_are_kept() {
+        let difference = unmatched_row(&row(Some(vec![0xff, 0x00, 0x13]), "legacy-hash"));
+        assert_eq!(difference.dto.kind, ReindexDifferenceKind::UnreadableProof);
+        assert!(difference.fix.is_none());
+        assert!(difference.dto.detail.is_some());
+    }
+}
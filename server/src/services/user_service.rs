@@ -1,106 +1,350 @@
-use prism_keys::CryptoAlgorithm;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use prism_keys::{CryptoAlgorithm, VerifyingKey};
 use prism_prover::Prover;
+use std::env;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 use keystore_rs::{FileStore, KeyStore as _};
-use log::debug;
+use log::{debug, warn};
+use std::sync::atomic::Ordering;
 
 use prism_client::{
-    Account, PendingTransaction, PrismApi, SignatureBundle, SigningKey
+    Account, PendingTransaction, PrismApi, SignatureBundle, SigningKey, Transaction
 };
 use crate::{domain::{
     errors::user_errors::UserError, 
-    models::user::{User, UserIdentityRecord, UserRecord},
-}, utils::arbitrary_message::from_arbitrary_message_bytes_to_data_structure, KEYSTORE_PATH};
+    models::{
+        account_state::AccountState,
+        event::UserEvent,
+        user::{
+            AccountEntry, AccountRecord, RecordSigner, UnrecognizedRecord, User, UserReadOptions, UserRecord,
+            VerifiedIdentityRecord,
+        },
+    },
+}, utils::{
+    arbitrary_message::{from_arbitrary_message_bytes_to_data_structure, to_arbitrary_message_bytes},
+    common::hash_bytes_sha256,
+    events,
+    keys::key_matches_address,
+    metrics,
+}, services::{job_service::JobService, operation_service::OperationService}, KEYSTORE_PATH};
 use crate::SERVICE_ID;
+use weave_verifier::{account::decode_account, jmt, AccountProof, SignedRecord};
 
 pub struct UserService {
     prover: Arc<Prover>,
     user_id: String,
+    job: Option<(JobService, String)>,
+    operation: Option<(OperationService, String)>,
+}
+
+/// Default of `PRISM_TX_TIMEOUT_SECONDS`
+const DEFAULT_TX_TIMEOUT_SECONDS: u64 = 120;
+
+/// Account with its signed records and JMT proof, verifiable with `weave_verifier`
+pub struct AccountProofBundle {
+    pub account: Option<Account>,
+    pub records: Vec<SignedRecord>,
+    pub proof: AccountProof,
+}
+
+/// Account state after a transaction was included, with the hash of that transaction.
+/// There is no hash when no transaction was needed (e.g. the account existed already).
+pub struct ConfirmedTransaction {
+    pub account: Account,
+    pub transaction_hash: Option<String>,
+    /// Index of the signed data entry the transaction appended, if it appended one
+    pub record_index: Option<usize>,
 }
 
 impl UserService {
     pub fn new(prover: Arc<Prover>, user_id: String) -> Self {
-        Self { prover, user_id }
+        Self { prover, user_id, job: None, operation: None }
+    }
+
+    /// Reports the posted transactions of this service to the job
+    pub fn with_job(mut self, jobs: JobService, job_id: String) -> Self {
+        self.job = Some((jobs, job_id));
+        self
+    }
+
+    /// Reports the posted transactions of this service to the outbox operation
+    pub fn with_operation(mut self, operations: OperationService, operation_id: String) -> Self {
+        self.operation = Some((operations, operation_id));
+        self
     }
 
+    /// Checks that the wallet of the signer signed the record data
+    pub fn verify_user_record(self: &Self, user_record: &UserRecord) -> Result<(), UserError> {
+        verify_signed_record(user_record)
+    }
+
+    /// Returns the user with revoked and superseded identities omitted
     pub async fn get_user(self: &Self) -> Result<User, UserError> {
+        self.get_user_with_options(UserReadOptions::default()).await
+    }
+
+    /// Returns the current identities by default, or every identity record
+    /// (revoked and replaced ones included) in append order
+    pub async fn get_user_with_options(self: &Self, options: UserReadOptions) -> Result<User, UserError> {
+        let mut records: Vec<AccountRecord> = Vec::new();
+        let mut signers: Vec<(RecordSigner, bool)> = Vec::new();
+        let mut unrecognized_records: Vec<UnrecognizedRecord> = Vec::new();
+        for entry in self.get_account_entries().await? {
+            let trusted = entry.is_trusted();
+            match entry.record {
+                // records failing verification are left out of the fold and reported instead
+                Ok(record) if !trusted => unrecognized_records.push(UnrecognizedRecord {
+                    index: entry.index,
+                    data_hash: entry.data_hash,
+                    signer_key: entry.signer_key,
+                    error: if entry.signature_valid {
+                        format!("{} record signed by a {:?} key", record.record_type(), entry.signer)
+                    } else {
+                        "Invalid record signature".to_string()
+                    },
+                }),
+                Ok(record) => {
+                    records.push(record);
+                    signers.push((entry.signer, entry.signature_valid));
+                }
+                Err(unrecognized) => unrecognized_records.push(unrecognized),
+            }
+        }
+        if options.strict && !unrecognized_records.is_empty() {
+            return Err(UserError::UnrecognizedRecords(unrecognized_records.len()));
+        }
+
+        let state = AccountState::new(records);
+        let positions = if options.include_revoked {
+            state.identity_positions()
+        } else {
+            state.current_positions()
+        };
+        let identity_records = positions.into_iter()
+            .filter_map(|index| match &state.records()[index] {
+                AccountRecord::Identity(record) => Some(VerifiedIdentityRecord {
+                    record: record.clone(),
+                    signer: signers[index].0,
+                    signature_valid: signers[index].1,
+                }),
+                _ => None,
+            })
+            .collect();
+        Ok(User::new(self.user_id.clone(), identity_records, unrecognized_records))
+    }
+
+    /// Every signed data entry of the account in append order.
+    /// Entries which fail to decode are returned as unrecognized records instead of being dropped.
+    /// The signature of every entry is verified against its data, and its key is matched
+    /// against the service, custodial and wallet keys of the account.
+    pub async fn get_account_entries(self: &Self) -> Result<Vec<AccountEntry>, UserError> {
         let response = self.prover.get_account(&self.user_id).await
             .map_err(|_| UserError::AccountNotFound(self.user_id.clone()))?;
         
         if let Some(account) = response.account {
-            let mut identity_records: Vec<UserIdentityRecord> = Vec::new();
-            for signed in account.signed_data().iter() {
-                match from_arbitrary_message_bytes_to_data_structure::<UserIdentityRecord>(&signed.data.clone()) {
-                    Ok(identity_record) => {
-                        identity_records.push(identity_record);
-                    },
-                    Err(e) => debug!("Error parsing arbitrary message: {:?}", e),
+            let service_vk = self.service_verifying_key()?;
+            let custodial_vk = self.custodial_signing_key()?.verifying_key();
+
+            let mut entries: Vec<AccountEntry> = Vec::new();
+            for (index, signed) in account.signed_data().iter().enumerate() {
+                let verifying_key = &signed.signature.verifying_key;
+                let signer_key = STANDARD.encode(verifying_key.to_bytes());
+                let data_hash = hash_bytes_sha256(signed.data.clone());
+                let signature_valid = verifying_key
+                    .verify_signature(&signed.data, &signed.signature.signature)
+                    .is_ok();
+                let signer = if *verifying_key == service_vk {
+                    RecordSigner::Service
+                } else if *verifying_key == custodial_vk {
+                    RecordSigner::Custodial
+                } else if key_matches_address(verifying_key, &self.user_id) {
+                    RecordSigner::Wallet
+                } else {
+                    RecordSigner::Unknown
+                };
+                if !signature_valid || signer == RecordSigner::Unknown {
+                    warn!(
+                        "Unverified record {} of account {}: signer {:?}, valid signature {}",
+                        index, self.user_id, signer, signature_valid
+                    );
+                    metrics::UNVERIFIED_RECORDS_TOTAL.fetch_add(1, Ordering::Relaxed);
                 }
+
+                let record = match from_arbitrary_message_bytes_to_data_structure::<AccountRecord>(&signed.data.clone()) {
+                    Ok(record) => Ok(record),
+                    Err(e) => {
+                        warn!("Unrecognized record {} of account {}: {:?}", index, self.user_id, e);
+                        metrics::UNRECOGNIZED_RECORDS_TOTAL.fetch_add(1, Ordering::Relaxed);
+                        Err(UnrecognizedRecord {
+                            index,
+                            data_hash: data_hash.clone(),
+                            signer_key: signer_key.clone(),
+                            error: e.to_string(),
+                        })
+                    }
+                };
+                entries.push(AccountEntry { index, data_hash, signer_key, signer, signature_valid, record });
             }
-            return Ok(User::new(self.user_id.clone(), identity_records));
+            return Ok(entries);
         }
         Err(UserError::AccountNotFound(self.user_id.clone()))
     }
 
+    /// Account with its JMT proof against the latest commitment of the prover.
+    /// The commitment is read after the account, so a concurrent update can make
+    /// the proof stale; verifying clients should simply fetch it again.
+    pub async fn get_account_proof(self: &Self) -> Result<AccountProofBundle, UserError> {
+        let response = self.prover.get_account(&self.user_id).await?;
+        let commitment = self.prover.get_commitment().await?;
+
+        let account_bytes = match &response.account {
+            Some(account) => Some(STANDARD.encode(
+                bincode::serialize(account).map_err(|e| UserError::TransactionError(e.to_string()))?
+            )),
+            None => None,
+        };
+        // the prover only exposes the hash of the leaf, an account's own leaf is rebuilt from
+        // its bytes; the leaf of another account ending the path of a missing account can not
+        // be, so that proof is not served
+        let (leaf_key, leaf_value_hash) = match (&account_bytes, &response.proof.leaf) {
+            (Some(bytes), Some(leaf)) => {
+                let bytes = STANDARD.decode(bytes).map_err(|e| UserError::TransactionError(e.to_string()))?;
+                let key_hash = jmt::key_hash(&self.user_id);
+                let value_hash = jmt::value_hash(&bytes);
+                if hex::encode(jmt::leaf_hash(&key_hash, &value_hash)) != leaf.to_hex() {
+                    return Err(UserError::TransactionError(
+                        format!("Proof leaf of account {} does not match the account", self.user_id)
+                    ));
+                }
+                (Some(hex::encode(key_hash)), Some(hex::encode(value_hash)))
+            }
+            (None, None) => (None, None),
+            (Some(_), None) => return Err(UserError::TransactionError(
+                format!("Proof of account {} has no leaf", self.user_id)
+            )),
+            (None, Some(_)) => return Err(UserError::AccountNotFound(self.user_id.clone())),
+        };
+        let proof = AccountProof {
+            account_id: self.user_id.clone(),
+            account_bytes,
+            leaf_key,
+            leaf_value_hash,
+            siblings: response.proof.siblings.iter().map(|sibling| sibling.to_hex()).collect(),
+            root: commitment.to_hex(),
+        };
+        let records = match &response.account {
+            Some(account) => account.signed_data().iter()
+                .map(|signed| SignedRecord {
+                    data: STANDARD.encode(&signed.data),
+                    public_key: STANDARD.encode(signed.signature.verifying_key.to_bytes()),
+                    signature: STANDARD.encode(signed.signature.signature.to_bytes()),
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        // verifiers read the records from the committed bytes, make sure they read what was stored
+        if let Some(account_bytes) = proof.account_bytes.as_deref() {
+            let bytes = STANDARD.decode(account_bytes).map_err(|e| UserError::TransactionError(e.to_string()))?;
+            let committed = decode_account(&bytes).map_err(|e| UserError::TransactionError(e.to_string()))?;
+            let matches = committed.records.len() == records.len()
+                && committed.records.iter().zip(&records).all(|(committed, record)| committed.data == record.data);
+            if !matches {
+                return Err(UserError::TransactionError(
+                    format!("Committed bytes of account {} do not decode to its records", self.user_id)
+                ));
+            }
+        }
+        Ok(AccountProofBundle { account: response.account, records, proof })
+    }
+
     pub async fn add_data_to_user_account(
         self: &Self, 
         user_record: UserRecord
-    ) -> Result<Account, UserError> {
+    ) -> Result<ConfirmedTransaction, UserError> {
         if let Some(account) = self.prover.get_account(&user_record.user_id).await?.account {
             // verify user_record.signature_bundle to be sure that client have signed data
-            match user_record.signature_bundle.verifying_key.verify_signature(&user_record.user_data, &user_record.signature_bundle.signature) {
-                Ok(_) => {
-                    debug!("Add data to user account has valid signature");
-                },
-                Err(e) => return Err(UserError::InvalidSignature(format!("Invalid signature: {:?}", e))),
-            }
+            self.verify_user_record(&user_record)?;
+            debug!("Add data to user account has valid signature");
 
-            let user_sk = FileStore::new(KEYSTORE_PATH).unwrap()
-                .get_or_create_signing_key(&format!("{}/{}", self.user_id.clone(), SERVICE_ID))
-                .map_err(|e| UserError::KeyStoreError(e.to_string()))?;
-            let user_sk = SigningKey::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, &user_sk.to_bytes())?;
-            let user_vk = user_sk.verifying_key();
-            let signature = user_sk.sign(&user_record.user_data);
-            let signature_bundle = SignatureBundle::new(user_vk, signature);
+            // the wallet signature is stored with the data so that readers, including offline
+            // verifiers of the account proof, can check it without trusting the service keys;
+            // the transaction itself is signed by the custodial key
+            let user_sk = self.custodial_signing_key()?;
 
-            let updated_account = self.prover
-                .add_data(&account, user_record.user_data.clone(), signature_bundle, &user_sk)
-                .await?
-                .wait()
-                .await?;
+            let tx = self.prover
+                .build_request()
+                .to_modify_account(&account)
+                .add_data(user_record.user_data.clone(), user_record.signature_bundle.clone())?
+                .transaction()
+                .sign(&user_sk)
+                .map_err(|e| UserError::TransactionError(e.to_string()))?;
+            let confirmed = self.post_transaction(tx).await?.appending(&user_record.user_data);
+            self.publish_record_event(&user_record.user_data, confirmed.transaction_hash.clone());
 
-            println!("updated_account: {:?}", updated_account);
-
-            Ok(updated_account)
+            Ok(confirmed)
         } else {
             Err(UserError::AccountNotFound(self.user_id.clone()))
         }
     }
 
-    pub async fn create_user_account(self: &Self, user_record: UserRecord) -> Result<Account, UserError> {
+    /// Adds data produced by the service itself (not signed by the user's wallet),
+    /// wrapped into the same arbitrary message format as user signed records
+    pub async fn add_service_record_to_user_account(
+        self: &Self,
+        record_data: Vec<u8>,
+    ) -> Result<ConfirmedTransaction, UserError> {
+        let account = self.prover.get_account(&self.user_id).await?.account
+            .ok_or_else(|| UserError::AccountNotFound(self.user_id.clone()))?;
+
+        let data = service_record_data(&record_data);
+        let service_sk = self.service_signing_key()?;
+        let signature_bundle = SignatureBundle::new(service_sk.verifying_key(), service_sk.sign(&data));
+        let user_sk = self.custodial_signing_key()?;
+
+        let tx = self.prover
+            .build_request()
+            .to_modify_account(&account)
+            .add_data(data.clone(), signature_bundle)?
+            .transaction()
+            .sign(&user_sk)
+            .map_err(|e| UserError::TransactionError(e.to_string()))?;
+        let confirmed = self.post_transaction(tx).await?.appending(&data);
+        self.publish_record_event(&data, confirmed.transaction_hash.clone());
+
+        Ok(confirmed)
+    }
+
+    /// Creates the account, or returns the existing one. The wallet signature is
+    /// verified in both cases since it authenticates the login.
+    pub async fn create_user_account(self: &Self, user_record: UserRecord) -> Result<ConfirmedTransaction, UserError> {
+        // verify user_record.signature_bundle to be sure that client have signed data
+        self.verify_user_record(&user_record)?;
         if let Some(account) = self.prover.get_account(&self.user_id).await?.account {
             debug!("Account {} exists already", &self.user_id);
-            return Ok(account);
+            return Ok(ConfirmedTransaction { account, transaction_hash: None, record_index: None });
         }
-        // verify user_record.signature_bundle to be sure that client have signed data
-        user_record.signature_bundle.verifying_key.verify_signature(&user_record.user_data, &user_record.signature_bundle.signature)
-            .map_err(|_| UserError::InvalidSignature(format!("Invalid signature: {:?}", user_record.signature_bundle.signature)))?;
 
-        let service_sk = FileStore::new(KEYSTORE_PATH).unwrap()
-            .get_or_create_signing_key(SERVICE_ID)
-            .map_err(|e| UserError::KeyStoreError(e.to_string()))?;    
-        let service_sk = SigningKey::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, &service_sk.to_bytes())?;
+        let service_sk = self.service_signing_key()?;
+        let user_sk = self.custodial_signing_key()?;
 
-        let user_sk = FileStore::new(KEYSTORE_PATH).unwrap()
-            .get_or_create_signing_key(&format!("{}/{}", self.user_id.clone(), SERVICE_ID))
-            .map_err(|e| UserError::KeyStoreError(e.to_string()))?;
-        let user_sk = SigningKey::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, &user_sk.to_bytes())?;
-
-        let new_account = self.prover
-            .create_account(self.user_id.clone(), SERVICE_ID.to_string(), &service_sk, &user_sk)
-            .await?
-            .wait()
-            .await?;
+        let tx = self.prover
+            .build_request()
+            .create_account()
+            .with_id(self.user_id.clone())
+            .with_key(user_sk.verifying_key())
+            .for_service_with_id(SERVICE_ID.to_string())
+            .meeting_signed_challenge(&service_sk)
+            .map_err(|e| UserError::TransactionError(e.to_string()))?
+            .transaction()
+            .sign(&user_sk)
+            .map_err(|e| UserError::TransactionError(e.to_string()))?;
+        let confirmed = self.post_transaction(tx).await?;
+        events::publish(UserEvent::AccountCreated {
+            user_id: self.user_id.clone(),
+            transaction_hash: confirmed.transaction_hash.clone(),
+        });
 
         // let unsigned_tx = self.prover
         //     .build_request()
@@ -119,7 +363,177 @@ impl UserService {
         //     .await?;
 
 
-        Ok(new_account)     
+        Ok(confirmed)
+    }
+
+    /// Posts a signed transaction and waits until it is included.
+    /// The hash is the hex encoded sha256 of the bincode encoded signed transaction.
+    async fn post_transaction(self: &Self, tx: Transaction) -> Result<ConfirmedTransaction, UserError> {
+        let transaction_hash = transaction_hash(&tx)?;
+        let nonce = tx.nonce;
+
+        let pending = self.prover.post_transaction(tx).await?;
+        if let Some((jobs, job_id)) = &self.job {
+            jobs.mark_submitted(job_id, &self.user_id, &transaction_hash);
+        }
+        if let Some((operations, operation_id)) = &self.operation {
+            operations.mark_submitted(operation_id, &transaction_hash, nonce);
+        }
+        let account = wait_with_timeout(pending.wait()).await?;
+
+        Ok(ConfirmedTransaction { account, transaction_hash: Some(transaction_hash), record_index: None })
+    }
+
+    /// Publishes the event of a record included in the account.
+    /// Data which does not decode into a known record is not announced.
+    fn publish_record_event(self: &Self, data: &[u8], transaction_hash: Option<String>) {
+        let record = match from_arbitrary_message_bytes_to_data_structure::<AccountRecord>(data) {
+            Ok(record) => record,
+            Err(e) => {
+                warn!("Included record of account {} does not decode: {:?}", self.user_id, e);
+                return;
+            }
+        };
+        let event = match record {
+            AccountRecord::Revocation(revocation) => UserEvent::RecordRevoked {
+                user_id: self.user_id.clone(),
+                provider_id: revocation.provider_id,
+                transaction_hash,
+            },
+            record => UserEvent::RecordAdded {
+                user_id: self.user_id.clone(),
+                record_type: record.record_type().to_string(),
+                provider_id: record.provider_id().to_string(),
+                transaction_hash,
+            },
+        };
+        events::publish(event);
+    }
+
+    fn service_signing_key(self: &Self) -> Result<SigningKey, UserError> {
+        let service_sk = FileStore::new(KEYSTORE_PATH)
+            .map_err(|e| UserError::KeyStoreError(e.to_string()))?
+            .get_or_create_signing_key(SERVICE_ID)
+            .map_err(|e| UserError::KeyStoreError(e.to_string()))?;
+        Ok(SigningKey::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, &service_sk.to_bytes())?)
+    }
+
+    fn service_verifying_key(self: &Self) -> Result<VerifyingKey, UserError> {
+        Ok(self.service_signing_key()?.verifying_key())
+    }
+
+    /// Key the service keeps on behalf of the user to sign account transactions
+    fn custodial_signing_key(self: &Self) -> Result<SigningKey, UserError> {
+        let user_sk = FileStore::new(KEYSTORE_PATH)
+            .map_err(|e| UserError::KeyStoreError(e.to_string()))?
+            .get_or_create_signing_key(&format!("{}/{}", self.user_id.clone(), SERVICE_ID))
+            .map_err(|e| UserError::KeyStoreError(e.to_string()))?;
+        Ok(SigningKey::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, &user_sk.to_bytes())?)
     }
     
 }
+
+impl ConfirmedTransaction {
+    /// Notes the index of `data`, the entry the transaction added to the account
+    fn appending(mut self, data: &[u8]) -> Self {
+        self.record_index = self.account.signed_data().iter().rposition(|signed| signed.data == data);
+        self
+    }
+}
+
+/// Hex encoded sha256 of the bincode encoded signed transaction, also used to find
+/// the transaction on the DA layer
+/// Checks that the key of the record belongs to its signer and signed the record data.
+/// A valid signature of another wallet is rejected, so it can not write to this account.
+pub fn verify_signed_record(user_record: &UserRecord) -> Result<(), UserError> {
+    if !key_matches_address(&user_record.signature_bundle.verifying_key, &user_record.user_id) {
+        return Err(UserError::InvalidSignature(
+            format!("Public key does not belong to signer {}", user_record.user_id)
+        ));
+    }
+    user_record.signature_bundle.verifying_key
+        .verify_signature(&user_record.user_data, &user_record.signature_bundle.signature)
+        .map_err(|e| UserError::InvalidSignature(format!("Invalid signature: {:?}", e)))
+}
+
+/// Bytes added to the account for a record of the service, see `add_service_record_to_user_account`
+pub fn service_record_data(record_data: &[u8]) -> Vec<u8> {
+    to_arbitrary_message_bytes(SERVICE_ID, &STANDARD.encode(record_data))
+}
+
+pub fn transaction_hash(tx: &Transaction) -> Result<String, UserError> {
+    let tx_bytes = bincode::serialize(tx)
+        .map_err(|e| UserError::TransactionError(e.to_string()))?;
+    Ok(hash_bytes_sha256(tx_bytes))
+}
+
+/// Timeout of `PendingTransaction::wait`, from `PRISM_TX_TIMEOUT_SECONDS`
+pub fn transaction_timeout() -> Duration {
+    let seconds = env::var("PRISM_TX_TIMEOUT_SECONDS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_TX_TIMEOUT_SECONDS);
+    Duration::from_secs(seconds)
+}
+
+/// Waits for a posted transaction to be included. A timed out transaction
+/// stays posted and may still be included later.
+async fn wait_with_timeout<F, E>(wait: F) -> Result<Account, UserError>
+where
+    F: Future<Output = Result<Account, E>>,
+    UserError: From<E>,
+{
+    let timeout = transaction_timeout();
+    match tokio::time::timeout(timeout, wait).await {
+        Ok(result) => Ok(result?),
+        Err(_) => Err(UserError::TransactionTimeout(timeout.as_secs())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::{signature::Signer, Signature as EcdsaSignature, SigningKey as EcdsaSigningKey};
+    use prism_keys::Signature;
+    use weave_client::{LocalSigner, WeaveSigner};
+
+    use super::*;
+    use crate::domain::errors::api_error::ErrorCode;
+
+    fn key(seed: u8) -> EcdsaSigningKey {
+        EcdsaSigningKey::from_slice(&[seed; 32]).unwrap()
+    }
+
+    fn address(key: &EcdsaSigningKey) -> String {
+        LocalSigner::new(key.clone(), "cosmos").unwrap().address()
+    }
+
+    fn signed_record(key: &EcdsaSigningKey, signer: String) -> UserRecord {
+        let data = b"record".to_vec();
+        let signature: EcdsaSignature = key.sign(&data);
+        let signature_bundle = SignatureBundle::new(
+            VerifyingKey::from_algorithm_and_bytes(
+                CryptoAlgorithm::Secp256k1,
+                key.verifying_key().to_encoded_point(true).as_bytes(),
+            ).unwrap(),
+            Signature::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, &signature.to_bytes()).unwrap(),
+        );
+        UserRecord::new(signature_bundle, data, signer)
+    }
+
+    #[test]
+    fn records_signed_by_the_signer_are_accepted() {
+        let key = key(1);
+        assert!(verify_signed_record(&signed_record(&key, address(&key))).is_ok());
+    }
+
+    #[test]
+    fn valid_signatures_of_another_key_are_rejected() {
+        let signer = key(1);
+        let other = key(2);
+
+        let error = verify_signed_record(&signed_record(&other, address(&signer))).unwrap_err();
+
+        assert_eq!(error.code(), ErrorCode::InvalidSignature);
+        assert!(error.to_string().contains(&address(&signer)));
+    }
+}
@@ -0,0 +1,600 @@
+use std::{
+    collections::HashMap,
+    env,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+use anyhow::{anyhow, Result};
+use log::{debug, error, warn};
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect, Url,
+};
+use serde_json::{json, Value};
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::{
+    api::dto::{
+        request::webhook_req::CreateWebhookRequestDto,
+        response::webhook_res::{WebhookDeliveryDto, WebhookResponseDto},
+    },
+    domain::{
+        errors::webhook_errors::WebhookError,
+        models::{
+            event::UserEvent,
+            user::{IdentitySupersedeRecord, UserIdentityRecord},
+            webhook::{DeliveryStatus, WebhookEventType, WebhookPayload},
+        },
+    },
+    entities::{user::{WebhookDeliveryEntity, WebhookEntity}, user_repo::UserRepo},
+    utils::common::get_current_time,
+};
+use weave_core::webhook_signature::{self, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER};
+
+/// Attempts after which a delivery is marked failed
+const MAX_ATTEMPTS: i32 = 10;
+/// Delay before the first retry, doubled on every further retry
+const RETRY_BASE_SECONDS: i64 = 30;
+const RETRY_MAX_SECONDS: i64 = 6 * 60 * 60;
+/// How often the worker looks for due deliveries
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Deliveries attempted per poll
+const BATCH_SIZE: i64 = 50;
+/// Kept in the delivery log so a misbehaving receiver can not fill the database
+const MAX_LOGGED_RESPONSE_BYTES: usize = 1024;
+pub const DEFAULT_DELIVERIES_LIMIT: i64 = 50;
+
+/// Webhooks of integrators and their persistent delivery queue
+#[derive(Clone)]
+pub struct WebhookService {
+    user_repo: UserRepo,
+    client: reqwest::Client,
+    /// `WEBHOOK_ALLOW_PRIVATE_URLS`, lets webhooks reach private networks for local development
+    allow_private_urls: bool,
+}
+
+impl WebhookService {
+    pub fn new(user_repo: UserRepo) -> Self {
+        let allow_private_urls = env::var("WEBHOOK_ALLOW_PRIVATE_URLS")
+            .map(|value| value == "true")
+            .unwrap_or(false);
+        Self { user_repo, client: delivery_client(allow_private_urls), allow_private_urls }
+    }
+
+    /// Registers a webhook. The secret is only returned here.
+    pub async fn create(self: &Self, integrator: &str, request: CreateWebhookRequestDto) -> Result<WebhookResponseDto, WebhookError> {
+        let url = Url::parse(&request.url).map_err(|e| WebhookError::InvalidUrl(e.to_string()))?;
+        check_url(&url, self.allow_private_urls).await?;
+        let events: Vec<WebhookEventType> = WebhookEventType::ALL.into_iter()
+            .filter(|event| request.events.is_empty() || request.events.contains(event))
+            .collect();
+
+        let webhook = WebhookEntity {
+            id: Uuid::new_v4().to_string(),
+            integrator: integrator.to_string(),
+            url: url.to_string(),
+            // two v4 uuids carry 244 random bits from the OS generator
+            secret: format!("whsec_{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()),
+            events: events.iter().map(|event| event.as_str()).collect::<Vec<_>>().join(","),
+            created_at: get_current_time(),
+        };
+        self.user_repo.insert_webhook(&webhook)?;
+
+        let mut response = to_webhook_dto(&webhook);
+        response.secret = Some(webhook.secret);
+        Ok(response)
+    }
+
+    pub fn list(self: &Self, integrator: &str) -> Result<Vec<WebhookResponseDto>, WebhookError> {
+        Ok(self.user_repo.get_webhooks_by_integrator(integrator)?.iter().map(to_webhook_dto).collect())
+    }
+
+    pub fn delete(self: &Self, integrator: &str, webhook_id: &str) -> Result<(), WebhookError> {
+        self.get_owned(integrator, webhook_id)?;
+        self.user_repo.delete_webhook(webhook_id)?;
+        Ok(())
+    }
+
+    /// Delivery log of the webhook, most recent first
+    pub fn deliveries(self: &Self, integrator: &str, webhook_id: &str, limit: i64) -> Result<Vec<WebhookDeliveryDto>, WebhookError> {
+        self.get_owned(integrator, webhook_id)?;
+        self.user_repo.get_webhook_deliveries(webhook_id, limit)?
+            .into_iter()
+            .map(|delivery| to_delivery_dto(delivery).map_err(WebhookError::from))
+            .collect()
+    }
+
+    /// Sends a delivery again right away, whatever its status. A failed delivery
+    /// gets a fresh set of retries.
+    pub async fn redeliver(self: &Self, integrator: &str, webhook_id: &str, delivery_id: &str) -> Result<WebhookDeliveryDto, WebhookError> {
+        let webhook = self.get_owned(integrator, webhook_id)?;
+        let mut delivery = self.user_repo.get_webhook_delivery(delivery_id)?
+            .filter(|delivery| delivery.webhook_id == webhook.id)
+            .ok_or_else(|| WebhookError::DeliveryNotFound(delivery_id.to_string()))?;
+        if delivery.status == DeliveryStatus::Failed.as_str() {
+            delivery.attempts = 0;
+        }
+        let delivery = self.attempt(&webhook, delivery).await?;
+        Ok(to_delivery_dto(delivery)?)
+    }
+
+    /// Queues a delivery for every webhook subscribed to the event
+    pub fn enqueue(self: &Self, event: &UserEvent) -> Result<usize> {
+        let Some((event_type, data)) = webhook_event(event) else {
+            return Ok(0);
+        };
+        let now = get_current_time();
+        let mut deliveries = Vec::new();
+        for webhook in self.user_repo.get_all_webhooks()? {
+            if !parse_events(&webhook.events).contains(&event_type) {
+                continue;
+            }
+            let id = Uuid::new_v4().to_string();
+            let payload = WebhookPayload { id: id.clone(), event: event_type, created_at: now, data: data.clone() };
+            deliveries.push(WebhookDeliveryEntity {
+                id,
+                webhook_id: webhook.id,
+                event_type: event_type.as_str().to_string(),
+                payload: serde_json::to_string(&payload)?,
+                status: DeliveryStatus::Pending.as_str().to_string(),
+                attempts: 0,
+                next_attempt_at: Some(now),
+                last_status_code: None,
+                last_error: None,
+                created_at: now,
+                updated_at: now,
+            });
+        }
+        if deliveries.is_empty() {
+            return Ok(0);
+        }
+        self.user_repo.insert_webhook_deliveries(&deliveries)
+    }
+
+    /// Queues the deliveries of an event whose database rows were just committed.
+    /// Failures are logged only, they must not fail the commit itself.
+    pub fn notify(self: &Self, event: &UserEvent) {
+        if let Err(e) = self.enqueue(event) {
+            error!("Failed to queue webhook deliveries for {}: {}", event.name(), e);
+        }
+    }
+
+    /// Attempts the due deliveries until the process exits. The queue itself lives in
+    /// the database, so pending deliveries survive restarts.
+    pub fn start(self: Self) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.deliver_due().await {
+                    error!("Failed to process webhook deliveries: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Webhooks are served concurrently, the deliveries of one webhook in order
+    async fn deliver_due(self: &Self) -> Result<()> {
+        let mut by_webhook: HashMap<String, Vec<WebhookDeliveryEntity>> = HashMap::new();
+        for delivery in self.user_repo.get_due_webhook_deliveries(get_current_time(), BATCH_SIZE)? {
+            by_webhook.entry(delivery.webhook_id.clone()).or_default().push(delivery);
+        }
+
+        let mut tasks = JoinSet::new();
+        for (webhook_id, deliveries) in by_webhook {
+            let Some(webhook) = self.user_repo.get_webhook(&webhook_id)? else {
+                continue;
+            };
+            let service = self.clone();
+            tasks.spawn(async move {
+                for delivery in deliveries {
+                    let delivery_id = delivery.id.clone();
+                    if let Err(e) = service.attempt(&webhook, delivery).await {
+                        error!("Failed to attempt delivery {} to webhook {}: {}", delivery_id, webhook.id, e);
+                    }
+                }
+            });
+        }
+        while tasks.join_next().await.is_some() {}
+        Ok(())
+    }
+
+    /// Posts the delivery once and stores the outcome, scheduling a retry
+    /// with exponential backoff when it failed
+    async fn attempt(self: &Self, webhook: &WebhookEntity, mut delivery: WebhookDeliveryEntity) -> Result<WebhookDeliveryEntity> {
+        // the address may have changed since the webhook was registered
+        let outcome = match Url::parse(&webhook.url) {
+            Ok(url) => match check_url(&url, self.allow_private_urls).await {
+                Ok(()) => send(&self.client, webhook, &delivery, get_current_time()).await,
+                Err(e) => AttemptOutcome { status_code: None, error: Some(e.to_string()) },
+            },
+            Err(e) => AttemptOutcome { status_code: None, error: Some(e.to_string()) },
+        };
+        record_attempt(&mut delivery, outcome, get_current_time());
+
+        match DeliveryStatus::parse(&delivery.status) {
+            Some(DeliveryStatus::Delivered) => debug!("Delivered {} to webhook {}", delivery.id, webhook.id),
+            Some(DeliveryStatus::Failed) => warn!(
+                "Giving up delivery {} to webhook {} after {} attempts", delivery.id, webhook.id, delivery.attempts
+            ),
+            _ => {}
+        }
+        self.user_repo.update_webhook_delivery(&delivery)?;
+        Ok(delivery)
+    }
+
+    /// Webhooks of other integrators are reported as not found
+    fn get_owned(self: &Self, integrator: &str, webhook_id: &str) -> Result<WebhookEntity, WebhookError> {
+        self.user_repo.get_webhook(webhook_id)?
+            .filter(|webhook| webhook.integrator == integrator)
+            .ok_or_else(|| WebhookError::NotFound(webhook_id.to_string()))
+    }
+}
+
+/// Result of posting a delivery once, delivered when there is no error
+struct AttemptOutcome {
+    status_code: Option<i32>,
+    error: Option<String>,
+}
+
+/// Signs and posts the delivery payload
+async fn send(client: &reqwest::Client, webhook: &WebhookEntity, delivery: &WebhookDeliveryEntity, now: i64) -> AttemptOutcome {
+    let signature = webhook_signature::sign(&webhook.secret, now, delivery.payload.as_bytes());
+    let response = client
+        .post(&webhook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, signature)
+        .header(EVENT_HEADER, &delivery.event_type)
+        .header(DELIVERY_HEADER, &delivery.id)
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    match response {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                return AttemptOutcome { status_code: Some(status.as_u16() as i32), error: None };
+            }
+            let mut body = response.text().await.unwrap_or_default();
+            let mut end = body.len().min(MAX_LOGGED_RESPONSE_BYTES);
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+            body.truncate(end);
+            AttemptOutcome {
+                status_code: Some(status.as_u16() as i32),
+                error: Some(format!("Receiver answered {}: {}", status, body)),
+            }
+        }
+        Err(e) => AttemptOutcome { status_code: None, error: Some(e.to_string()) },
+    }
+}
+
+/// Stores the outcome of an attempt on the delivery and schedules the next one
+fn record_attempt(delivery: &mut WebhookDeliveryEntity, outcome: AttemptOutcome, now: i64) {
+    delivery.attempts += 1;
+    delivery.updated_at = now;
+    delivery.last_status_code = outcome.status_code;
+    let delivered = outcome.error.is_none();
+    delivery.last_error = outcome.error;
+
+    if delivered {
+        delivery.status = DeliveryStatus::Delivered.as_str().to_string();
+        delivery.next_attempt_at = None;
+    } else if delivery.attempts >= MAX_ATTEMPTS {
+        delivery.status = DeliveryStatus::Failed.as_str().to_string();
+        delivery.next_attempt_at = None;
+    } else {
+        delivery.status = DeliveryStatus::Pending.as_str().to_string();
+        delivery.next_attempt_at = Some(now + retry_delay(delivery.attempts));
+    }
+}
+
+/// Client for deliveries: redirects are not followed, and unless private URLs are allowed
+/// host names only resolve to public addresses, so a name rebound after the check still
+/// can not reach internal services
+fn delivery_client(allow_private_urls: bool) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .redirect(redirect::Policy::none());
+    if !allow_private_urls {
+        builder = builder.dns_resolver(Arc::new(PublicResolver));
+    }
+    builder.build().unwrap_or_default()
+}
+
+/// Rejects URLs which are not http(s) or whose host is, or resolves to, an address
+/// which is not public: loopback, private, link-local (cloud metadata), and the like
+async fn check_url(url: &Url, allow_private_urls: bool) -> Result<(), WebhookError> {
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(WebhookError::InvalidUrl(format!("Unsupported scheme {}", url.scheme())));
+    }
+    let host = url.host_str().ok_or_else(|| WebhookError::InvalidUrl("URL has no host".to_string()))?;
+    if allow_private_urls {
+        return Ok(());
+    }
+
+    let addresses: Vec<IpAddr> = match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(ip) => vec![ip],
+        Err(_) => tokio::net::lookup_host((host, url.port_or_known_default().unwrap_or(443)))
+            .await
+            .map_err(|e| WebhookError::InvalidUrl(format!("Failed to resolve {}: {}", host, e)))?
+            .map(|address| address.ip())
+            .collect(),
+    };
+    if addresses.is_empty() {
+        return Err(WebhookError::InvalidUrl(format!("{} does not resolve", host)));
+    }
+    match addresses.into_iter().find(|ip| !is_public(*ip)) {
+        Some(ip) => Err(WebhookError::InvalidUrl(format!("{} is not a public address", ip))),
+        None => Ok(()),
+    }
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                || a >= 240
+                // carrier-grade NAT, IETF protocol assignments, benchmarking
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 192 && b == 0 && c == 0)
+                || (a == 198 && (b == 18 || b == 19)))
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            // IPv4-mapped and NAT64 addresses reach the embedded IPv4 address
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(ip));
+            }
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                let [.., a, b, c, d] = ip.octets();
+                return is_public(IpAddr::V4([a, b, c, d].into()));
+            }
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // unique local, link-local, documentation
+                || (segments[0] & 0xfe00) == 0xfc00
+                || (segments[0] & 0xffc0) == 0xfe80
+                || (segments[0] == 0x2001 && segments[1] == 0x0db8))
+        }
+    }
+}
+
+/// Resolver of the delivery client which drops addresses that are not public
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| is_public(address.ip()))
+                .collect();
+            if addresses.is_empty() {
+                return Err(format!("{} does not resolve to a public address", name.as_str()).into());
+            }
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+/// Seconds to wait after the given number of failed attempts
+fn retry_delay(attempts: i32) -> i64 {
+    let exponent = (attempts - 1).clamp(0, 20) as u32;
+    (RETRY_BASE_SECONDS * 2_i64.pow(exponent)).min(RETRY_MAX_SECONDS)
+}
+
+/// Webhook event type and data of an account event, `None` for events
+/// integrators can not subscribe to
+fn webhook_event(event: &UserEvent) -> Option<(WebhookEventType, Value)> {
+    match event {
+        UserEvent::AccountCreated { user_id, transaction_hash } => Some((
+            WebhookEventType::AccountCreated,
+            json!({ "user_id": user_id, "transaction_hash": transaction_hash }),
+        )),
+        UserEvent::RecordAdded { user_id, record_type, provider_id, transaction_hash } if record_type == UserIdentityRecord::RECORD_TYPE => Some((
+            WebhookEventType::IdentityLinked,
+            json!({ "user_id": user_id, "provider_id": provider_id, "transaction_hash": transaction_hash }),
+        )),
+        // the identity moved to another wallet, for this user it is gone just like a revocation
+        UserEvent::RecordAdded { user_id, record_type, provider_id, transaction_hash } if record_type == IdentitySupersedeRecord::RECORD_TYPE => Some((
+            WebhookEventType::IdentityRevoked,
+            json!({ "user_id": user_id, "provider_id": provider_id, "reason": "superseded", "transaction_hash": transaction_hash }),
+        )),
+        UserEvent::RecordRevoked { user_id, provider_id, transaction_hash } => Some((
+            WebhookEventType::IdentityRevoked,
+            json!({ "user_id": user_id, "provider_id": provider_id, "reason": "revoked", "transaction_hash": transaction_hash }),
+        )),
+        _ => None,
+    }
+}
+
+fn parse_events(events: &str) -> Vec<WebhookEventType> {
+    events.split(',').filter_map(WebhookEventType::parse).collect()
+}
+
+fn to_webhook_dto(webhook: &WebhookEntity) -> WebhookResponseDto {
+    WebhookResponseDto {
+        id: webhook.id.clone(),
+        url: webhook.url.clone(),
+        events: parse_events(&webhook.events),
+        created_at: webhook.created_at,
+        secret: None,
+    }
+}
+
+fn to_delivery_dto(delivery: WebhookDeliveryEntity) -> Result<WebhookDeliveryDto> {
+    Ok(WebhookDeliveryDto {
+        payload: serde_json::from_str(&delivery.payload)?,
+        status: DeliveryStatus::parse(&delivery.status)
+            .ok_or_else(|| anyhow!("Unknown delivery status {}", delivery.status))?,
+        id: delivery.id,
+        webhook_id: delivery.webhook_id,
+        attempts: delivery.attempts,
+        next_attempt_at: delivery.next_attempt_at,
+        last_status_code: delivery.last_status_code,
+        last_error: delivery.last_error,
+        created_at: delivery.created_at,
+        updated_at: delivery.updated_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    const SECRET: &str = "whsec_test";
+
+    /// Answers every request with the status and sends the raw request to the channel
+    async fn receiver(status: &'static str) -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (sender, requests) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0; 4096];
+                loop {
+                    let read = stream.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head.lines()
+                            .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length: ").map(str::to_string))
+                            .and_then(|length| length.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if body.len() >= length || read == 0 {
+                            break;
+                        }
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 {}\r\nlocation: http://169.254.169.254/\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    status,
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+                sender.send(String::from_utf8_lossy(&request).to_string()).unwrap();
+            }
+        });
+        (format!("http://{}/hook", address), requests)
+    }
+
+    fn webhook(url: &str) -> WebhookEntity {
+        WebhookEntity {
+            id: "webhook".to_string(),
+            integrator: "acme".to_string(),
+            url: url.to_string(),
+            secret: SECRET.to_string(),
+            events: "identity.linked".to_string(),
+            created_at: 0,
+        }
+    }
+
+    fn delivery() -> WebhookDeliveryEntity {
+        WebhookDeliveryEntity {
+            id: "delivery".to_string(),
+            webhook_id: "webhook".to_string(),
+            event_type: "identity.linked".to_string(),
+            payload: r#"{"id":"delivery","event":"identity.linked"}"#.to_string(),
+            status: DeliveryStatus::Pending.as_str().to_string(),
+            attempts: 0,
+            next_attempt_at: Some(0),
+            last_status_code: None,
+            last_error: None,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn deliveries_are_signed_for_the_receiver() {
+        let (url, mut requests) = receiver("200 OK").await;
+        let now = get_current_time();
+        let outcome = send(&delivery_client(true), &webhook(&url), &delivery(), now).await;
+        assert_eq!(outcome.status_code, Some(200));
+        assert!(outcome.error.is_none());
+
+        let request = requests.recv().await.unwrap();
+        let (head, body) = request.split_once("\r\n\r\n").unwrap();
+        let signature = head.lines()
+            .find_map(|line| line.strip_prefix(&format!("{}: ", SIGNATURE_HEADER)))
+            .unwrap();
+        assert_eq!(body, delivery().payload);
+        assert!(webhook_signature::verify(SECRET, signature, body.as_bytes(), now, 60));
+        assert!(!webhook_signature::verify("whsec_other", signature, body.as_bytes(), now, 60));
+    }
+
+    #[tokio::test]
+    async fn redirects_are_not_followed() {
+        let (url, _requests) = receiver("302 Found").await;
+        let outcome = send(&delivery_client(true), &webhook(&url), &delivery(), get_current_time()).await;
+        assert_eq!(outcome.status_code, Some(302));
+        assert!(outcome.error.is_some());
+    }
+
+    #[test]
+    fn failed_attempts_back_off_until_the_delivery_fails() {
+        let mut delivery = delivery();
+        let mut delays = Vec::new();
+        for now in (0..).step_by(100_000).take(MAX_ATTEMPTS as usize) {
+            record_attempt(&mut delivery, AttemptOutcome { status_code: Some(500), error: Some("down".to_string()) }, now);
+            if let Some(next_attempt_at) = delivery.next_attempt_at {
+                delays.push(next_attempt_at - now);
+            }
+        }
+        assert_eq!(delays, vec![30, 60, 120, 240, 480, 960, 1920, 3840, 7680]);
+        assert_eq!(delivery.status, DeliveryStatus::Failed.as_str());
+        assert_eq!(delivery.attempts, MAX_ATTEMPTS);
+
+        let mut delivery = self::delivery();
+        record_attempt(&mut delivery, AttemptOutcome { status_code: Some(204), error: None }, 10);
+        assert_eq!(delivery.status, DeliveryStatus::Delivered.as_str());
+        assert_eq!(delivery.next_attempt_at, None);
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        assert_eq!(retry_delay(1), 30);
+        assert_eq!(retry_delay(20), RETRY_MAX_SECONDS);
+    }
+
+    #[tokio::test]
+    async fn urls_of_internal_addresses_are_rejected() {
+        for url in [
+            "http://127.0.0.1/hook",
+            "http://localhost:8080/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://10.0.0.5/hook",
+            "http://192.168.1.1/hook",
+            "http://100.64.0.1/hook",
+            "http://[::1]/hook",
+            "http://[fd00::1]/hook",
+            "http://[::ffff:127.0.0.1]/hook",
+            "ftp://example.com/hook",
+        ] {
+            let url = Url::parse(url).unwrap();
+            assert!(check_url(&url, false).await.is_err(), "{} was accepted", url);
+        }
+        assert!(check_url(&Url::parse("http://8.8.8.8/hook").unwrap(), false).await.is_ok());
+        assert!(check_url(&Url::parse("http://127.0.0.1/hook").unwrap(), true).await.is_ok());
+    }
+}
@@ -0,0 +1,23 @@
+use std::sync::OnceLock;
+use tokio::sync::broadcast;
+
+use crate::domain::models::event::UserEvent;
+
+/// Events kept for subscribers which fall behind, older ones are dropped
+/// and the subscriber is told to resync
+const CHANNEL_CAPACITY: usize = 1024;
+
+fn channel() -> &'static broadcast::Sender<UserEvent> {
+    static CHANNEL: OnceLock<broadcast::Sender<UserEvent>> = OnceLock::new();
+    CHANNEL.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+/// Sends the event to every open event stream, streams of other users filter it out
+pub fn publish(event: UserEvent) {
+    // an error only means that nobody is listening
+    let _ = channel().send(event);
+}
+
+pub fn subscribe() -> broadcast::Receiver<UserEvent> {
+    channel().subscribe()
+}
@@ -28,7 +28,7 @@ fn create_token_internal(signer: String, token_type: TokenType) -> Result<String
             TokenType::Access => ChronoDuration::minutes(ACCESS_TOKEN_LIFETIME_MINUTES),
             TokenType::Refresh => ChronoDuration::days(REFRESH_TOKEN_LIFETIME_DAYS),
         })
-        .expect("valid timestamp")
+        .ok_or_else(|| AuthError::TokenGenerationError("Token expiration is out of range".to_string()))?
         .timestamp();
 
     let claims = Claims {
@@ -0,0 +1,33 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use anyhow::{anyhow, Result};
+use prism_keys::{CryptoAlgorithm, Signature, VerifyingKey};
+use tendermint::PublicKey;
+use weave_core::signature;
+
+/// Decodes a base64 encoded raw secp256k1 public key (as sent by Cosmos wallets)
+pub fn decode_secp256k1_verifying_key(public_key: &str) -> Result<VerifyingKey> {
+    let pk = PublicKey::from_raw_secp256k1(STANDARD.decode(public_key)?.as_slice())
+        .ok_or_else(|| anyhow!("Invalid secp256k1 public key"))?;
+    let vk = pk.secp256k1()
+        .ok_or_else(|| anyhow!("Failed to get secp256k1 key"))?;
+
+    VerifyingKey::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, vk.to_bytes().as_slice())
+        .map_err(|e| anyhow!("Failed to create verifying key: {}", e))
+}
+
+/// Decodes a base64 encoded secp256k1 signature
+pub fn decode_secp256k1_signature(signature: &str) -> Result<Signature> {
+    let signature_bytes = STANDARD.decode(signature)?;
+
+    Signature::from_algorithm_and_bytes(CryptoAlgorithm::Secp256k1, &signature_bytes)
+        .map_err(|e| anyhow!("Failed to create signature: {}", e))
+}
+
+/// Checks that a secp256k1 key belongs to a bech32 Cosmos address,
+/// i.e. the address data is ripemd160(sha256(compressed public key))
+pub fn key_matches_address(vk: &VerifyingKey, address: &str) -> bool {
+    match k256::ecdsa::VerifyingKey::from_sec1_bytes(&vk.to_bytes()) {
+        Ok(key) => signature::key_matches_address(&key, address),
+        Err(_) => false,
+    }
+}
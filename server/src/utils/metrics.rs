@@ -0,0 +1,42 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Signed data entries which could not be decoded while reading accounts
+pub static UNRECOGNIZED_RECORDS_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+/// Account records with an invalid signature or signed by an unknown key
+pub static UNVERIFIED_RECORDS_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+/// Requests whose handler panicked
+pub static HANDLER_PANICS_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+/// Outbox operations undone because their Prism transaction was not included
+pub static OPERATIONS_COMPENSATED_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+/// Outbox operations whose database commit was given up
+pub static OPERATIONS_FAILED_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+/// Renders the counters in the Prometheus text exposition format
+pub fn render() -> String {
+    format!(
+        "# HELP weave_unrecognized_records_total Account records which could not be decoded\n\
+        # TYPE weave_unrecognized_records_total counter\n\
+        weave_unrecognized_records_total {}\n\
+        # HELP weave_unverified_records_total Account records with an invalid signature or an unknown signer\n\
+        # TYPE weave_unverified_records_total counter\n\
+        weave_unverified_records_total {}\n\
+        # HELP weave_handler_panics_total Requests whose handler panicked\n\
+        # TYPE weave_handler_panics_total counter\n\
+        weave_handler_panics_total {}\n\
+        # HELP weave_operations_compensated_total Outbox operations undone because their Prism transaction was not included\n\
+        # TYPE weave_operations_compensated_total counter\n\
+        weave_operations_compensated_total {}\n\
+        # HELP weave_operations_failed_total Outbox operations whose database commit was given up\n\
+        # TYPE weave_operations_failed_total counter\n\
+        weave_operations_failed_total {}\n",
+        UNRECOGNIZED_RECORDS_TOTAL.load(Ordering::Relaxed),
+        UNVERIFIED_RECORDS_TOTAL.load(Ordering::Relaxed),
+        HANDLER_PANICS_TOTAL.load(Ordering::Relaxed),
+        OPERATIONS_COMPENSATED_TOTAL.load(Ordering::Relaxed),
+        OPERATIONS_FAILED_TOTAL.load(Ordering::Relaxed)
+    )
+}
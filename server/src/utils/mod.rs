@@ -1,3 +1,6 @@
 pub mod jwt;
 pub mod common;
-pub mod arbitrary_message;
\ No newline at end of file
+pub mod keys;
+pub mod metrics;
+pub mod events;
+pub use weave_core::{amino as arbitrary_message, handle};
\ No newline at end of file